@@ -0,0 +1,70 @@
+use hot_validation_primitives::UserPayload;
+
+/// A bridge action in the user payloads doesn't authorize the message being signed.
+#[derive(thiserror::Error, Debug)]
+pub enum BridgeChallengeError {
+    #[error("Malformed bridge payload: {0:#}")]
+    Malformed(anyhow::Error),
+    #[error("Message {message} doesn't match the bridge challenge {expected}")]
+    Mismatch { message: String, expected: String },
+}
+
+/// Bridge actions are authorized for one specific message: the challenge built from the action.
+/// Checks that every bridge payload among `user_payloads` is bound to `message`,
+/// payloads of other auth methods are ignored.
+pub(crate) fn ensure_bridge_challenges(
+    message: &[u8],
+    user_payloads: &[UserPayload],
+) -> Result<(), BridgeChallengeError> {
+    for user_payload in user_payloads {
+        let Some(bridge) = user_payload.as_bridge() else {
+            continue;
+        };
+        let expected = bridge
+            .build_challenge()
+            .map_err(BridgeChallengeError::Malformed)?;
+        if message != expected {
+            return Err(BridgeChallengeError::Mismatch {
+                message: hex::encode(message),
+                expected: hex::encode(expected),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hot_validation_primitives::bridge::{
+        CompletedWithdrawal, DepositAction, DepositData, HotVerifyBridge,
+    };
+    use hot_validation_primitives::ChainId;
+
+    #[test]
+    fn bridge_payload_is_bound_to_message() {
+        let payloads: Vec<UserPayload> = vec![
+            r#"{"auth_method":0,"signatures":[]}"#.parse().unwrap(),
+            r#"{"ClearCompletedWithdrawal":{"chain_id":1100,"nonce":"42"}}"#
+                .parse()
+                .unwrap(),
+        ];
+        let challenge = CompletedWithdrawal::build_challenge_for_removal(42);
+        assert!(ensure_bridge_challenges(&challenge, &payloads).is_ok());
+
+        let other = CompletedWithdrawal::build_challenge_for_removal(43);
+        assert!(matches!(
+            ensure_bridge_challenges(&other, &payloads),
+            Err(BridgeChallengeError::Mismatch { .. })
+        ));
+
+        let payloads = vec![UserPayload::from(HotVerifyBridge::Deposit(DepositAction {
+            chain_id: ChainId::Near,
+            data: DepositData::from_nonce(42),
+        }))];
+        assert!(matches!(
+            ensure_bridge_challenges(&challenge, &payloads),
+            Err(BridgeChallengeError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+//! Heads of each RPC endpoint of a chain, kept fresh in the background.
+//!
+//! Verifiers read the heads from the tracker instead of fetching them on every verification,
+//! and [`ThresholdVerifier`](crate::threshold_verifier::ThresholdVerifier) skips endpoints
+//! that fell too far behind the others (see [`HeadTracker::is_lagging`]).
+use crate::metrics;
+use crate::threshold_verifier::Identifiable;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use hot_validation_primitives::{ChainFamily, ChainId, FinalityLevel};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, Instant};
+
+/// Heads older than this many poll intervals are ignored, verifiers fetch the head themselves then.
+const MAX_HEAD_AGE_INTERVALS: u32 = 5;
+
+/// Heads of an endpoint, in blocks (EVM, NEAR), slots (Solana), ledgers (Stellar)
+/// or masterchain seqnos (TON).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Heads {
+    pub latest: u64,
+    /// `safe` on EVM, `near-final` on NEAR, `confirmed` on Solana.
+    pub safe: Option<u64>,
+    /// `finalized` on EVM and Solana, `final` on NEAR, the latest one on chains with instant finality.
+    pub finalized: Option<u64>,
+}
+
+impl Heads {
+    /// Heads of an endpoint polled for its latest head only.
+    #[must_use]
+    pub const fn latest(latest: u64) -> Self {
+        Self {
+            latest,
+            safe: None,
+            finalized: None,
+        }
+    }
+
+    /// Sets the head selected by the finality level.
+    #[must_use]
+    pub const fn with(mut self, level: FinalityLevel, head: u64) -> Self {
+        match level {
+            FinalityLevel::Optimistic => self.latest = head,
+            FinalityLevel::Confirmed => self.safe = Some(head),
+            FinalityLevel::Final => self.finalized = Some(head),
+        }
+        self
+    }
+
+    /// The head selected by the finality level, if the endpoint reports it.
+    pub const fn at(&self, level: FinalityLevel) -> Option<u64> {
+        match level {
+            FinalityLevel::Optimistic => Some(self.latest),
+            FinalityLevel::Confirmed => self.safe,
+            FinalityLevel::Final => self.finalized,
+        }
+    }
+}
+
+/// An endpoint that can report its heads. Besides the latest head, which is needed to find
+/// lagging endpoints, only the heads the verifier reads are fetched.
+#[async_trait]
+pub trait HeadSource: Identifiable + Send + Sync {
+    async fn fetch_heads(&self) -> Result<Heads>;
+}
+
+struct TrackedHeads {
+    heads: Heads,
+    updated_at: Instant,
+}
+
+pub struct HeadTracker {
+    chain_id: ChainId,
+    /// How far behind the median an endpoint can be before it's excluded.
+    max_lag: u64,
+    poll_interval: Duration,
+    max_head_age: Duration,
+    heads: RwLock<HashMap<String, TrackedHeads>>,
+}
+
+impl HeadTracker {
+    pub fn new(chain_id: ChainId, poll_interval: Duration) -> Arc<Self> {
+        let max_lag = match chain_id.family() {
+            // ~1 minute worth of slots.
+            Some(ChainFamily::Solana) => 150,
+            Some(ChainFamily::Stellar) => 3,
+            Some(ChainFamily::Evm) => 20,
+            _ => 10,
+        };
+        Arc::new(Self {
+            chain_id,
+            max_lag,
+            poll_interval,
+            max_head_age: poll_interval * MAX_HEAD_AGE_INTERVALS,
+            heads: RwLock::default(),
+        })
+    }
+
+    /// Fresh heads of the endpoint `id`, `None` if they aren't tracked or are outdated.
+    pub fn heads(&self, id: &str) -> Option<Heads> {
+        let heads = self.heads.read().expect("Head tracker lock is poisoned");
+        heads
+            .get(id)
+            .filter(|tracked| tracked.updated_at.elapsed() <= self.max_head_age)
+            .map(|tracked| tracked.heads)
+    }
+
+    /// Median of the fresh latest heads of all endpoints.
+    pub fn median_latest(&self) -> Option<u64> {
+        self.median_at(FinalityLevel::Optimistic)
+    }
+
+    /// Median of the fresh heads selected by `level` of all endpoints that report it.
+    pub fn median_at(&self, level: FinalityLevel) -> Option<u64> {
+        let heads = self.heads.read().expect("Head tracker lock is poisoned");
+        let mut values: Vec<u64> = heads
+            .values()
+            .filter(|tracked| tracked.updated_at.elapsed() <= self.max_head_age)
+            .filter_map(|tracked| tracked.heads.at(level))
+            .collect();
+        values.sort_unstable();
+        values.get(values.len() / 2).copied()
+    }
+
+    /// Whether the endpoint `id` is more than `max_lag` behind the median.
+    /// Endpoints without fresh heads aren't considered lagging, the threshold call handles them.
+    pub fn is_lagging(&self, id: &str) -> bool {
+        let (Some(heads), Some(median)) = (self.heads(id), self.median_latest()) else {
+            return false;
+        };
+        median.saturating_sub(heads.latest) > self.max_lag
+    }
+
+    fn update(&self, id: String, heads: Result<Heads>) {
+        let mut tracked = self.heads.write().expect("Head tracker lock is poisoned");
+        match heads {
+            Ok(heads) => {
+                tracked.insert(
+                    id,
+                    TrackedHeads {
+                        heads,
+                        updated_at: Instant::now(),
+                    },
+                );
+            }
+            Err(err) => {
+                tracing::debug!("Couldn't fetch heads of {id} ({}): {err:#}", self.chain_id);
+                tracked.remove(&id);
+            }
+        }
+    }
+
+    fn report_lag(&self) {
+        let Some(median) = self.median_latest() else {
+            return;
+        };
+        let heads = self.heads.read().expect("Head tracker lock is poisoned");
+        for (id, tracked) in heads.iter() {
+            let lag = median.saturating_sub(tracked.heads.latest);
+            metrics::set_head_lag(self.chain_id, id, lag);
+            if lag > self.max_lag {
+                tracing::warn!(
+                    "{id} is {lag} behind the median head {median} of {}, excluding it",
+                    self.chain_id
+                );
+            }
+        }
+    }
+
+    /// Polls `sources` in the background until all of them are dropped.
+    /// Outside a Tokio runtime nothing is tracked, lagging endpoints aren't excluded
+    /// and the verifiers fetch the heads on every call.
+    pub fn spawn<T: HeadSource + 'static>(self: &Arc<Self>, sources: &[Arc<T>]) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(
+                "No Tokio runtime to track heads of {}, lagging endpoints won't be excluded",
+                self.chain_id
+            );
+            return;
+        };
+        let tracker = self.clone();
+        let sources: Vec<Weak<T>> = sources.iter().map(Arc::downgrade).collect();
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(tracker.poll_interval);
+            loop {
+                interval.tick().await;
+                let alive: Vec<Arc<T>> = sources.iter().filter_map(Weak::upgrade).collect();
+                if alive.is_empty() {
+                    break;
+                }
+                let heads = join_all(
+                    alive
+                        .iter()
+                        .map(|source| async { (source.id(), source.fetch_heads().await) }),
+                )
+                .await;
+                for (id, heads) in heads {
+                    tracker.update(id, heads);
+                }
+                tracker.report_lag();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HeadTracker, Heads};
+    use hot_validation_primitives::{ChainId, FinalityLevel};
+    use std::time::Duration;
+
+    fn heads(latest: u64) -> Heads {
+        Heads {
+            latest,
+            safe: Some(latest - 2),
+            finalized: Some(latest - 10),
+        }
+    }
+
+    #[test]
+    fn lagging_endpoints() {
+        let tracker = HeadTracker::new(ChainId::Evm(1), Duration::from_secs(2));
+        tracker.update("a".to_string(), Ok(heads(1000)));
+        tracker.update("b".to_string(), Ok(heads(1002)));
+        tracker.update("c".to_string(), Ok(heads(900)));
+        tracker.update("d".to_string(), Err(anyhow::anyhow!("timeout")));
+
+        assert_eq!(tracker.median_latest(), Some(1000));
+        assert_eq!(tracker.median_at(FinalityLevel::Final), Some(990));
+        assert!(!tracker.is_lagging("a"));
+        assert!(!tracker.is_lagging("b"));
+        assert!(tracker.is_lagging("c"));
+        assert!(!tracker.is_lagging("d"));
+        assert_eq!(tracker.heads("d"), None);
+        assert_eq!(
+            tracker
+                .heads("b")
+                .and_then(|heads| heads.at(FinalityLevel::Final)),
+            Some(992)
+        );
+    }
+
+    #[test]
+    fn heads_of_the_polled_levels() {
+        let heads = Heads::latest(100).with(FinalityLevel::Final, 90);
+        assert_eq!(heads.at(FinalityLevel::Optimistic), Some(100));
+        assert_eq!(heads.at(FinalityLevel::Confirmed), None);
+        assert_eq!(heads.at(FinalityLevel::Final), Some(90));
+    }
+}
@@ -122,15 +122,31 @@ pub async fn get_json<U>(
     url: &str,
     chain_id: ChainId, // for metrics
 ) -> std::result::Result<U, HttpError>
+where
+    U: DeserializeOwned,
+{
+    get_json_with_headers(client, url, &[], chain_id).await
+}
+
+/// Same as [`get_json`], with additional request headers.
+pub async fn get_json_with_headers<U>(
+    client: &Arc<Client>,
+    url: &str,
+    headers: &[(&'static str, String)],
+    chain_id: ChainId, // for metrics
+) -> std::result::Result<U, HttpError>
 where
     U: DeserializeOwned,
 {
     metrics::bump_metrics_rpc_call_total(chain_id, url);
 
-    let req = client
+    let mut req = client
         .get(url)
         .header(ACCEPT, "application/json")
         .timeout(TIMEOUT);
+    for (name, value) in headers {
+        req = req.header(*name, value);
+    }
 
     let resp = req.send().await.map_err(|e| {
         HttpError::request_failed_error(chain_id, url.to_string(), None, None, anyhow!(e))
@@ -2,12 +2,18 @@
 #![allow(clippy::missing_errors_doc)]
 mod verifiers;
 
+pub mod challenge;
+pub mod head_tracker;
 mod http_client;
 mod metrics;
+pub mod policy;
+pub mod revert;
 mod threshold_verifier;
 
 pub use hot_validation_primitives::*;
+pub use threshold_verifier::VerificationError;
 
+use crate::policy::AuthCallPolicy;
 use crate::threshold_verifier::ThresholdVerifier;
 use crate::verifiers::cosmos::CosmosVerifier;
 use crate::verifiers::evm::EvmVerifier;
@@ -17,16 +23,19 @@ use crate::verifiers::stellar::StellarVerifier;
 use crate::verifiers::ton::TonVerifier;
 use anyhow::{bail, ensure, Context, Result};
 use futures_util::future::try_join_all;
-use hot_validation_primitives::bridge::HotVerifyResult;
+use hot_validation_primitives::bridge::{CompletedWithdrawal, HotVerifyResult};
 use hot_validation_primitives::uid::WalletId;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::instrument;
 
 pub const HOT_VERIFY_METHOD_NAME: &str = "hot_verify";
+/// Mainnet registry contract, see [`NetworkProfile::near_registry_contract`].
 pub const MPC_HOT_WALLET_CONTRACT: &str = "mpc.hot.tg";
 pub const MPC_GET_WALLET_METHOD: &str = "get_wallet";
+/// NEP-141 view method used by token balance auth calls to NEAR.
+pub const FT_BALANCE_OF_METHOD: &str = "ft_balance_of";
 
 /// `account_id` is the smart contract address, and `chain_id` is the internal identifier for the chain.
 /// Together, they indicate where to call `hot_verify`.
@@ -37,12 +46,21 @@ pub struct AuthMethod {
     pub metadata: Option<String>,
 }
 
-/// The output of `get_wallet` on Near `mpc.hot.tg` smart contract.
+/// The output of `get_wallet` on Near registry smart contract (`mpc.hot.tg` on mainnet).
 #[derive(Debug, Deserialize, PartialEq, Clone, Eq, Hash)]
 pub struct WalletAuthMethods {
     pub access_list: Vec<AuthMethod>,
 }
 
+/// What an auth method of the wallet was verified against, besides its verdict.
+#[derive(Debug, Default, Serialize, PartialEq, Clone, Eq)]
+pub struct AuthMethodReport {
+    /// Ledger at which the Stellar storage entry read by the auth call was last modified,
+    /// so that the write the verdict is based on can be found.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified_ledger: Option<u64>,
+}
+
 /// The logic that prevents signing arbitrary messages.
 #[derive(Clone)]
 pub struct Validation {
@@ -52,12 +70,33 @@ pub struct Validation {
     pub stellar: Arc<ThresholdVerifier<StellarVerifier>>,
     pub ton: Arc<ThresholdVerifier<TonVerifier>>,
     pub solana: Arc<ThresholdVerifier<SolanaVerifier>>,
+    pub network: NetworkProfile,
+    /// If set, only the auth calls allowed by the policy are executed.
+    pub auth_call_policy: Option<AuthCallPolicy>,
 }
 
 impl Validation {
+    /// Creates a validation object against mainnet contracts.
     pub fn new(configs: &HashMap<ChainId, ChainValidationConfig>) -> Result<Self> {
+        Self::new_with_network(configs, NetworkProfile::mainnet())
+    }
+
+    pub fn new_with_network(
+        configs: &HashMap<ChainId, ChainValidationConfig>,
+        network: NetworkProfile,
+    ) -> Result<Self> {
+        network.ensure_contracts()?;
         let client: Arc<reqwest::Client> = Arc::new(reqwest::Client::new());
         for (chain_id, config) in configs {
+            // Verifiers are picked by the chain family, an unknown chain would be dropped silently.
+            let family = chain_id.family().with_context(|| {
+                format!("Chain {chain_id} is configured, but missing from the chain registry")
+            })?;
+            if let Some(finality) = config.finality {
+                finality
+                    .ensure_supported_by(family)
+                    .with_context(|| format!("Invalid finality of chain {chain_id}"))?;
+            }
             metrics::set_threshold_delta(*chain_id, config.servers.len(), config.threshold);
         }
 
@@ -66,7 +105,7 @@ impl Validation {
                 .get(&ChainId::Near)
                 .expect("No near config (chain_id = 0) found")
                 .clone();
-            let verifier = ThresholdVerifier::new_near(config, &client);
+            let verifier = ThresholdVerifier::new_near(config, &client, &network);
             Arc::new(verifier)
         };
 
@@ -75,15 +114,14 @@ impl Validation {
                 .get(&ChainId::Stellar)
                 .expect("No stellar config (chain_id = 1100) found")
                 .clone();
-            let verifier = ThresholdVerifier::new_stellar(config)?;
+            let verifier = ThresholdVerifier::new_stellar(config, &client, &network)?;
             Arc::new(verifier)
         };
 
         let cosmos = configs
             .clone()
             .into_iter()
-            // Some chains that are not EVM (Ton, Cosmos) are being included too, but we dont really care.
-            .filter(|(id, _)| id.is_cosmos())
+            .filter(|(id, _)| id.family() == Some(ChainFamily::Cosmos))
             .map(|(id, config)| {
                 let threshold_verifier = {
                     let verifier = ThresholdVerifier::new_cosmos(config, &client.clone(), id);
@@ -96,8 +134,7 @@ impl Validation {
         let evm = configs
             .clone()
             .into_iter()
-            // Some chains that are not EVM (Ton, Cosmos) are being included too, but we dont really care.
-            .filter(|(id, _)| matches!(id, ChainId::Evm(_)))
+            .filter(|(id, _)| id.family() == Some(ChainFamily::Evm))
             .map(|(id, config)| {
                 let threshold_verifier = {
                     let verifier = ThresholdVerifier::new_evm(config, &client.clone(), id);
@@ -112,7 +149,7 @@ impl Validation {
                 .get(&ChainId::TON_V2)
                 .expect("No ton config (chain_id = 1117) found")
                 .clone();
-            let verifier = ThresholdVerifier::new_ton(config, &client);
+            let verifier = ThresholdVerifier::new_ton(config, &client)?;
             Arc::new(verifier)
         };
 
@@ -120,7 +157,7 @@ impl Validation {
             let config = configs
                 .get(&ChainId::Solana)
                 .expect("No solana config found");
-            let verifier = ThresholdVerifier::new_solana(config);
+            let verifier = ThresholdVerifier::new_solana(config, &network)?;
             Arc::new(verifier)
         };
 
@@ -131,26 +168,62 @@ impl Validation {
             stellar,
             ton,
             solana,
+            network,
+            auth_call_policy: None,
         };
         Ok(validation)
     }
 
+    #[must_use]
+    pub fn with_auth_call_policy(mut self, policy: AuthCallPolicy) -> Self {
+        self.auth_call_policy = Some(policy);
+        self
+    }
+
+    /// Verifies the proof of `message` against all auth methods of the wallet,
+    /// and reports them in the order of the wallet's access list.
+    pub async fn verify(
+        self: &Arc<Self>,
+        wallet_id: WalletId,
+        message: Vec<u8>,
+        proof: ProofModel,
+    ) -> Result<Vec<AuthMethodReport>> {
+        self.verify_at(wallet_id, message, proof, &HashMap::new())
+            .await
+    }
+
+    /// Same as [`Validation::verify`], but every chain in `pins` is queried at the given
+    /// block height (NEAR, EVM, Cosmos) or masterchain seqno (TON)
+    /// instead of the head selected by its finality policy.
+    /// Used to replay past signing decisions against archival RPCs.
+    /// Solana and Stellar RPCs can only read the current state, so pinning them is an error.
     #[instrument(
         skip(self, message),
         fields(message_hex = %hex::encode(&message)),
         err(Debug)
     )]
-    pub async fn verify(
+    pub async fn verify_at(
         self: &Arc<Self>,
         wallet_id: WalletId,
         message: Vec<u8>,
         proof: ProofModel,
-    ) -> Result<()> {
+        pins: &HashMap<ChainId, u64>,
+    ) -> Result<Vec<AuthMethodReport>> {
+        for chain_id in pins.keys() {
+            ensure!(
+                !matches!(
+                    chain_id.family(),
+                    Some(ChainFamily::Solana | ChainFamily::Stellar)
+                ),
+                "{chain_id} can't be pinned, its RPC only reads the current state"
+            );
+        }
+        challenge::ensure_bridge_challenges(&message, &proof.user_payloads)?;
         let _timer = metrics::RPC_VERIFY_TOTAL_DURATION.start_timer();
 
         let wallet = self
             .near
-            .get_wallet_auth_methods(wallet_id.clone())
+            .get_wallet_auth_methods_at(wallet_id.clone(), pins.get(&ChainId::Near).copied())
             .await
             .context(format!("Couldn't get auth methods for wallet {wallet_id}"))?;
 
@@ -161,7 +234,7 @@ impl Validation {
             wallet.access_list.len()
         );
 
-        try_join_all(
+        let reports = try_join_all(
             wallet
                 .access_list
                 .into_iter()
@@ -173,12 +246,59 @@ impl Validation {
                         proof.message_body.clone(),
                         message.clone(),
                         user_payload,
+                        pins,
                     )
                 }),
         )
         .await?;
 
-        Ok(())
+        Ok(reports)
+    }
+
+    /// Whether the bridge `contract_id` on `chain_id` has already executed the withdrawal
+    /// with `nonce`. Solana and TON keep the last nonce per receiver, so they require
+    /// `receiver_address` (base58 public key and raw or user-friendly address respectively).
+    #[instrument(skip(self), err(Debug))]
+    pub async fn is_nonce_used(
+        &self,
+        chain_id: ChainId,
+        contract_id: &str,
+        nonce: u128,
+        receiver_address: Option<String>,
+    ) -> Result<bool> {
+        let family = chain_id
+            .family()
+            .ok_or_else(|| anyhow::anyhow!("Unknown chain {chain_id:?}"))?;
+        let contract_id = contract_id.to_string();
+        let withdrawal = CompletedWithdrawal {
+            nonce,
+            receiver_address,
+        };
+        let used = match family {
+            ChainFamily::Stellar => self.stellar.is_nonce_used(contract_id, withdrawal).await?,
+            ChainFamily::Cosmos => {
+                let verifier = self.cosmos.get(&chain_id).ok_or(anyhow::anyhow!(
+                    "Cosmos validation is not configured for chain {chain_id:?}"
+                ))?;
+                verifier.is_nonce_used(contract_id, withdrawal).await?
+            }
+            ChainFamily::Ton => self.ton.is_ton_nonce_used(contract_id, withdrawal).await?,
+            ChainFamily::Solana => {
+                self.solana
+                    .is_solana_nonce_used(contract_id, withdrawal)
+                    .await?
+            }
+            ChainFamily::Evm => {
+                let verifier = self.evm.get(&chain_id).ok_or(anyhow::anyhow!(
+                    "EVM validation is not configured for chain {chain_id:?}"
+                ))?;
+                verifier.is_nonce_used(contract_id, withdrawal).await?
+            }
+            ChainFamily::Near => {
+                bail!("Withdrawals are not executed on NEAR, there are no nonces to check")
+            }
+        };
+        Ok(used)
     }
 
     #[instrument(
@@ -192,75 +312,81 @@ impl Validation {
         auth_method: AuthMethod,
         message_body: String,
         message: Vec<u8>,
-        user_payload: String,
-    ) -> Result<()> {
+        user_payload: UserPayload,
+        pins: &HashMap<ChainId, u64>,
+    ) -> Result<AuthMethodReport> {
         let _timer = metrics::RPC_SINGLE_VERIFY_DURATION.start_timer();
 
         metrics::tick_metrics_verify_total_attempts(ChainId::Near);
         let status = self
             .near
-            .verify(
+            .verify_at(
                 wallet_id.clone(),
                 auth_method.clone(),
                 message,
                 message_body,
-                user_payload,
+                user_payload.encode(),
+                pins.get(&ChainId::Near).copied(),
             )
             .await
             .context("Could not get HotVerifyResult from NEAR")?;
         metrics::tick_metrics_verify_success_attempts(ChainId::Near);
 
+        let mut report = AuthMethodReport::default();
         let status = match status {
             HotVerifyResult::AuthCall(auth_call) => {
-                metrics::tick_metrics_verify_total_attempts(auth_call.chain_id);
-                let status = match auth_call.chain_id {
-                    ChainId::Stellar => {
+                let chain_id = auth_call.chain_id;
+                metrics::tick_metrics_verify_total_attempts(chain_id);
+                let family = auth_call.input_family()?;
+                policy::ensure_well_formed(&auth_call)?;
+                if let Some(policy) = &self.auth_call_policy {
+                    policy.check(&auth_method.account_id, &auth_call)?;
+                }
+                let at = pins.get(&chain_id).copied();
+                let status = match family {
+                    ChainFamily::Stellar => {
                         let verifier = &self.stellar;
-                        verifier
-                            .verify(auth_call.contract_id, auth_call.method, auth_call.input)
-                            .await?
+                        let verdict = verifier.verify_stellar_auth_call(auth_call, at).await?;
+                        if let Some(ledger) = verdict.last_modified_ledger {
+                            tracing::info!(
+                                %wallet_id,
+                                "Stellar storage entry was last modified at ledger {ledger}"
+                            );
+                        }
+                        report.last_modified_ledger = verdict.last_modified_ledger;
+                        verdict.status
                     }
 
-                    chain_id if chain_id.is_cosmos() => {
+                    ChainFamily::Cosmos => {
                         let verifier =
                             self.cosmos.get(&auth_call.chain_id).ok_or(anyhow::anyhow!(
                                 "Cosmos validation is not configured for chain {:?}",
                                 auth_call.chain_id
                             ))?;
-                        verifier
-                            .verify(auth_call.contract_id, auth_call.method, auth_call.input)
-                            .await?
+                        verifier.verify_cosmos_auth_call(auth_call, at).await?
                     }
 
-                    ChainId::Ton | ChainId::TON_V2 => {
+                    ChainFamily::Ton => {
                         let verifier = &self.ton;
-                        verifier
-                            .verify(auth_call.contract_id, auth_call.method, auth_call.input)
-                            .await?
+                        verifier.verify_ton_auth_call(auth_call, at).await?
                     }
 
-                    ChainId::Solana => {
+                    ChainFamily::Solana => {
                         let verifier = &self.solana;
-                        verifier
-                            .verify(auth_call.contract_id, auth_call.method, auth_call.input)
-                            .await?
+                        verifier.verify_solana_auth_call(auth_call, at).await?
                     }
 
-                    ChainId::Evm(_) => {
+                    ChainFamily::Evm => {
                         let verifier = self.evm.get(&auth_call.chain_id).ok_or(anyhow::anyhow!(
                             "EVM validation is not configured for chain {:?}",
                             auth_call.chain_id
                         ))?;
-                        verifier
-                            .verify(auth_call.contract_id, auth_call.method, auth_call.input)
-                            .await?
+                        verifier.verify_evm_auth_call(auth_call, at).await?
                     }
 
-                    ChainId::Near => {
-                        bail!("Auth call should not lead to NEAR")
-                    }
+                    ChainFamily::Near => self.near.verify_auth_call(auth_call, at).await?,
                 };
-                metrics::tick_metrics_verify_success_attempts(auth_call.chain_id);
+                metrics::tick_metrics_verify_success_attempts(chain_id);
                 status
             }
             HotVerifyResult::Result(status) => status,
@@ -271,7 +397,7 @@ impl Validation {
             "Auth method {auth_method:?} failed for wallet_id {wallet_id}"
         );
 
-        Ok(())
+        Ok(report)
     }
 }
 
@@ -318,6 +444,9 @@ pub mod test_data {
                         "https://allthatnode.com/protocol/near.dsrv".to_string(),
                         near_rpc(),
                     ],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
             (
@@ -325,6 +454,9 @@ pub mod test_data {
                 ChainValidationConfig {
                     threshold: 1,
                     servers: vec!["https://mainnet.sorobanrpc.com".to_string()],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
             (
@@ -335,6 +467,9 @@ pub mod test_data {
                         "https://eth.drpc.org".to_string(),
                         "http://bad-rpc:8545".to_string(),
                     ],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
             (
@@ -346,6 +481,9 @@ pub mod test_data {
                         "http://bad-rpc:8545".to_string(),
                         base_rpc(),
                     ],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
             (
@@ -353,6 +491,9 @@ pub mod test_data {
                 ChainValidationConfig {
                     threshold: 1,
                     servers: vec!["https://bsc.blockrazor.xyz".to_string(), bnb_rpc()],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
             (
@@ -363,6 +504,9 @@ pub mod test_data {
                         "https://toncenter.com/api/v2/jsonRPC".to_string(),
                         ton_rpc(),
                     ],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
             (
@@ -370,6 +514,9 @@ pub mod test_data {
                 ChainValidationConfig {
                     threshold: 1,
                     servers: vec!["https://api.mainnet-beta.solana.com".to_string()],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
             (
@@ -377,6 +524,9 @@ pub mod test_data {
                 ChainValidationConfig {
                     threshold: 1,
                     servers: vec!["https://juno-api.stakeandrelax.net".to_string()],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
         ]);
@@ -410,12 +560,40 @@ mod tests {
                 .unwrap();
         let proof = ProofModel {
             message_body: "S8safEk4JWgnJsVKxans4TqBL796cEuV5GcrqnFHPdNW91AupymrQ6zgwEXoeRb6P3nyaSskoFtMJzaskXTDAnQUTKs5dGMWQHsz7irQJJ2UA2aDHSQ4qxgsU3h1U83nkq4rBstK8PL1xm6WygSYihvBTmuaMjuKCK6JT1tB4Uw71kGV262kU914YDwJa53BiNLuVi3s2rj5tboEwsSEpyJo9x5diq4Ckmzf51ZjZEDYCH8TdrP1dcY4FqkTCBA7JhjfCTToJR5r74ApfnNJLnDhTxkvJb4ReR9T9Ga7hPNazCFGE8Xq1deu44kcPjXNvb1GJGWLAZ5k1wxq9nnARb3bvkqBTmeYiDcPDamauhrwYWZkMNUsHtoMwF6286gcmY3ZgE3jja1NGuYKYQHnvscUqcutuT9qH".to_string(),
-            user_payloads: vec![r#"{"auth_method":0,"signatures":["HZUhhJamfp8GJLL8gEa2F2qZ6TXPu4PYzzWkDqsTQsMcW9rQsG2Hof4eD2Vex6he2fVVy3UNhgi631CY8E9StAH"]}"#.to_string()],
+            user_payloads: vec![r#"{"auth_method":0,"signatures":["HZUhhJamfp8GJLL8gEa2F2qZ6TXPu4PYzzWkDqsTQsMcW9rQsG2Hof4eD2Vex6he2fVVy3UNhgi631CY8E9StAH"]}"#.parse().unwrap()],
         };
 
         validation.verify(wallet_id, message, proof).await.unwrap();
     }
 
+    #[test]
+    fn unregistered_chain_fails_at_startup() {
+        let config = ChainValidationConfig {
+            threshold: 1,
+            servers: vec!["http://localhost:8545".to_string()],
+            finality: None,
+            head_poll_interval_ms: None,
+            max_log_block_range: None,
+        };
+        let configs = HashMap::from([(ChainId::Evm(999_999_999), config)]);
+        let err = Validation::new(&configs).err().unwrap();
+        assert!(err.to_string().contains("missing from the chain registry"));
+    }
+
+    #[test]
+    fn min_confirmations_on_solana_fail_at_startup() {
+        let config = ChainValidationConfig {
+            threshold: 1,
+            servers: vec!["https://api.mainnet-beta.solana.com".to_string()],
+            finality: Some(Finality::new(FinalityLevel::Final).with_min_confirmations(3)),
+            head_poll_interval_ms: None,
+            max_log_block_range: None,
+        };
+        let configs = HashMap::from([(ChainId::Solana, config)]);
+        let err = Validation::new(&configs).err().unwrap();
+        assert!(format!("{err:#}").contains("min_confirmations is not supported by Solana"));
+    }
+
     #[should_panic]
     #[tokio::test]
     async fn two_auth_methods_fail_with_bad_rpc() {
@@ -432,6 +610,9 @@ mod tests {
                         "https://allthatnode.com/protocol/near.dsrv".to_string(),
                         near_rpc(),
                     ],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
             (
@@ -439,6 +620,9 @@ mod tests {
                 ChainValidationConfig {
                     threshold: 1,
                     servers: vec!["http://bad-rpc:8545".to_string()],
+                    finality: None,
+                    head_poll_interval_ms: None,
+                    max_log_block_range: None,
                 },
             ),
         ]);
@@ -451,8 +635,8 @@ mod tests {
         let proof = ProofModel {
             message_body: String::new(),
             user_payloads: vec![
-                "{\"signatures\": [\"2r4RNC49RGA6Wqo5VzZtATBs3jMvqZCo5NYfJGkDpHZd598Zvt7kFfiuH8yr26CynzSMsgoHYoMUF5h31dSVHAT1\"], \"auth_method\": 0}".to_string(),
-                "00000000000000000000000000000000000000000000005e9def3f04597b183c0000000000000000000000000000000000000000000000000000000000000000".to_string()
+                "{\"signatures\": [\"2r4RNC49RGA6Wqo5VzZtATBs3jMvqZCo5NYfJGkDpHZd598Zvt7kFfiuH8yr26CynzSMsgoHYoMUF5h31dSVHAT1\"], \"auth_method\": 0}".parse().unwrap(),
+                "00000000000000000000000000000000000000000000005e9def3f04597b183c0000000000000000000000000000000000000000000000000000000000000000".parse().unwrap()
             ],
         };
 
@@ -481,11 +665,9 @@ mod tests {
                 nonce: 1_754_431_900_000_000_013_182,
             },
         });
-        let json = serde_json::to_value(&payload)?;
-        dbg!(&json);
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(wallet_id, message, proof).await?;
@@ -509,11 +691,9 @@ mod tests {
                 nonce: 1_754_531_354_365_901_458_000,
             },
         });
-        let json = serde_json::to_value(&payload)?;
-        dbg!(&json);
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(wallet_id, message, proof).await?;
@@ -538,11 +718,9 @@ mod tests {
                 nonce: 1_753_218_716_000_000_003_679,
             },
         });
-        let json = serde_json::to_value(&payload)?;
-        dbg!(&json);
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(wallet_id, message, proof).await?;
@@ -566,11 +744,9 @@ mod tests {
                 ),
             },
         });
-        let json = serde_json::to_value(&payload)?;
-        dbg!(&json);
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(wallet_id, message, proof).await?;
@@ -593,11 +769,9 @@ mod tests {
                 receiver_address: Some("dontcare".to_string()),
             },
         });
-        let json = serde_json::to_value(&payload)?;
-        dbg!(&json);
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(wallet_id, message, proof).await?;
@@ -620,11 +794,9 @@ mod tests {
                 receiver_address: Some("dontcare".to_string()),
             },
         });
-        let json = serde_json::to_value(&payload)?;
-        dbg!(&json);
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(wallet_id, message, proof).await?;
@@ -638,7 +810,7 @@ mod tests {
 
         let wallet_id = staging_wallet_id();
         let message =
-            hex::decode("bcb143828f64d7e4bf0b6a8e66a2a2d03c916c16e9e9034419ae778b9f699d3c")?;
+            hex::decode("47b8b751a0d90d113e4e16678ebda646a01a02d376f49f666ddd17ee9f383c2f")?;
         let payload = HotVerifyBridge::Deposit(DepositAction {
             chain_id: ChainId::Solana,
             data: DepositData {
@@ -661,11 +833,9 @@ mod tests {
                 nonce: 1_757_984_522_000_007_228,
             },
         });
-        let json = serde_json::to_value(&payload)?;
-        dbg!(&json);
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(wallet_id, message, proof).await?;
@@ -673,6 +843,33 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn bridge_payload_with_foreign_message_is_rejected() -> Result<()> {
+        let validation = create_validation_object();
+
+        let payload = HotVerifyBridge::ClearCompletedWithdrawal(CompletedWithdrawalAction {
+            chain_id: ChainId::Solana,
+            data: CompletedWithdrawal {
+                nonce: 1_749_390_032_000_000_032_243,
+                receiver_address: None,
+            },
+        });
+        let proof = ProofModel {
+            message_body: String::new(),
+            user_payloads: vec![payload.into()],
+        };
+
+        let err = validation
+            .verify(staging_wallet_id(), vec![0; 32], proof)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<challenge::BridgeChallengeError>(),
+            Some(challenge::BridgeChallengeError::Mismatch { .. })
+        ));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn bridge_completed_withdrawal_validation_solana() -> Result<()> {
         let validation = create_validation_object();
@@ -688,11 +885,9 @@ mod tests {
                 receiver_address: Some("5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho".to_string()),
             },
         });
-        let json = serde_json::to_value(&payload)?;
-        dbg!(&json);
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(wallet_id, message, proof).await?;
@@ -716,10 +911,9 @@ mod tests {
                 nonce: 1764175051000000000008,
             },
         });
-        let json = serde_json::to_value(&payload)?;
         let proof = ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
+            user_payloads: vec![payload.into()],
         };
 
         validation.verify(uid, message, proof).await?;
@@ -87,6 +87,23 @@ pub fn bump_metrics_rpc_call_fail(chain_id: ChainId, url: &str) {
         .inc();
 }
 
+static RPC_HEAD_LAG: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "rpc_head_lag",
+        "How far the latest head of the RPC is behind the median of all RPCs of the chain",
+        &["chain_id", "provider"]
+    )
+    .expect("register rpc_head_lag")
+});
+
+pub fn set_head_lag(chain_id: ChainId, url: &str, lag: u64) {
+    let chain_label = chain_label(chain_id);
+    let provider = second_level_or_url(url);
+    RPC_HEAD_LAG
+        .with_label_values(&[&chain_label, &provider])
+        .set(i64::try_from(lag).unwrap_or(i64::MAX));
+}
+
 fn second_level_or_url(url: &str) -> String {
     match Url::parse(url)
         .ok()
@@ -0,0 +1,432 @@
+use crate::verifiers::{evm, stellar, ton};
+use anyhow::{ensure, Result};
+use hot_validation_primitives::bridge::expectation::ExpectationOp;
+use hot_validation_primitives::bridge::solana::SolanaInputData;
+use hot_validation_primitives::bridge::{HotVerifyAuthCall, InputData, InputKind};
+use hot_validation_primitives::{ChainFamily, ChainId};
+use serde::{Deserialize, Serialize};
+
+/// Allowlist of auth calls that NEAR auth contracts may redirect verification to.
+///
+/// Without a policy, whatever [`HotVerifyAuthCall`] an auth contract returns is called on the
+/// target chain. With a policy, an auth call is executed only if it matches at least one rule.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthCallPolicy {
+    pub rules: Vec<AuthCallRule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthCallRule {
+    pub chain_id: ChainId,
+    pub contract_id: String,
+    pub method: String,
+    /// If set, the rule only applies to auth calls returned by this NEAR auth contract
+    /// (`AuthMethod::account_id`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_account_id: Option<String>,
+    /// Kinds of input the call may carry, only plain contract calls if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_kinds: Vec<InputKind>,
+    /// Expectations the call may carry, none if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expectations: Vec<ExpectationOp>,
+    /// Return fields the call may select, none if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub return_fields: Vec<String>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "Auth call to {contract_id}::{method} on chain {chain_id} returned by {auth_account_id} is not allowed by the policy"
+)]
+pub struct AuthCallRejected {
+    pub auth_account_id: String,
+    pub chain_id: ChainId,
+    pub contract_id: String,
+    pub method: String,
+}
+
+impl AuthCallRule {
+    fn matches(&self, auth_account_id: &str, auth_call: &HotVerifyAuthCall) -> bool {
+        let same_contract = if auth_call.chain_id.family() == Some(ChainFamily::Evm) {
+            // EVM addresses may come in checksummed or lowercase form.
+            self.contract_id
+                .eq_ignore_ascii_case(&auth_call.contract_id)
+        } else {
+            self.contract_id == auth_call.contract_id
+        };
+        let input_kind = auth_call.input.kind();
+        let allowed_input = if self.input_kinds.is_empty() {
+            input_kind.is_contract_call()
+        } else {
+            self.input_kinds.contains(&input_kind)
+        };
+        self.chain_id == auth_call.chain_id
+            && same_contract
+            && self.method == auth_call.method
+            && self
+                .auth_account_id
+                .as_ref()
+                .is_none_or(|account_id| account_id == auth_account_id)
+            && allowed_input
+            && auth_call
+                .expectation
+                .is_none_or(|expectation| self.expectations.contains(&expectation.op()))
+            && auth_call
+                .return_field
+                .as_ref()
+                .is_none_or(|field| self.return_fields.contains(field))
+    }
+}
+
+/// Refuses expectations and return fields the input can't honor, with or without a policy:
+/// * only Stellar and TON calls return a value a field can be selected from
+/// * built-in checks and log queries carry their own condition, which an expectation would replace
+/// * bridge entry points return the verdict itself, so an expectation on them
+///   (e.g. `not_equals: true`) would turn a failed verification into a pass
+pub fn ensure_well_formed(auth_call: &HotVerifyAuthCall) -> Result<()> {
+    let kind = auth_call.input.kind();
+    ensure!(
+        auth_call.return_field.is_none()
+            || matches!(
+                kind,
+                InputKind::Stellar | InputKind::StellarStorage | InputKind::Ton
+            ),
+        "Return fields are only supported by Stellar and TON auth calls, got {kind:?} input"
+    );
+    let has_own_condition = matches!(
+        kind,
+        InputKind::TokenBalance | InputKind::Transaction | InputKind::EvmLogs
+    );
+    let accepts_expectation = !has_own_condition && !returns_verdict(auth_call);
+    ensure!(
+        auth_call.expectation.is_none() || accepts_expectation,
+        "Auth call to {}::{} with {kind:?} input can't carry an expectation",
+        auth_call.contract_id,
+        auth_call.method
+    );
+    Ok(())
+}
+
+/// Whether the entry point the auth call resolves to is one of the bridge verdicts.
+fn returns_verdict(auth_call: &HotVerifyAuthCall) -> bool {
+    let method = auth_call.method.as_str();
+    match &auth_call.input {
+        InputData::Evm(input) => evm::returns_verdict(method, input),
+        InputData::Stellar(_) => stellar::VERDICT_METHODS.contains(&method),
+        // The child get-method is the entry point, the treasury one only finds the child.
+        InputData::Ton(input) => ton::VERDICT_METHODS.contains(&input.child_call_method.as_str()),
+        // Deposits and withdrawal checks are answered by the built-in rules, whatever the method.
+        InputData::Solana(input) => matches!(
+            input,
+            SolanaInputData::Deposit(_) | SolanaInputData::CheckCompletedWithdrawal(_)
+        ),
+        // The query message selects the entry point, and both of them are bridge verdicts.
+        InputData::Cosmos(_) => true,
+        _ => false,
+    }
+}
+
+impl AuthCallPolicy {
+    pub fn check(
+        &self,
+        auth_account_id: &str,
+        auth_call: &HotVerifyAuthCall,
+    ) -> Result<(), AuthCallRejected> {
+        if self
+            .rules
+            .iter()
+            .any(|rule| rule.matches(auth_account_id, auth_call))
+        {
+            return Ok(());
+        }
+        Err(AuthCallRejected {
+            auth_account_id: auth_account_id.to_string(),
+            chain_id: auth_call.chain_id,
+            contract_id: auth_call.contract_id.clone(),
+            method: auth_call.method.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hot_validation_primitives::bridge::balance::TokenBalanceInputData;
+    use hot_validation_primitives::bridge::cosmos::{CosmosInputData, CosmosStorageQuery};
+    use hot_validation_primitives::bridge::evm::{EvmFunctionCall, EvmInputData};
+    use hot_validation_primitives::bridge::expectation::{Expectation, ReturnValue};
+    use hot_validation_primitives::bridge::stellar::{StellarInputArg, StellarInputData};
+    use hot_validation_primitives::bridge::ton::{Action, TonInputData};
+    use hot_validation_primitives::bridge::transaction::{TransactionId, TransactionInputData};
+    use hot_validation_primitives::bridge::CompletedWithdrawal;
+
+    fn auth_call(chain_id: ChainId, contract_id: &str) -> HotVerifyAuthCall {
+        HotVerifyAuthCall {
+            contract_id: contract_id.to_string(),
+            method: "hot_verify".to_string(),
+            chain_id,
+            input: EvmInputData::from_parts(
+                "00".repeat(32),
+                "00000000000000000000000000000000".to_string(),
+            )
+            .unwrap()
+            .into(),
+            expectation: None,
+            return_field: None,
+        }
+    }
+
+    fn policy() -> AuthCallPolicy {
+        serde_json::from_value(serde_json::json!({
+            "rules": [
+                {
+                    "chain_id": 8453,
+                    "contract_id": "0xf22Ef29d5Bb80256B569f4233a76EF09Cae996eC",
+                    "method": "hot_verify",
+                    "auth_account_id": "keys.auth.hot.tg"
+                },
+                {
+                    "chain_id": 1100,
+                    "contract_id": "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG",
+                    "method": "hot_verify"
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn allows_matching_call() {
+        let policy = policy();
+        let call = auth_call(
+            ChainId::Evm(8453),
+            "0xf22ef29d5bb80256b569f4233a76ef09cae996ec",
+        );
+        assert!(policy.check("keys.auth.hot.tg", &call).is_ok());
+
+        let call = auth_call(
+            ChainId::Stellar,
+            "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG",
+        );
+        assert!(policy.check("any.auth.hot.tg", &call).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_call() {
+        let policy = policy();
+        let call = auth_call(
+            ChainId::Evm(8453),
+            "0x0000000000000000000000000000000000000001",
+        );
+        let err = policy.check("keys.auth.hot.tg", &call).unwrap_err();
+        assert_eq!(err.chain_id, ChainId::Evm(8453));
+
+        // right target, but returned by another auth contract
+        let call = auth_call(
+            ChainId::Evm(8453),
+            "0xf22Ef29d5Bb80256B569f4233a76EF09Cae996eC",
+        );
+        assert!(policy.check("evil.near", &call).is_err());
+
+        // same address on another chain
+        let call = auth_call(
+            ChainId::Evm(1),
+            "0xf22Ef29d5Bb80256B569f4233a76EF09Cae996eC",
+        );
+        assert!(policy.check("keys.auth.hot.tg", &call).is_err());
+    }
+
+    #[test]
+    fn rejects_unlisted_expectation_and_return_field() {
+        let policy = policy();
+        let mut call = auth_call(
+            ChainId::Evm(8453),
+            "0xf22Ef29d5Bb80256B569f4233a76EF09Cae996eC",
+        );
+        call.expectation = Some(Expectation::NotEquals(ReturnValue::Bool(true)));
+        assert!(policy.check("keys.auth.hot.tg", &call).is_err());
+
+        let mut call = auth_call(
+            ChainId::Evm(8453),
+            "0xf22Ef29d5Bb80256B569f4233a76EF09Cae996eC",
+        );
+        call.return_field = Some("0".to_string());
+        assert!(policy.check("keys.auth.hot.tg", &call).is_err());
+    }
+
+    #[test]
+    fn allows_listed_expectation_and_return_field() {
+        let policy: AuthCallPolicy = serde_json::from_value(serde_json::json!({
+            "rules": [{
+                "chain_id": 1100,
+                "contract_id": "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG",
+                "method": "get_user",
+                "input_kinds": ["stellar"],
+                "expectations": ["greater_or_equal"],
+                "return_fields": ["nonce"]
+            }]
+        }))
+        .unwrap();
+        let mut call = auth_call(
+            ChainId::Stellar,
+            "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG",
+        );
+        call.method = "get_user".to_string();
+        call.input = StellarInputData(vec![StellarInputArg::Address(
+            "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG".to_string(),
+        )])
+        .into();
+        call.expectation = Some(Expectation::GreaterOrEqual(ReturnValue::from(42u128)));
+        call.return_field = Some("nonce".to_string());
+        assert!(policy.check("any.auth.hot.tg", &call).is_ok());
+
+        // listed target, but an evm input is not among the allowed kinds
+        let mut evm_input = call.clone();
+        evm_input.input = auth_call(ChainId::Stellar, "").input;
+        assert!(policy.check("any.auth.hot.tg", &evm_input).is_err());
+
+        call.expectation = Some(Expectation::LessOrEqual(ReturnValue::from(42u128)));
+        assert!(policy.check("any.auth.hot.tg", &call).is_err());
+    }
+
+    fn inverted(mut call: HotVerifyAuthCall, method: &str, input: InputData) -> HotVerifyAuthCall {
+        call.method = method.to_string();
+        call.input = input;
+        call.expectation = Some(Expectation::NotEquals(ReturnValue::Bool(true)));
+        call
+    }
+
+    #[test]
+    fn evm_verdict_cant_be_overridden() {
+        let call = auth_call(
+            ChainId::Evm(8453),
+            "0xf22Ef29d5Bb80256B569f4233a76EF09Cae996eC",
+        );
+        assert!(ensure_well_formed(&call).is_ok());
+        let input = call.input.clone();
+        assert!(ensure_well_formed(&inverted(call.clone(), "hot_verify", input)).is_err());
+
+        // the selector counts, not the outputs the signature declares
+        let signature = |function: &str| {
+            EvmInputData::Call(EvmFunctionCall {
+                function: function.to_string(),
+                args: vec![],
+            })
+            .into()
+        };
+        let input = signature("function hot_verify(bytes32,bytes,bytes,bytes) returns (uint8)");
+        assert!(ensure_well_formed(&inverted(call.clone(), "hot_verify", input)).is_err());
+        let input = signature("function usedNonces(uint128) returns (uint8)");
+        assert!(ensure_well_formed(&inverted(call.clone(), "usedNonces", input)).is_err());
+        let input = signature("function nonceOf(uint128) returns (uint128)");
+        assert!(ensure_well_formed(&inverted(call, "nonceOf", input)).is_ok());
+    }
+
+    #[test]
+    fn stellar_verdict_cant_be_overridden() {
+        let call = auth_call(
+            ChainId::Stellar,
+            "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG",
+        );
+        let input: InputData = StellarInputData(vec![]).into();
+        for method in ["hot_verify", "is_executed"] {
+            let call = inverted(call.clone(), method, input.clone());
+            assert!(ensure_well_formed(&call).is_err());
+        }
+        assert!(ensure_well_formed(&inverted(call, "get_user", input)).is_ok());
+    }
+
+    #[test]
+    fn ton_verdict_cant_be_overridden() {
+        let call = auth_call(
+            ChainId::TON_V2,
+            "EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ",
+        );
+        let input = |child_call_method: &str| -> InputData {
+            TonInputData {
+                treasury_call_args: vec![],
+                child_call_method: child_call_method.to_string(),
+                child_call_args: vec![],
+                action: Action::Deposit,
+            }
+            .into()
+        };
+        // whatever the treasury method is called
+        for child_call_method in ["verify_withdraw", "get_last_withdrawn_nonce"] {
+            let call = inverted(call.clone(), "get_anything", input(child_call_method));
+            assert!(ensure_well_formed(&call).is_err());
+        }
+        let call = inverted(call, "get_user_jetton_address", input("get_user_nonce"));
+        assert!(ensure_well_formed(&call).is_ok());
+    }
+
+    #[test]
+    fn solana_verdict_cant_be_overridden() {
+        let call = auth_call(
+            ChainId::Solana,
+            "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC",
+        );
+        let input: InputData = SolanaInputData::CheckCompletedWithdrawal(CompletedWithdrawal {
+            nonce: 1,
+            receiver_address: None,
+        })
+        .into();
+        // the method doesn't pick the handler of a withdrawal check
+        let call = inverted(call, "get_user", input);
+        assert!(ensure_well_formed(&call).is_err());
+    }
+
+    #[test]
+    fn cosmos_verdict_cant_be_overridden() {
+        let call = auth_call(ChainId::Evm(4_444_118), "hot1contract");
+        let input: InputData = CosmosInputData::IsExecuted { nonce: 1 }.into();
+        // the method isn't used by smart queries at all
+        let call = inverted(call, "anything", input);
+        assert!(ensure_well_formed(&call).is_err());
+    }
+
+    #[test]
+    fn return_fields_need_a_stellar_or_ton_call() {
+        let mut call = auth_call(
+            ChainId::Evm(8453),
+            "0xf22Ef29d5Bb80256B569f4233a76EF09Cae996eC",
+        );
+        call.method = "nonceOf".to_string();
+        call.return_field = Some("0".to_string());
+        assert!(ensure_well_formed(&call).is_err());
+
+        call.chain_id = ChainId::Stellar;
+        call.input = StellarInputData(vec![]).into();
+        assert!(ensure_well_formed(&call).is_ok());
+
+        call.chain_id = ChainId::Evm(4_444_118);
+        call.input = CosmosStorageQuery { key: vec![0] }.into();
+        assert!(ensure_well_formed(&call).is_err());
+    }
+
+    #[test]
+    fn built_in_checks_cant_carry_an_expectation() {
+        let call = auth_call(ChainId::Near, "usdt.tether-token.near");
+        let inputs: [InputData; 2] = [
+            TokenBalanceInputData {
+                holder: "alice.near".to_string(),
+                min_balance: 1,
+            }
+            .into(),
+            TransactionInputData {
+                transaction: TransactionId::Evm {
+                    hash: format!("0x{}", "00".repeat(32)),
+                },
+                min_confirmations: 0,
+            }
+            .into(),
+        ];
+        for input in inputs {
+            let mut call = inverted(call.clone(), "ft_balance_of", input);
+            assert!(ensure_well_formed(&call).is_err());
+            call.expectation = None;
+            assert!(ensure_well_formed(&call).is_ok());
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Why a contract rejected a call, decoded from the chain-specific failure formats.
+//!
+//! Verifiers return a [`RevertReason`] as the source of their error when the call reached
+//! the contract and failed there. If enough verifiers agree on it, it ends up in
+//! [`VerificationError::reason`](crate::VerificationError::reason).
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RevertReason {
+    /// `Error(string)` on EVM, a panic message on NEAR.
+    #[error("reverted: {0}")]
+    Message(String),
+    /// `Panic(uint256)` on EVM, e.g. `0x11` for an arithmetic overflow.
+    #[error("panicked with code {0:#x}")]
+    Panic(u64),
+    /// Anchor error, parsed from the program logs.
+    #[error("Anchor error {code} ({name}): {message}")]
+    Anchor {
+        code: u32,
+        name: String,
+        message: String,
+    },
+    /// Custom program error on Solana, `contracterror` of a Soroban contract.
+    #[error("contract error #{0}")]
+    Code(u32),
+    /// A failure we couldn't decode: hex revert data on EVM, the raw error elsewhere.
+    #[error("reverted: {0}")]
+    Raw(String),
+}
+
+impl RevertReason {
+    /// The Anchor error logged by the program, e.g.
+    /// `Program log: AnchorError occurred. Error Code: NonceUsed. Error Number: 6001. Error Message: Nonce is used.`
+    pub fn from_anchor_logs(logs: &[String]) -> Option<Self> {
+        logs.iter().find_map(|log| {
+            let (_, rest) = log.split_once("AnchorError")?;
+            let (_, rest) = rest.split_once("Error Code: ")?;
+            let (name, rest) = rest.split_once(". Error Number: ")?;
+            let (code, message) = rest.split_once(". Error Message: ")?;
+            Some(Self::Anchor {
+                code: code.parse().ok()?,
+                name: name.to_string(),
+                message: message.trim_end_matches('.').to_string(),
+            })
+        })
+    }
+
+    /// The code of `InstructionError(_, Custom(code))` in a Solana transaction error.
+    pub fn from_solana_error(error: &str) -> Option<Self> {
+        let (_, rest) = error.split_once("Custom(")?;
+        let (code, _) = rest.split_once(')')?;
+        code.parse().ok().map(Self::Code)
+    }
+
+    /// The contract error of a Soroban host error, e.g. `HostError: Error(Contract, #3)`.
+    pub fn from_soroban_error(error: &str) -> Option<Self> {
+        let (_, rest) = error.split_once("Error(Contract, #")?;
+        let (code, _) = rest.split_once(')')?;
+        code.parse().ok().map(Self::Code)
+    }
+
+    /// The panic message of a NEAR `FunctionCallError`, e.g.
+    /// `wasm execution failed with error: FunctionCallError(ExecutionError("Smart contract panicked: Nonce is used"))`.
+    /// Other function call errors (missing method, out of gas, ...) are kept as is.
+    pub fn from_near_error(error: &str) -> Option<Self> {
+        if !error.contains("FunctionCallError") {
+            return None;
+        }
+        let reason = error
+            .split_once("Smart contract panicked: ")
+            .and_then(|(_, rest)| rest.rsplit_once("\")"))
+            .map_or_else(
+                || Self::Raw(error.to_string()),
+                |(message, _)| Self::Message(message.replace("\\\"", "\"")),
+            );
+        Some(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RevertReason;
+
+    #[test]
+    fn anchor_error_from_logs() {
+        let logs = [
+            "Program 5bG6efnrV6Ce8p9xcmTQRMi5P4sbEs5jnZsADmVNbFcb invoke [1]".to_string(),
+            "Program log: Instruction: HotVerifyDeposit".to_string(),
+            "Program log: AnchorError thrown in programs/bridge/src/lib.rs:120. Error Code: DepositNotFound. Error Number: 6003. Error Message: Deposit is not found.".to_string(),
+        ];
+        assert_eq!(
+            RevertReason::from_anchor_logs(&logs),
+            Some(RevertReason::Anchor {
+                code: 6003,
+                name: "DepositNotFound".to_string(),
+                message: "Deposit is not found".to_string(),
+            })
+        );
+        assert_eq!(RevertReason::from_anchor_logs(&logs[..2]), None);
+    }
+
+    #[test]
+    fn chain_error_codes() {
+        assert_eq!(
+            RevertReason::from_solana_error("InstructionError(0, Custom(6001))"),
+            Some(RevertReason::Code(6001))
+        );
+        assert_eq!(RevertReason::from_solana_error("AccountNotFound"), None);
+        assert_eq!(
+            RevertReason::from_soroban_error(
+                "HostError: Error(Contract, #3)\n\nEvent log (newest first):\n..."
+            ),
+            Some(RevertReason::Code(3))
+        );
+        assert_eq!(
+            RevertReason::from_soroban_error("HostError: Error(WasmVm, InvalidAction)"),
+            None
+        );
+    }
+
+    #[test]
+    fn near_panic_message() {
+        assert_eq!(
+            RevertReason::from_near_error(
+                r#"wasm execution failed with error: FunctionCallError(ExecutionError("Smart contract panicked: Nonce is used"))"#
+            ),
+            Some(RevertReason::Message("Nonce is used".to_string()))
+        );
+        assert!(matches!(
+            RevertReason::from_near_error(
+                "wasm execution failed with error: FunctionCallError(MethodResolveError(MethodNotFound))"
+            ),
+            Some(RevertReason::Raw(_))
+        ));
+        assert_eq!(RevertReason::from_near_error("Server error"), None);
+    }
+}
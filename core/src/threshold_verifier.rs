@@ -1,7 +1,10 @@
+use crate::revert::RevertReason;
 use crate::verifiers::Verifier;
 use anyhow::anyhow;
 use futures_util::{stream, StreamExt};
-use hot_validation_primitives::bridge::InputData;
+use hot_validation_primitives::bridge::expectation::ReturnValue;
+use hot_validation_primitives::bridge::transaction::TransactionInputData;
+use hot_validation_primitives::bridge::{CompletedWithdrawal, HotVerifyAuthCall, InputData};
 use hot_validation_primitives::ExtendedChainId;
 use rand::prelude::{SliceRandom, StdRng};
 use rand::SeedableRng;
@@ -16,6 +19,11 @@ type Id = String;
 
 pub trait Identifiable {
     fn id(&self) -> String;
+
+    /// Whether the endpoint fell behind the others, see [`crate::head_tracker::HeadTracker`].
+    fn is_lagging(&self) -> bool {
+        false
+    }
 }
 
 /// An interface, to call `hot_verify` concurrently on each `SingleVerifier`,
@@ -36,7 +44,16 @@ impl<T: Identifiable> ThresholdVerifier<T> {
 
         let shuffled_verifiers = {
             let mut rng = StdRng::from_os_rng();
-            let mut verifiers = self.verifiers.clone();
+            // Skip endpoints that fell behind, as long as there are enough of the rest
+            let mut verifiers: Vec<_> = self
+                .verifiers
+                .iter()
+                .filter(|verifier| !verifier.is_lagging())
+                .cloned()
+                .collect();
+            if verifiers.len() < threshold {
+                verifiers.clone_from(&self.verifiers);
+            }
             verifiers.shuffle(&mut rng);
             verifiers
         };
@@ -66,28 +83,66 @@ impl<T: Identifiable> ThresholdVerifier<T> {
         }
 
         // if we exit the loop, nobody hit the threshold
-        Err(anyhow!(
+        let message = format!(
             "No consensus for threshold call, success({}): {:#?}, errors({}): {:#?}",
             votes.len(),
             votes,
             errors.len(),
             errors,
-        ))
+        );
+        // Still, enough verifiers may agree on why the contract rejected the call
+        let mut reasons: HashMap<&RevertReason, usize> = HashMap::new();
+        for reason in errors
+            .values()
+            .filter_map(anyhow::Error::downcast_ref::<RevertReason>)
+        {
+            *reasons.entry(reason).or_default() += 1;
+        }
+        let reason = reasons
+            .into_iter()
+            .find(|(_, count)| *count >= threshold)
+            .map(|(reason, _)| reason.clone());
+        Err(match reason {
+            Some(reason) => anyhow::Error::new(reason).context(message),
+            None => anyhow!(message),
+        })
     }
 }
 
 #[derive(Error, Debug)]
 #[error(
-    "Verification failed for {chain_id}, contract={auth_contract_id}, method={method_name}: {kind}"
+    "Verification failed for {chain_id}, contract={auth_contract_id}, method={method_name}{}: {kind}",
+    .reason.as_ref().map(|reason| format!(", {reason}")).unwrap_or_default()
 )]
 pub struct VerificationError {
     pub chain_id: ExtendedChainId,
     pub auth_contract_id: String,
     pub method_name: String,
     pub input_data: InputData,
+    /// Why the contract rejected the call, if the verifiers agree on it.
+    pub reason: Option<RevertReason>,
     pub kind: anyhow::Error,
 }
 
+impl VerificationError {
+    pub(crate) fn new(
+        chain_id: ExtendedChainId,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        kind: anyhow::Error,
+    ) -> Self {
+        Self {
+            chain_id,
+            auth_contract_id,
+            method_name,
+            input_data,
+            reason: kind.downcast_ref::<RevertReason>().cloned(),
+            kind,
+        }
+    }
+}
+
 impl<T: Identifiable + Verifier + Sync + Send + 'static> ThresholdVerifier<T> {
     fn chain_id(&self) -> ExtendedChainId {
         self.verifiers
@@ -101,6 +156,18 @@ impl<T: Identifiable + Verifier + Sync + Send + 'static> ThresholdVerifier<T> {
         auth_contract_id: String,
         method_name: String,
         input_data: InputData,
+    ) -> Result<bool, VerificationError> {
+        self.verify_at(auth_contract_id, method_name, input_data, None)
+            .await
+    }
+
+    /// Same as [`Self::verify`], against the state at `at` if given, see [`Verifier::verify_at`].
+    pub async fn verify_at(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
     ) -> Result<bool, VerificationError> {
         let auth_contract_id_ = auth_contract_id.clone();
         let method_name_ = method_name.clone();
@@ -111,19 +178,127 @@ impl<T: Identifiable + Verifier + Sync + Send + 'static> ThresholdVerifier<T> {
             let input_data = input_data.clone();
             async move {
                 verifier
-                    .verify(auth_contract_id, method_name, input_data)
+                    .verify_at(auth_contract_id, method_name, input_data, at)
                     .await
             }
         })
         .await
-        .map_err(|kind| VerificationError {
-            chain_id: self.chain_id(),
-            auth_contract_id: auth_contract_id_,
-            method_name: method_name_,
-            input_data: input_data_,
-            kind,
+        .map_err(|kind| {
+            VerificationError::new(
+                self.chain_id(),
+                auth_contract_id_,
+                method_name_,
+                input_data_,
+                kind,
+            )
         })
     }
+
+    /// See [`Verifier::call`].
+    pub async fn call(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> Result<ReturnValue, VerificationError> {
+        let auth_contract_id_ = auth_contract_id.clone();
+        let method_name_ = method_name.clone();
+        let input_data_ = input_data.clone();
+        self.threshold_call(move |verifier| {
+            let auth_contract_id = auth_contract_id.clone();
+            let method_name = method_name.clone();
+            let input_data = input_data.clone();
+            async move {
+                verifier
+                    .call(auth_contract_id, method_name, input_data, at)
+                    .await
+            }
+        })
+        .await
+        .map_err(|kind| {
+            VerificationError::new(
+                self.chain_id(),
+                auth_contract_id_,
+                method_name_,
+                input_data_,
+                kind,
+            )
+        })
+    }
+
+    /// Executes the auth call, and checks the result against its expectation if there's one,
+    /// otherwise applies the implicit rule of the chain (see [`Self::verify_at`]).
+    /// Token balance and transaction inputs are checked by the built-in rules.
+    /// The auth call is expected to pass [`crate::policy::ensure_well_formed`].
+    pub async fn verify_auth_call(
+        &self,
+        auth_call: HotVerifyAuthCall,
+        at: Option<u64>,
+    ) -> anyhow::Result<bool> {
+        let HotVerifyAuthCall {
+            contract_id,
+            method,
+            input,
+            expectation,
+            ..
+        } = auth_call;
+        if let InputData::TokenBalance(input) = input {
+            let balance = self
+                .token_balance(contract_id, input.holder.clone(), at)
+                .await?;
+            return input.is_satisfied_by(&balance);
+        }
+        if let InputData::Transaction(input) = input {
+            return self.is_transaction_successful(input).await;
+        }
+        let Some(expectation) = expectation else {
+            return Ok(self.verify_at(contract_id, method, input, at).await?);
+        };
+        let returned = self.call(contract_id, method, input, at).await?;
+        expectation.evaluate(&returned)
+    }
+
+    /// See [`Verifier::token_balance`].
+    pub async fn token_balance(
+        &self,
+        token_id: String,
+        holder: String,
+        at: Option<u64>,
+    ) -> anyhow::Result<ReturnValue> {
+        self.threshold_call(move |verifier| {
+            let token_id = token_id.clone();
+            let holder = holder.clone();
+            async move { verifier.token_balance(token_id, holder, at).await }
+        })
+        .await
+    }
+
+    /// See [`Verifier::is_transaction_successful`].
+    pub async fn is_transaction_successful(
+        &self,
+        input: TransactionInputData,
+    ) -> anyhow::Result<bool> {
+        self.threshold_call(move |verifier| {
+            let input = input.clone();
+            async move { verifier.is_transaction_successful(input).await }
+        })
+        .await
+    }
+
+    /// See [`Verifier::is_nonce_used`].
+    pub async fn is_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
+    ) -> anyhow::Result<bool> {
+        self.threshold_call(move |verifier| {
+            let contract_id = contract_id.clone();
+            let withdrawal = withdrawal.clone();
+            async move { verifier.is_nonce_used(contract_id, withdrawal).await }
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,110 @@
+//! Bech32 account addresses: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>.
+use anyhow::{ensure, Context, Result};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATORS: [u32; 5] = [
+    0x3b6a_57b2,
+    0x2650_8e6d,
+    0x1ea1_19fa,
+    0x3d42_33dd,
+    0x2a14_62b3,
+];
+const CHECKSUM_LEN: usize = 6;
+
+/// Bytes of a bech32 `address`, e.g. the 32 bytes of a `juno1...` contract.
+pub(super) fn address_bytes(address: &str) -> Result<Vec<u8>> {
+    ensure!(
+        address == address.to_lowercase() || address == address.to_uppercase(),
+        "Mixed-case bech32 address {address}"
+    );
+    let lowercase = address.to_lowercase();
+    let (hrp, data) = lowercase
+        .rsplit_once('1')
+        .with_context(|| format!("No bech32 separator in {address}"))?;
+    ensure!(
+        !hrp.is_empty() && data.len() >= CHECKSUM_LEN,
+        "Invalid bech32 address {address}"
+    );
+    let values = data
+        .bytes()
+        .map(|byte| {
+            CHARSET
+                .iter()
+                .position(|c| *c == byte)
+                .and_then(|value| u8::try_from(value).ok())
+                .with_context(|| format!("Invalid bech32 character {}", char::from(byte)))
+        })
+        .collect::<Result<Vec<u8>>>()?;
+
+    let checked: Vec<u8> = hrp
+        .bytes()
+        .map(|c| c >> 5)
+        .chain([0])
+        .chain(hrp.bytes().map(|c| c & 0x1f))
+        .chain(values.iter().copied())
+        .collect();
+    ensure!(
+        polymod(&checked) == 1,
+        "Invalid bech32 checksum of {address}"
+    );
+
+    // Regroup the 5-bit values into bytes
+    let mut bytes = vec![];
+    let (mut acc, mut bits) = (0u32, 0);
+    for value in &values[..values.len() - CHECKSUM_LEN] {
+        acc = ((acc << 5) | u32::from(*value)) & 0x1fff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push(u8::try_from((acc >> bits) & 0xff)?);
+        }
+    }
+    ensure!(
+        bits < 5 && acc & ((1 << bits) - 1) == 0,
+        "Invalid bech32 padding of {address}"
+    );
+    Ok(bytes)
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ff_ffff) << 5) ^ u32::from(*value);
+        for (i, generator) in GENERATORS.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::address_bytes;
+    use anyhow::Result;
+
+    #[test]
+    fn contract_address() -> Result<()> {
+        let address = "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4";
+        assert_eq!(
+            hex::encode(address_bytes(address)?),
+            "674a0f237dd7f4ae800539d08bb2a3f72b79fd1b3d210dc1a92902ef7809775e"
+        );
+        assert_eq!(
+            address_bytes(&address.to_uppercase())?,
+            address_bytes(address)?
+        );
+        // Last character changed
+        assert!(
+            address_bytes("juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea5")
+                .is_err()
+        );
+        assert!(
+            address_bytes("juno1Va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4")
+                .is_err()
+        );
+        Ok(())
+    }
+}
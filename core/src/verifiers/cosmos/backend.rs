@@ -0,0 +1,216 @@
+//! APIs a [`CosmosVerifier`](super::CosmosVerifier) can query CosmWasm contracts through,
+//! selected per server by [`RpcDialect::of_server`]:
+//! * `cometbft+https://...`: CometBFT RPC `abci_query`,
+//! * anything else: the LCD REST API, e.g. `https://juno-api.polkachu.com`.
+use crate::http_client::{get_json, get_json_with_headers};
+use crate::verifiers::cosmos::comet::CometBft;
+use crate::verifiers::cosmos::ics23::{ProofOp, StoreProof};
+use anyhow::{ensure, Context, Result};
+use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use hot_validation_primitives::{ChainId, RpcDialect};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Header that makes the LCD execute a query against the given height.
+const BLOCK_HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+#[async_trait]
+pub(crate) trait CosmosBackend: Send + Sync {
+    async fn latest_height(&self) -> Result<u64>;
+
+    /// Runs the JSON smart `query` of `contract` at `height`, the latest one if `None`,
+    /// returns the JSON response.
+    async fn smart_query(
+        &self,
+        contract: &str,
+        query: &[u8],
+        height: Option<u64>,
+    ) -> Result<Vec<u8>>;
+
+    /// App hash of the header at `height`, which commits to the state after `height - 1`.
+    async fn app_hash(&self, height: u64) -> Result<Vec<u8>>;
+
+    /// Raw value of `key` in the module `store` at `height`, with its ICS-23 proof.
+    async fn store_proof(&self, store: &str, key: &[u8], height: u64) -> Result<StoreProof>;
+}
+
+pub(crate) fn connect(
+    client: Arc<reqwest::Client>,
+    server: &str,
+    chain_id: ChainId,
+) -> Box<dyn CosmosBackend> {
+    match RpcDialect::CosmosLcd.of_server(server) {
+        (RpcDialect::CometBftRpc, url) => Box::new(CometBft::new(client, url, chain_id)),
+        (_, url) => Box::new(Lcd {
+            client,
+            server: url.to_string(),
+            chain_id,
+        }),
+    }
+}
+
+/// `ResponseQuery` of ABCI, as both CometBFT RPC and the LCD return it.
+#[derive(Deserialize)]
+pub(super) struct AbciQueryResponse {
+    code: u32,
+    #[serde(default)]
+    log: String,
+    /// Base64, `null` for empty values.
+    value: Option<String>,
+    #[serde(default, alias = "proofOps")]
+    proof_ops: Option<ProofOps>,
+    #[serde(default)]
+    height: String,
+}
+
+#[derive(Deserialize)]
+struct ProofOps {
+    ops: Vec<RawProofOp>,
+}
+
+/// [`ProofOp`] with base64 `key` and `data`.
+#[derive(Deserialize)]
+struct RawProofOp {
+    #[serde(rename = "type")]
+    kind: String,
+    key: String,
+    data: String,
+}
+
+impl AbciQueryResponse {
+    pub(super) fn value(&self) -> Result<Vec<u8>> {
+        ensure!(
+            self.code == 0,
+            "ABCI query failed with code {}: {}",
+            self.code,
+            self.log
+        );
+        let value = match &self.value {
+            Some(value) => BASE64_STANDARD.decode(value)?,
+            None => vec![],
+        };
+        Ok(value)
+    }
+
+    /// The proven value of a store query made at `height`, an empty value is an absent key.
+    pub(super) fn into_store_proof(self, height: u64) -> Result<StoreProof> {
+        let value = self.value()?;
+        ensure!(
+            self.height == height.to_string(),
+            "ABCI query answered at height {} instead of {height}",
+            self.height
+        );
+        let ops = self
+            .proof_ops
+            .context("ABCI query returned no proof")?
+            .ops
+            .into_iter()
+            .map(|op| {
+                Ok(ProofOp {
+                    kind: op.kind,
+                    key: BASE64_STANDARD.decode(op.key)?,
+                    data: BASE64_STANDARD.decode(op.data)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(StoreProof {
+            value: (!value.is_empty()).then_some(value),
+            ops,
+        })
+    }
+}
+
+/// The LCD REST API of the Cosmos SDK.
+struct Lcd {
+    client: Arc<reqwest::Client>,
+    server: String,
+    chain_id: ChainId,
+}
+
+#[derive(Deserialize)]
+struct LcdHeader {
+    height: String,
+    /// Base64.
+    app_hash: String,
+}
+
+impl Lcd {
+    /// Header of the block at `height`, the latest one if `None`.
+    async fn header(&self, height: Option<u64>) -> Result<LcdHeader> {
+        #[derive(Deserialize)]
+        struct Block {
+            header: LcdHeader,
+        }
+        #[derive(Deserialize)]
+        struct Response {
+            block: Block,
+        }
+
+        let height = height.map_or_else(|| "latest".to_string(), |height| height.to_string());
+        let url = format!(
+            "{}/cosmos/base/tendermint/v1beta1/blocks/{height}",
+            self.server
+        );
+        let response: Response = get_json(&self.client, &url, self.chain_id).await?;
+        Ok(response.block.header)
+    }
+}
+
+#[async_trait]
+impl CosmosBackend for Lcd {
+    async fn latest_height(&self) -> Result<u64> {
+        self.header(None)
+            .await?
+            .height
+            .parse()
+            .context("Invalid block height")
+    }
+
+    async fn smart_query(
+        &self,
+        contract: &str,
+        query: &[u8],
+        height: Option<u64>,
+    ) -> Result<Vec<u8>> {
+        #[derive(Deserialize)]
+        struct Response {
+            data: serde_json::Value,
+        }
+        let url = format!(
+            "{}/cosmwasm/wasm/v1/contract/{}/smart/{}",
+            self.server,
+            contract,
+            BASE64_STANDARD.encode(query)
+        );
+        let headers = match height {
+            Some(height) => vec![(BLOCK_HEIGHT_HEADER, height.to_string())],
+            None => vec![],
+        };
+        let response: Response =
+            get_json_with_headers(&self.client, &url, &headers, self.chain_id).await?;
+        Ok(serde_json::to_vec(&response.data)?)
+    }
+
+    async fn app_hash(&self, height: u64) -> Result<Vec<u8>> {
+        let header = self.header(Some(height)).await?;
+        Ok(BASE64_STANDARD.decode(header.app_hash)?)
+    }
+
+    /// `ABCIQuery` of the Tendermint service, available since Cosmos SDK 0.46.
+    async fn store_proof(&self, store: &str, key: &[u8], height: u64) -> Result<StoreProof> {
+        let url = reqwest::Url::parse_with_params(
+            &format!("{}/cosmos/base/tendermint/v1beta1/abci_query", self.server),
+            [
+                ("path", format!("/store/{store}/key")),
+                ("data", BASE64_STANDARD.encode(key)),
+                ("height", height.to_string()),
+                ("prove", "true".to_string()),
+            ],
+        )?;
+        let response: AbciQueryResponse =
+            get_json(&self.client, url.as_str(), self.chain_id).await?;
+        response.into_store_proof(height)
+    }
+}
@@ -0,0 +1,182 @@
+//! CometBFT RPC: <https://docs.cometbft.com/v0.38/rpc/>.
+//! Contract queries go through `abci_query` with the protobuf-encoded gRPC request
+//! of the wasm module, store proofs with a `/store/{store}/key` query.
+use crate::http_client::post_json_receive_json;
+use crate::verifiers::cosmos::backend::{AbciQueryResponse, CosmosBackend};
+use crate::verifiers::cosmos::ics23::StoreProof;
+use crate::verifiers::cosmos::protobuf::{ProtoReader, ProtoWriter};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use hot_validation_primitives::ChainId;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+const SMART_CONTRACT_STATE_PATH: &str = "/cosmwasm.wasm.v1.Query/SmartContractState";
+
+pub(super) struct CometBft {
+    client: Arc<reqwest::Client>,
+    server: String,
+    chain_id: ChainId,
+}
+
+#[derive(Deserialize)]
+struct AbciQueryResult {
+    response: AbciQueryResponse,
+}
+
+impl CometBft {
+    pub(super) fn new(client: Arc<reqwest::Client>, server: &str, chain_id: ChainId) -> Self {
+        Self {
+            client,
+            server: server.trim_end_matches('/').to_string(),
+            chain_id,
+        }
+    }
+
+    async fn rpc<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T> {
+        #[derive(Deserialize)]
+        struct Response<T> {
+            result: Option<T>,
+            error: Option<serde_json::Value>,
+        }
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: Response<T> =
+            post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
+        match response.result {
+            Some(result) => Ok(result),
+            None => bail!("CometBFT {method} failed: {:?}", response.error),
+        }
+    }
+
+    /// `abci_query` of `path` with `data` at `height`, the latest one if `None`.
+    async fn abci_query(
+        &self,
+        path: &str,
+        data: &[u8],
+        height: Option<u64>,
+        prove: bool,
+    ) -> Result<AbciQueryResponse> {
+        let mut params = json!({
+            "path": path,
+            "data": hex::encode(data),
+            "prove": prove,
+        });
+        if let Some(height) = height {
+            params["height"] = json!(height.to_string());
+        }
+        let result: AbciQueryResult = self.rpc("abci_query", params).await?;
+        Ok(result.response)
+    }
+}
+
+#[async_trait]
+impl CosmosBackend for CometBft {
+    async fn latest_height(&self) -> Result<u64> {
+        #[derive(Deserialize)]
+        struct SyncInfo {
+            latest_block_height: String,
+        }
+        #[derive(Deserialize)]
+        struct Status {
+            sync_info: SyncInfo,
+        }
+        let status: Status = self.rpc("status", json!({})).await?;
+        status
+            .sync_info
+            .latest_block_height
+            .parse()
+            .context("Invalid block height")
+    }
+
+    /// `QuerySmartContractStateRequest { address = 1, query_data = 2 }`,
+    /// `QuerySmartContractStateResponse { data = 1 }`.
+    async fn smart_query(
+        &self,
+        contract: &str,
+        query: &[u8],
+        height: Option<u64>,
+    ) -> Result<Vec<u8>> {
+        let mut request = ProtoWriter::default();
+        request.string(1, contract).bytes(2, query);
+        let response = self
+            .abci_query(
+                SMART_CONTRACT_STATE_PATH,
+                &request.into_inner(),
+                height,
+                false,
+            )
+            .await?
+            .value()?;
+        let mut reader = ProtoReader::new(&response);
+        while let Some((field, value)) = reader.field()? {
+            if field == 1 {
+                return Ok(value.as_bytes()?.to_vec());
+            }
+        }
+        bail!("Smart query of {contract} returned no data")
+    }
+
+    async fn app_hash(&self, height: u64) -> Result<Vec<u8>> {
+        #[derive(Deserialize)]
+        struct Header {
+            /// Hex.
+            app_hash: String,
+        }
+        #[derive(Deserialize)]
+        struct SignedHeader {
+            header: Header,
+        }
+        #[derive(Deserialize)]
+        struct Commit {
+            signed_header: SignedHeader,
+        }
+        let commit: Commit = self
+            .rpc("commit", json!({ "height": height.to_string() }))
+            .await?;
+        Ok(hex::decode(commit.signed_header.header.app_hash)?)
+    }
+
+    async fn store_proof(&self, store: &str, key: &[u8], height: u64) -> Result<StoreProof> {
+        self.abci_query(&format!("/store/{store}/key"), key, Some(height), true)
+            .await?
+            .into_store_proof(height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CometBft;
+    use crate::verifiers::cosmos::backend::CosmosBackend;
+    use anyhow::Result;
+    use hot_validation_primitives::ChainId;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn smart_query() -> Result<()> {
+        let rpc = CometBft::new(
+            Arc::new(reqwest::Client::new()),
+            "https://juno-rpc.polkachu.com",
+            ChainId::Evm(4444_118),
+        );
+        let height = rpc.latest_height().await?;
+        let query = serde_json::to_vec(&serde_json::json!({
+            "is_executed": { "nonce": "1764027631000000481371" }
+        }))?;
+        let data = rpc
+            .smart_query(
+                "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4",
+                &query,
+                Some(height - 1),
+            )
+            .await?;
+        assert!(serde_json::from_slice::<bool>(&data)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,534 @@
+//! ICS-23 commitment proofs of the Cosmos SDK stores:
+//! <https://github.com/cosmos/ics23/blob/master/proto/cosmos/ics23/v1/proofs.proto>.
+//!
+//! A value of a module store is proven by two operations: an IAVL proof up to the root of
+//! the store, and a proof of that root in the simple Merkle tree of the multistore, whose
+//! root is the app hash. Only these two proof specs are supported.
+use crate::verifiers::cosmos::protobuf::{ProtoReader, ProtoWriter};
+use anyhow::{bail, ensure, Context, Result};
+use sha2::{Digest, Sha256};
+
+const HASH_OP_NONE: u64 = 0;
+const HASH_OP_SHA256: u64 = 1;
+const LENGTH_OP_VAR_PROTO: u64 = 1;
+/// First byte of the leaf prefix, inner nodes can't start with it.
+const LEAF_PREFIX: u8 = 0;
+/// Both trees are binary, with `child_order = [0, 1]`.
+const CHILDREN: usize = 2;
+
+const IAVL_PROOF: &str = "ics23:iavl";
+const SIMPLE_PROOF: &str = "ics23:simple";
+
+/// The parts of an ICS-23 `ProofSpec` that differ between the supported trees. Both hash
+/// with SHA-256 and have leaves `sha256(prefix || len(key) || key || len(h) || h)`,
+/// where `h = sha256(value)`.
+struct Spec {
+    child_size: usize,
+    min_prefix_length: usize,
+    max_prefix_length: usize,
+}
+
+const IAVL_SPEC: Spec = Spec {
+    child_size: 33,
+    min_prefix_length: 4,
+    max_prefix_length: 12,
+};
+
+const TENDERMINT_SPEC: Spec = Spec {
+    child_size: 32,
+    min_prefix_length: 1,
+    max_prefix_length: 1,
+};
+
+/// `ProofOp` of an ABCI query response, `data` is a protobuf `CommitmentProof`.
+pub(crate) struct ProofOp {
+    pub(crate) kind: String,
+    pub(crate) key: Vec<u8>,
+    pub(crate) data: Vec<u8>,
+}
+
+/// A value of a module store with its proof, `None` if the store doesn't have the key.
+pub(crate) struct StoreProof {
+    pub(crate) value: Option<Vec<u8>>,
+    pub(crate) ops: Vec<ProofOp>,
+}
+
+impl StoreProof {
+    /// Checks that the proof commits to the value of `key` in the module `store`
+    /// under `app_hash`.
+    pub(crate) fn verify(&self, store: &str, key: &[u8], app_hash: &[u8]) -> Result<()> {
+        let [iavl, simple] = self.ops.as_slice() else {
+            bail!("Expected 2 proof operations, got {}", self.ops.len());
+        };
+        ensure!(
+            iavl.kind == IAVL_PROOF && iavl.key == key,
+            "Unexpected store proof operation {}",
+            iavl.kind
+        );
+        ensure!(
+            simple.kind == SIMPLE_PROOF && simple.key == store.as_bytes(),
+            "Unexpected multistore proof operation {}",
+            simple.kind
+        );
+
+        let store_root = match (CommitmentProof::decode(&iavl.data)?, &self.value) {
+            (CommitmentProof::Exist(proof), Some(value)) => {
+                ensure!(
+                    proof.key == key && &proof.value == value,
+                    "Store proof is of another entry"
+                );
+                proof.root(&IAVL_SPEC)?
+            }
+            (CommitmentProof::NonExist(proof), None) => {
+                ensure!(proof.key == key, "Store proof is of another key");
+                proof.root(&IAVL_SPEC)?
+            }
+            (CommitmentProof::Exist(_), None) => bail!("Absent key proven to exist"),
+            (CommitmentProof::NonExist(_), Some(_)) => bail!("Present key proven to be absent"),
+        };
+
+        let CommitmentProof::Exist(proof) = CommitmentProof::decode(&simple.data)? else {
+            bail!("Expected a proof of the {store} store root");
+        };
+        ensure!(
+            proof.key == store.as_bytes() && proof.value == store_root,
+            "Multistore proof is of another store root"
+        );
+        let root = proof.root(&TENDERMINT_SPEC)?;
+        ensure!(
+            root.as_slice() == app_hash,
+            "Proof root {} doesn't match the app hash {}",
+            hex::encode(root),
+            hex::encode(app_hash)
+        );
+        Ok(())
+    }
+}
+
+enum CommitmentProof {
+    Exist(ExistenceProof),
+    NonExist(NonExistenceProof),
+}
+
+impl CommitmentProof {
+    fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = ProtoReader::new(data);
+        while let Some((field, value)) = reader.field()? {
+            match field {
+                1 => return Ok(Self::Exist(ExistenceProof::decode(value.as_bytes()?)?)),
+                2 => {
+                    return Ok(Self::NonExist(NonExistenceProof::decode(
+                        value.as_bytes()?,
+                    )?))
+                }
+                3 | 4 => bail!("Batch and compressed ICS-23 proofs are not supported"),
+                _ => {}
+            }
+        }
+        bail!("Empty ICS-23 commitment proof")
+    }
+}
+
+#[derive(Default)]
+struct ExistenceProof {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    leaf: LeafOp,
+    /// From the leaf up to the root.
+    path: Vec<InnerOp>,
+}
+
+impl ExistenceProof {
+    fn decode(data: &[u8]) -> Result<Self> {
+        let mut proof = Self::default();
+        let mut reader = ProtoReader::new(data);
+        while let Some((field, value)) = reader.field()? {
+            match field {
+                1 => proof.key = value.as_bytes()?.to_vec(),
+                2 => proof.value = value.as_bytes()?.to_vec(),
+                3 => proof.leaf = LeafOp::decode(value.as_bytes()?)?,
+                4 => proof.path.push(InnerOp::decode(value.as_bytes()?)?),
+                _ => {}
+            }
+        }
+        Ok(proof)
+    }
+
+    /// Root of the tree of `spec` the proof commits to.
+    fn root(&self, spec: &Spec) -> Result<[u8; 32]> {
+        self.leaf.check()?;
+        let mut hash = self.leaf.apply(&self.key, &self.value);
+        for step in &self.path {
+            step.check(spec)?;
+            hash = step.apply(&hash);
+        }
+        Ok(hash)
+    }
+}
+
+/// Absence of `key`, proven by the existence of its neighbors in the sorted tree.
+#[derive(Default)]
+struct NonExistenceProof {
+    key: Vec<u8>,
+    left: Option<ExistenceProof>,
+    right: Option<ExistenceProof>,
+}
+
+impl NonExistenceProof {
+    fn decode(data: &[u8]) -> Result<Self> {
+        let mut proof = Self::default();
+        let mut reader = ProtoReader::new(data);
+        while let Some((field, value)) = reader.field()? {
+            match field {
+                1 => proof.key = value.as_bytes()?.to_vec(),
+                2 => proof.left = Some(ExistenceProof::decode(value.as_bytes()?)?),
+                3 => proof.right = Some(ExistenceProof::decode(value.as_bytes()?)?),
+                _ => {}
+            }
+        }
+        Ok(proof)
+    }
+
+    fn root(&self, spec: &Spec) -> Result<[u8; 32]> {
+        match (&self.left, &self.right) {
+            (Some(left), Some(right)) => {
+                ensure!(
+                    left.key < self.key && self.key < right.key,
+                    "ICS-23 neighbors don't surround the key"
+                );
+                let root = left.root(spec)?;
+                ensure!(
+                    right.root(spec)? == root,
+                    "ICS-23 neighbors are proven against different roots"
+                );
+                ensure_adjacent(spec, &left.path, &right.path)?;
+                Ok(root)
+            }
+            (Some(left), None) => {
+                ensure!(left.key < self.key, "ICS-23 left neighbor is after the key");
+                ensure_outermost(spec, &left.path, CHILDREN - 1)?;
+                left.root(spec)
+            }
+            (None, Some(right)) => {
+                ensure!(
+                    self.key < right.key,
+                    "ICS-23 right neighbor is before the key"
+                );
+                ensure_outermost(spec, &right.path, 0)?;
+                right.root(spec)
+            }
+            (None, None) => bail!("ICS-23 non-existence proof without neighbors"),
+        }
+    }
+}
+
+/// Every step of `path` hashes its `branch` child, i.e. the leaf is the leftmost (`0`)
+/// or the rightmost one.
+fn ensure_outermost(spec: &Spec, path: &[InnerOp], branch: usize) -> Result<()> {
+    ensure!(
+        path.iter().all(|step| step.has_padding(spec, branch)),
+        "ICS-23 neighbor isn't the outermost leaf"
+    );
+    Ok(())
+}
+
+/// The leaves of `left` and `right` are next to each other: right below the node where the
+/// paths split they are adjacent children, and below that they are the outermost leaves.
+fn ensure_adjacent(spec: &Spec, left: &[InnerOp], right: &[InnerOp]) -> Result<()> {
+    let shared = left
+        .iter()
+        .rev()
+        .zip(right.iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let (Some((left_split, left)), Some((right_split, right))) = (
+        left[..left.len() - shared].split_last(),
+        right[..right.len() - shared].split_last(),
+    ) else {
+        bail!("ICS-23 neighbor paths don't split");
+    };
+    ensure!(
+        left_split.branch(spec)? + 1 == right_split.branch(spec)?,
+        "ICS-23 neighbors aren't adjacent"
+    );
+    ensure_outermost(spec, left, CHILDREN - 1)?;
+    ensure_outermost(spec, right, 0)
+}
+
+#[derive(Default)]
+struct LeafOp {
+    hash: u64,
+    prehash_key: u64,
+    prehash_value: u64,
+    length: u64,
+    prefix: Vec<u8>,
+}
+
+impl LeafOp {
+    fn decode(data: &[u8]) -> Result<Self> {
+        let mut leaf = Self::default();
+        let mut reader = ProtoReader::new(data);
+        while let Some((field, value)) = reader.field()? {
+            match field {
+                1 => leaf.hash = value.as_varint()?,
+                2 => leaf.prehash_key = value.as_varint()?,
+                3 => leaf.prehash_value = value.as_varint()?,
+                4 => leaf.length = value.as_varint()?,
+                5 => leaf.prefix = value.as_bytes()?.to_vec(),
+                _ => {}
+            }
+        }
+        Ok(leaf)
+    }
+
+    fn check(&self) -> Result<()> {
+        ensure!(
+            self.hash == HASH_OP_SHA256
+                && self.prehash_key == HASH_OP_NONE
+                && self.prehash_value == HASH_OP_SHA256
+                && self.length == LENGTH_OP_VAR_PROTO,
+            "Unsupported ICS-23 leaf operation"
+        );
+        ensure!(
+            self.prefix.first() == Some(&LEAF_PREFIX),
+            "ICS-23 leaf doesn't have the leaf prefix"
+        );
+        Ok(())
+    }
+
+    fn apply(&self, key: &[u8], value: &[u8]) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(&self.prefix)
+            .chain_update(length_prefixed(key))
+            .chain_update(length_prefixed(&Sha256::digest(value)))
+            .finalize()
+            .into()
+    }
+}
+
+#[derive(Default, PartialEq, Eq)]
+struct InnerOp {
+    hash: u64,
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+}
+
+impl InnerOp {
+    fn decode(data: &[u8]) -> Result<Self> {
+        let mut inner = Self::default();
+        let mut reader = ProtoReader::new(data);
+        while let Some((field, value)) = reader.field()? {
+            match field {
+                1 => inner.hash = value.as_varint()?,
+                2 => inner.prefix = value.as_bytes()?.to_vec(),
+                3 => inner.suffix = value.as_bytes()?.to_vec(),
+                _ => {}
+            }
+        }
+        Ok(inner)
+    }
+
+    fn check(&self, spec: &Spec) -> Result<()> {
+        let max_siblings = (CHILDREN - 1) * spec.child_size;
+        ensure!(
+            self.hash == HASH_OP_SHA256,
+            "Unsupported ICS-23 inner hash {}",
+            self.hash
+        );
+        ensure!(
+            self.prefix.first() != Some(&LEAF_PREFIX),
+            "ICS-23 inner node has the leaf prefix"
+        );
+        ensure!(
+            (spec.min_prefix_length..=spec.max_prefix_length + max_siblings)
+                .contains(&self.prefix.len()),
+            "ICS-23 inner prefix of unexpected length {}",
+            self.prefix.len()
+        );
+        ensure!(
+            self.suffix.len() % spec.child_size == 0 && self.suffix.len() <= max_siblings,
+            "ICS-23 inner suffix of unexpected length {}",
+            self.suffix.len()
+        );
+        Ok(())
+    }
+
+    fn apply(&self, child: &[u8]) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(&self.prefix)
+            .chain_update(child)
+            .chain_update(&self.suffix)
+            .finalize()
+            .into()
+    }
+
+    /// Whether the hashed child is the `branch` one, judging by the siblings around it.
+    fn has_padding(&self, spec: &Spec, branch: usize) -> bool {
+        let before = branch * spec.child_size;
+        let after = (CHILDREN - 1 - branch) * spec.child_size;
+        (before + spec.min_prefix_length..=before + spec.max_prefix_length)
+            .contains(&self.prefix.len())
+            && self.suffix.len() == after
+    }
+
+    fn branch(&self, spec: &Spec) -> Result<usize> {
+        (0..CHILDREN)
+            .find(|branch| self.has_padding(spec, *branch))
+            .context("ICS-23 inner node of no branch")
+    }
+}
+
+/// `data` prefixed with its length as a varint (`LengthOp::VAR_PROTO`).
+fn length_prefixed(data: &[u8]) -> Vec<u8> {
+    let mut writer = ProtoWriter::default();
+    writer.varint(u64::try_from(data.len()).expect("Length fits in 64 bits"));
+    let mut bytes = writer.into_inner();
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProofOp, StoreProof, IAVL_PROOF, SIMPLE_PROOF};
+    use crate::verifiers::cosmos::protobuf::ProtoWriter;
+    use anyhow::Result;
+    use sha2::{Digest, Sha256};
+
+    /// Height 0, size 1 and version 1 as zigzag varints.
+    const IAVL_LEAF: [u8; 3] = [0x00, 0x02, 0x02];
+    /// Height 1, size 2 and version 1 as zigzag varints.
+    const IAVL_INNER: [u8; 3] = [0x02, 0x04, 0x02];
+    /// `hash = SHA256`, the varint field 1 of leaf and inner operations.
+    const SHA256_HASH_OP: [u8; 2] = [0x08, 1];
+    /// `prehash_value = SHA256` and `length = VAR_PROTO` of leaf operations.
+    const LEAF_OPS: [u8; 4] = [0x18, 1, 0x20, 1];
+
+    fn leaf_hash(prefix: &[u8], key: &[u8], value: &[u8]) -> Vec<u8> {
+        let key_len = [u8::try_from(key.len()).unwrap()];
+        Sha256::digest([prefix, &key_len, key, &[32], &Sha256::digest(value)].concat()).to_vec()
+    }
+
+    fn field(number: u32, value: &[u8]) -> Vec<u8> {
+        let mut writer = ProtoWriter::default();
+        writer.bytes(number, value);
+        writer.into_inner()
+    }
+
+    /// `ExistenceProof` of a SHA-256 leaf with `prefix`, and a path of `(prefix, suffix)`.
+    fn existence(key: &[u8], value: &[u8], prefix: &[u8], path: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+        let leaf = [&SHA256_HASH_OP[..], &LEAF_OPS, &field(5, prefix)].concat();
+        let mut proof = [field(1, key), field(2, value), field(3, &leaf)].concat();
+        for (prefix, suffix) in path {
+            let step = [&SHA256_HASH_OP[..], &field(2, prefix), &field(3, suffix)].concat();
+            proof.extend(field(4, &step));
+        }
+        proof
+    }
+
+    fn non_existence(key: &[u8], left: Option<&[u8]>, right: Option<&[u8]>) -> Vec<u8> {
+        let mut proof = field(1, key);
+        if let Some(left) = left {
+            proof.extend(field(2, left));
+        }
+        if let Some(right) = right {
+            proof.extend(field(3, right));
+        }
+        field(2, &proof)
+    }
+
+    /// A wasm store with `a = true` and `c = false`, next to another store in the multistore.
+    struct Stores {
+        a: Vec<u8>,
+        c: Vec<u8>,
+        wasm_proof: Vec<u8>,
+        app_hash: Vec<u8>,
+    }
+
+    fn stores() -> Stores {
+        let a_hash = leaf_hash(&IAVL_LEAF, b"a", b"true");
+        let c_hash = leaf_hash(&IAVL_LEAF, b"c", b"false");
+        let a_path = (
+            [&IAVL_INNER[..], &[32]].concat(),
+            [&[32][..], &c_hash].concat(),
+        );
+        let c_path = ([&IAVL_INNER[..], &[32], &a_hash, &[32]].concat(), vec![]);
+        let wasm_root = Sha256::digest([&a_path.0[..], &a_hash, &a_path.1].concat());
+
+        let acc_hash = leaf_hash(&[0], b"acc", &[7; 32]);
+        let wasm_hash = leaf_hash(&[0], b"wasm", &wasm_root);
+        let app_hash = Sha256::digest([&[1][..], &acc_hash, &wasm_hash].concat()).to_vec();
+        let wasm_path = ([&[1][..], &acc_hash].concat(), vec![]);
+        Stores {
+            a: existence(b"a", b"true", &IAVL_LEAF, &[a_path]),
+            c: existence(b"c", b"false", &IAVL_LEAF, &[c_path]),
+            wasm_proof: field(1, &existence(b"wasm", &wasm_root, &[0], &[wasm_path])),
+            app_hash,
+        }
+    }
+
+    fn store_proof(key: &[u8], value: Option<&[u8]>, proof: Vec<u8>, wasm: Vec<u8>) -> StoreProof {
+        StoreProof {
+            value: value.map(<[u8]>::to_vec),
+            ops: vec![
+                ProofOp {
+                    kind: IAVL_PROOF.to_string(),
+                    key: key.to_vec(),
+                    data: proof,
+                },
+                ProofOp {
+                    kind: SIMPLE_PROOF.to_string(),
+                    key: b"wasm".to_vec(),
+                    data: wasm,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn existence_proof() -> Result<()> {
+        let stores = stores();
+        let proven = store_proof(
+            b"c",
+            Some(b"false"),
+            field(1, &stores.c),
+            stores.wasm_proof.clone(),
+        );
+        proven.verify("wasm", b"c", &stores.app_hash)?;
+        assert!(proven.verify("wasm", b"c", &[0; 32]).is_err());
+        assert!(proven.verify("bank", b"c", &stores.app_hash).is_err());
+
+        let forged = store_proof(b"c", Some(b"true"), field(1, &stores.c), stores.wasm_proof);
+        assert!(forged.verify("wasm", b"c", &stores.app_hash).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn non_existence_proof() -> Result<()> {
+        let stores = stores();
+        let verify = |key: &[u8], value: Option<&[u8]>, proof: Vec<u8>| {
+            store_proof(key, value, proof, stores.wasm_proof.clone()).verify(
+                "wasm",
+                key,
+                &stores.app_hash,
+            )
+        };
+
+        verify(
+            b"b",
+            None,
+            non_existence(b"b", Some(&stores.a), Some(&stores.c)),
+        )?;
+        verify(b"d", None, non_existence(b"d", Some(&stores.c), None))?;
+        verify(b"0", None, non_existence(b"0", None, Some(&stores.a)))?;
+
+        // An absent key can't be proven to have a value.
+        let proof = non_existence(b"b", Some(&stores.a), Some(&stores.c));
+        assert!(verify(b"b", Some(b"true"), proof).is_err());
+        // `a` isn't the rightmost key, so it can't prove that `d` is absent.
+        assert!(verify(b"d", None, non_existence(b"d", Some(&stores.a), None)).is_err());
+        // Neighbors in the wrong order.
+        let proof = non_existence(b"b", Some(&stores.c), Some(&stores.a));
+        assert!(verify(b"b", None, proof).is_err());
+        Ok(())
+    }
+}
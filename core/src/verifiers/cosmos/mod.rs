@@ -0,0 +1,435 @@
+mod address;
+mod backend;
+mod comet;
+mod ics23;
+mod protobuf;
+
+use crate::threshold_verifier::{Identifiable, ThresholdVerifier};
+use crate::verifiers::cosmos::backend::CosmosBackend;
+use crate::verifiers::Verifier;
+use anyhow::{bail, ensure, Context};
+use async_trait::async_trait;
+use hot_validation_primitives::bridge::cosmos::CosmosInputData;
+use hot_validation_primitives::bridge::expectation::ReturnValue;
+use hot_validation_primitives::bridge::transaction::TransactionInputData;
+use hot_validation_primitives::bridge::{CompletedWithdrawal, HotVerifyAuthCall, InputData};
+use hot_validation_primitives::{
+    ChainFamily, ChainId, ChainValidationConfig, ExtendedChainId, Finality,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Module store of CosmWasm.
+const WASM_STORE: &str = "wasm";
+/// Prefix of contract storage keys in the wasm store, followed by the contract address.
+const CONTRACT_STORE_PREFIX: u8 = 0x03;
+
+/// Cosmos chains have instant finality, so only `min_confirmations` of the [`Finality`] matters:
+/// the query is executed at `latest - min_confirmations`.
+/// `server` is an LCD URL, or a CometBFT RPC one prefixed with `cometbft+`.
+pub struct CosmosVerifier {
+    server: String,
+    chain_id: ChainId,
+    finality: Finality,
+    backend: Box<dyn CosmosBackend>,
+}
+
+impl CosmosVerifier {
+    pub fn new(
+        client: Arc<reqwest::Client>,
+        server: String,
+        chain_id: ChainId,
+        finality: Finality,
+    ) -> Self {
+        Self {
+            backend: backend::connect(client, &server, chain_id),
+            server,
+            chain_id,
+            finality,
+        }
+    }
+
+    /// Height to query at, `None` for the latest one.
+    async fn query_height(&self, at: Option<u64>) -> anyhow::Result<Option<u64>> {
+        if at.is_some() {
+            return Ok(at);
+        }
+        let min_confirmations = self.finality.min_confirmations();
+        if min_confirmations == 0 {
+            return Ok(None);
+        }
+        let latest = self.backend.latest_height().await?;
+        let height = latest
+            .checked_sub(min_confirmations)
+            .context("Not enough blocks for the required confirmations")?;
+        Ok(Some(height))
+    }
+
+    /// Height to read proven state at. The state after a block is committed by the app hash
+    /// of the next header, so the latest height can't be proven yet.
+    async fn proof_height(&self, at: Option<u64>) -> anyhow::Result<u64> {
+        if let Some(at) = at {
+            return Ok(at);
+        }
+        let latest = self.backend.latest_height().await?;
+        latest
+            .checked_sub(self.finality.min_confirmations().max(1))
+            .context("Not enough blocks for the required confirmations")
+    }
+
+    async fn smart_query<Q: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        auth_contract_id: &str,
+        query: &Q,
+        at: Option<u64>,
+    ) -> anyhow::Result<T> {
+        let height = self.query_height(at).await?;
+        let data = self
+            .backend
+            .smart_query(auth_contract_id, &serde_json::to_vec(query)?, height)
+            .await?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+}
+
+impl Identifiable for CosmosVerifier {
+    fn id(&self) -> String {
+        self.server.clone()
+    }
+}
+
+#[async_trait]
+impl Verifier for CosmosVerifier {
+    fn chain_id(&self) -> ExtendedChainId {
+        self.chain_id
+            .try_into()
+            .expect("Couldn't convert ChainId to ExtendedChainId")
+    }
+
+    async fn verify_at(
+        &self,
+        auth_contract_id: String,
+        _method_name: String, // method_name is being encoded in the input_data
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> anyhow::Result<bool> {
+        let input: CosmosInputData = input_data.try_into()?;
+        self.smart_query(&auth_contract_id, &input, at).await
+    }
+
+    async fn call(
+        &self,
+        auth_contract_id: String,
+        _method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> anyhow::Result<ReturnValue> {
+        let input: CosmosInputData = input_data.try_into()?;
+        self.smart_query(&auth_contract_id, &input, at).await
+    }
+
+    async fn token_balance(
+        &self,
+        token_id: String,
+        _holder: String,
+        _at: Option<u64>,
+    ) -> anyhow::Result<ReturnValue> {
+        anyhow::bail!("Token balances are not supported on Cosmos, got token {token_id}")
+    }
+
+    async fn is_transaction_successful(&self, input: TransactionInputData) -> anyhow::Result<bool> {
+        anyhow::bail!(
+            "Transaction proofs are not supported on Cosmos, got {:?}",
+            input.transaction
+        )
+    }
+
+    async fn is_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
+    ) -> anyhow::Result<bool> {
+        let input = CosmosInputData::IsExecuted {
+            nonce: withdrawal.nonce,
+        };
+        self.verify(contract_id, String::new(), InputData::Cosmos(input))
+            .await
+    }
+}
+
+impl ThresholdVerifier<CosmosVerifier> {
+    /// Same as [`ThresholdVerifier::verify_auth_call`], but also accepts storage reads:
+    /// a single verifier provides the entry with its ICS-23 proof, and the verifiers only
+    /// have to agree on the app hash the proof is checked against.
+    pub async fn verify_cosmos_auth_call(
+        &self,
+        auth_call: HotVerifyAuthCall,
+        at: Option<u64>,
+    ) -> anyhow::Result<bool> {
+        let InputData::CosmosStorage(query) = &auth_call.input else {
+            return self.verify_auth_call(auth_call, at).await;
+        };
+        let key = [
+            &[CONTRACT_STORE_PREFIX][..],
+            &address::address_bytes(&auth_call.contract_id)?,
+            &query.key,
+        ]
+        .concat();
+        let Some(value) = self.read_proven(&key, at).await? else {
+            ensure!(
+                auth_call.expectation.is_none(),
+                "No storage entry {:?} in {}",
+                auth_call.input,
+                auth_call.contract_id
+            );
+            return Ok(false);
+        };
+        match auth_call.expectation {
+            Some(expectation) => {
+                expectation.evaluate(&serde_json::from_slice::<ReturnValue>(&value)?)
+            }
+            None => match serde_json::from_slice::<serde_json::Value>(&value)? {
+                serde_json::Value::Bool(b) => Ok(b),
+                _ => Ok(true),
+            },
+        }
+    }
+
+    /// Proven value of `key` in the wasm store, from the first verifier that can prove it.
+    async fn read_proven(&self, key: &[u8], at: Option<u64>) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut errors = vec![];
+        for verifier in &self.verifiers {
+            match self.read_proven_from(verifier, key, at).await {
+                Ok(value) => return Ok(value),
+                Err(err) => errors.push(format!("{}: {err:#}", verifier.id())),
+            }
+        }
+        bail!("No verifier could prove the storage entry: {errors:#?}")
+    }
+
+    async fn read_proven_from(
+        &self,
+        verifier: &CosmosVerifier,
+        key: &[u8],
+        at: Option<u64>,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let height = verifier.proof_height(at).await?;
+        let proof = verifier
+            .backend
+            .store_proof(WASM_STORE, key, height)
+            .await?;
+        let app_hash = self
+            .threshold_call(
+                move |endpoint| async move { endpoint.backend.app_hash(height + 1).await },
+            )
+            .await?;
+        proof.verify(WASM_STORE, key, &app_hash)?;
+        tracing::debug!(
+            "Storage entry proven by {} at height {height}",
+            verifier.id()
+        );
+        Ok(proof.value)
+    }
+
+    pub fn new_cosmos(
+        config: ChainValidationConfig,
+        client: &Arc<reqwest::Client>,
+        chain_id: ChainId,
+    ) -> Self {
+        let threshold = config.threshold;
+        let finality = config.finality_or_default(ChainFamily::Cosmos);
+        let servers = config.servers;
+        let verifiers = servers
+            .into_iter()
+            .map(|url| Arc::new(CosmosVerifier::new(client.clone(), url, chain_id, finality)))
+            .collect();
+        Self {
+            threshold,
+            verifiers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::threshold_verifier::ThresholdVerifier;
+    use crate::verifiers::cosmos::CosmosVerifier;
+    use crate::verifiers::Verifier;
+    use anyhow::Result;
+    use base64::prelude::BASE64_STANDARD;
+    use base64::Engine;
+    use hot_validation_primitives::bridge::cosmos::{CosmosInputData, CosmosStorageQuery};
+    use hot_validation_primitives::bridge::expectation::ReturnValue;
+    use hot_validation_primitives::bridge::{HotVerifyAuthCall, InputData};
+    use hot_validation_primitives::{ChainFamily, ChainId, ChainValidationConfig, Finality};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_hot_verify() -> Result<()> {
+        let api = "https://juno-api.stakeandrelax.net";
+        let verifier = CosmosVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            api.to_string(),
+            ChainId::Evm(4444_118),
+            Finality::default_for(ChainFamily::Cosmos),
+        );
+
+        {
+            let address =
+                "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4".to_string();
+            let msg_hash_b64 = "utaIqDt2xuY7c2V+b2JU1B+I5dJ10EbaFzvmLpjpx+U=";
+            let nonce = 1764175051000000000008;
+            let input_data = InputData::Cosmos(CosmosInputData::HotVerify {
+                nonce,
+                msg_hash: BASE64_STANDARD.decode(msg_hash_b64)?.try_into().unwrap(),
+            });
+
+            let x = verifier.verify(address, String::new(), input_data).await?;
+            assert!(x);
+        }
+        {
+            // wrong addr
+            let address =
+                "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea5".to_string();
+            let msg_hash_b64 = "utaIqDt2xuY7c2V+b2JU1B+I5dJ10EbaFzvmLpjpx+U=";
+            let nonce = 1764175051000000000008;
+            let input_data = InputData::Cosmos(CosmosInputData::HotVerify {
+                nonce,
+                msg_hash: BASE64_STANDARD.decode(msg_hash_b64)?.try_into().unwrap(),
+            });
+
+            assert!(verifier
+                .verify(address, String::new(), input_data,)
+                .await
+                .is_err());
+        }
+        {
+            // wrong msg hash
+            let address =
+                "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4".to_string();
+            let msg_hash_b64 = "ftaIqDt2xuY7c2V+b2JU1B+I5dJ10EbaFzvmLpjpx+U=";
+            let nonce = 1764175051000000000008;
+            let input_data = InputData::Cosmos(CosmosInputData::HotVerify {
+                nonce,
+                msg_hash: BASE64_STANDARD.decode(msg_hash_b64)?.try_into().unwrap(),
+            });
+
+            assert!(!verifier.verify(address, String::new(), input_data,).await?);
+        }
+        {
+            // wrong nonce
+            let address =
+                "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4".to_string();
+            let msg_hash_b64 = "utaIqDt2xuY7c2V+b2JU1B+I5dJ10EbaFzvmLpjpx+U=";
+            let nonce = 1764175051000000000009;
+            let input_data = InputData::Cosmos(CosmosInputData::HotVerify {
+                nonce,
+                msg_hash: BASE64_STANDARD.decode(msg_hash_b64)?.try_into().unwrap(),
+            });
+
+            assert!(verifier
+                .verify(address, String::new(), input_data,)
+                .await
+                .is_err());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_is_executed() -> Result<()> {
+        let api = "https://juno-api.stakeandrelax.net";
+        let verifier = CosmosVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            api.to_string(),
+            ChainId::Evm(4444_118),
+            Finality::default_for(ChainFamily::Cosmos),
+        );
+
+        {
+            let address =
+                "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4".to_string();
+            let input_data = InputData::Cosmos(CosmosInputData::IsExecuted {
+                nonce: 1764027631000000481371,
+            });
+
+            let x = verifier.verify(address, String::new(), input_data).await?;
+            assert!(x);
+        }
+        {
+            let address =
+                "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4".to_string();
+            let input_data = InputData::Cosmos(CosmosInputData::IsExecuted {
+                nonce: 2764027631000000481371,
+            });
+            let x = verifier.verify(address, String::new(), input_data).await?;
+            assert!(!x);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_call_is_executed() -> Result<()> {
+        let api = "https://juno-api.stakeandrelax.net";
+        let verifier = CosmosVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            api.to_string(),
+            ChainId::Evm(4444_118),
+            Finality::default_for(ChainFamily::Cosmos),
+        );
+
+        let address = "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4".to_string();
+        let input_data = InputData::Cosmos(CosmosInputData::IsExecuted {
+            nonce: 1764027631000000481371,
+        });
+        let x = verifier
+            .call(address, String::new(), input_data, None)
+            .await?;
+        assert_eq!(x, ReturnValue::Bool(true));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_proven_storage_read() -> Result<()> {
+        let config = ChainValidationConfig {
+            threshold: 1,
+            servers: vec![
+                "cometbft+https://juno-rpc.polkachu.com".to_string(),
+                "https://juno-api.stakeandrelax.net".to_string(),
+            ],
+            finality: None,
+            head_poll_interval_ms: None,
+            max_log_block_range: None,
+        };
+        let verifier = ThresholdVerifier::new_cosmos(
+            config,
+            &Arc::new(reqwest::Client::new()),
+            ChainId::Evm(4444_118),
+        );
+        let auth_call = |key: &[u8]| HotVerifyAuthCall {
+            contract_id: "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4"
+                .to_string(),
+            method: String::new(),
+            chain_id: ChainId::Evm(4444_118),
+            input: InputData::CosmosStorage(CosmosStorageQuery { key: key.to_vec() }),
+            expectation: None,
+            return_field: None,
+        };
+
+        // cw2 version info, stored by every contract
+        assert!(
+            verifier
+                .verify_cosmos_auth_call(auth_call(b"contract_info"), None)
+                .await?
+        );
+        assert!(
+            !verifier
+                .verify_cosmos_auth_call(auth_call(b"no such key"), None)
+                .await?
+        );
+        Ok(())
+    }
+}
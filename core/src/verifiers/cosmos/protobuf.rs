@@ -0,0 +1,142 @@
+//! Protobuf encoding of the few Cosmos messages the verifier exchanges:
+//! <https://protobuf.dev/programming-guides/encoding/>.
+use anyhow::{bail, ensure, Context, Result};
+
+const VARINT: u8 = 0;
+const FIXED64: u8 = 1;
+const LEN: u8 = 2;
+const FIXED32: u8 = 5;
+
+#[derive(Default)]
+pub(super) struct ProtoWriter(Vec<u8>);
+
+impl ProtoWriter {
+    pub(super) fn bytes(&mut self, field: u32, value: &[u8]) -> &mut Self {
+        self.varint((u64::from(field) << 3) | u64::from(LEN));
+        self.varint(u64::try_from(value.len()).expect("Length fits in 64 bits"));
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    pub(super) fn string(&mut self, field: u32, value: &str) -> &mut Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    /// A bare varint, without a field key.
+    pub(super) fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value.to_le_bytes()[0] | 0x80);
+            value >>= 7;
+        }
+        self.0
+            .push(u8::try_from(value).expect("Varint tail fits in 7 bits"));
+    }
+
+    pub(super) fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// A field of a message, unknown fields are skipped by the callers.
+pub(super) enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+impl<'a> Value<'a> {
+    pub(super) fn as_bytes(&self) -> Result<&'a [u8]> {
+        match self {
+            Self::Bytes(bytes) => Ok(bytes),
+            _ => bail!("Expected a length-delimited protobuf field"),
+        }
+    }
+
+    pub(super) fn as_varint(&self) -> Result<u64> {
+        match self {
+            Self::Varint(value) => Ok(*value),
+            _ => bail!("Expected a varint protobuf field"),
+        }
+    }
+}
+
+pub(super) struct ProtoReader<'a>(&'a [u8]);
+
+impl<'a> ProtoReader<'a> {
+    pub(super) const fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    /// The next field number and value, `None` at the end of the message.
+    pub(super) fn field(&mut self) -> Result<Option<(u32, Value<'a>)>> {
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let field = u32::try_from(key >> 3)?;
+        let value = match u8::try_from(key & 0x7)? {
+            VARINT => Value::Varint(self.varint()?),
+            LEN => {
+                let len = usize::try_from(self.varint()?)?;
+                ensure!(self.0.len() >= len, "Unexpected end of protobuf bytes");
+                let (value, tail) = self.0.split_at(len);
+                self.0 = tail;
+                Value::Bytes(value)
+            }
+            FIXED64 => {
+                self.0 = self.0.get(8..).context("Unexpected end of protobuf data")?;
+                Value::Fixed
+            }
+            FIXED32 => {
+                self.0 = self.0.get(4..).context("Unexpected end of protobuf data")?;
+                Value::Fixed
+            }
+            wire_type => bail!("Unsupported protobuf wire type {wire_type}"),
+        };
+        Ok(Some((field, value)))
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let [byte, tail @ ..] = self.0 else {
+                bail!("Unexpected end of protobuf varint");
+            };
+            self.0 = tail;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("Protobuf varint is too long")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProtoReader, ProtoWriter};
+
+    #[test]
+    fn round_trip() -> anyhow::Result<()> {
+        let long = vec![7; 300];
+        let mut writer = ProtoWriter::default();
+        writer.string(1, "juno1").bytes(2, &long);
+        let message = writer.into_inner();
+        // 300 = 0b10_0101100 takes 2 varint bytes.
+        assert_eq!(&message[7..10], &[0x12, 0xac, 0x02]);
+
+        let mut reader = ProtoReader::new(&message);
+        let (field, value) = reader.field()?.expect("first field");
+        assert_eq!((field, value.as_bytes()?), (1, b"juno1".as_slice()));
+        let (field, value) = reader.field()?.expect("second field");
+        assert_eq!((field, value.as_bytes()?), (2, long.as_slice()));
+        assert!(reader.field()?.is_none());
+
+        // Field 1 = 150, as in the protobuf encoding guide.
+        let mut reader = ProtoReader::new(&[0x08, 0x96, 0x01]);
+        let (field, value) = reader.field()?.expect("varint field");
+        assert_eq!((field, value.as_varint()?), (1, 150));
+        assert!(value.as_bytes().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+//! Auth calls satisfied by an event log, see [`EvmLogQuery`].
+use alloy_dyn_abi::{DynSolType, DynSolValue, EventExt};
+use alloy_json_abi::Event;
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure, Context, Result};
+use hot_validation_primitives::bridge::evm::EvmLogQuery;
+use serde::Deserialize;
+
+/// Identity of the log that satisfied the query, threshold consensus is reached on it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedLog {
+    pub block_hash: String,
+    pub transaction_hash: String,
+    pub log_index: String,
+}
+
+/// Element of the `eth_getLogs` response, only the fields we need.
+#[derive(Deserialize)]
+pub(crate) struct Log {
+    topics: Vec<String>,
+    data: String,
+    /// Set for logs of blocks that were reorged out.
+    #[serde(default)]
+    removed: bool,
+    #[serde(flatten)]
+    id: MatchedLog,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LogsResponse {
+    pub(crate) result: Vec<Log>,
+}
+
+/// Decodes logs of the queried event and checks them against the expected values.
+pub(crate) struct EventMatcher {
+    event: Event,
+    /// Index of the event input and the value it has to be equal to.
+    expected: Vec<(usize, DynSolValue)>,
+}
+
+impl EventMatcher {
+    pub(crate) fn new(query: &EvmLogQuery) -> Result<Self> {
+        let event = Event::parse(&query.event)
+            .map_err(|e| anyhow!("Invalid event signature {}: {e}", query.event))?;
+        let expected = query
+            .expected
+            .iter()
+            .map(|(name, value)| {
+                let (index, param) = event
+                    .inputs
+                    .iter()
+                    .enumerate()
+                    .find(|(_, param)| &param.name == name)
+                    .with_context(|| format!("Event {} has no parameter {name}", query.event))?;
+                let ty = DynSolType::parse(&param.ty)
+                    .map_err(|e| anyhow!("Unsupported type of {name}: {e}"))?;
+                let value = ty
+                    .coerce_str(value)
+                    .map_err(|e| anyhow!("Invalid value of {name} ({value}): {e}"))?;
+                Ok((index, value))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { event, expected })
+    }
+
+    /// `topic0` of the event followed by the filters of the indexed parameters.
+    pub(crate) fn topics(&self, filters: &[Option<String>]) -> Result<Vec<Option<String>>> {
+        ensure!(
+            filters.len() <= 3,
+            "An event has at most 3 indexed topics, got {} filters",
+            filters.len()
+        );
+        let selector = format!("0x{}", hex::encode(self.event.selector()));
+        Ok(std::iter::once(Some(selector))
+            .chain(filters.iter().cloned())
+            .collect())
+    }
+
+    /// The first log of the event that has all the expected values.
+    /// Indexed parameters of dynamic types are hashed, so they can only be filtered by topics.
+    /// Logs that don't decode as the event (e.g. another event with the same `topic0`
+    /// but different indexed parameters) are skipped.
+    pub(crate) fn find(&self, logs: Vec<Log>) -> Option<MatchedLog> {
+        for log in logs {
+            if log.removed {
+                continue;
+            }
+            let values = match self.decode(&log) {
+                Ok(values) => values,
+                Err(err) => {
+                    tracing::debug!("Skipping log: {err:#}");
+                    continue;
+                }
+            };
+            if self
+                .expected
+                .iter()
+                .all(|(index, expected)| values.get(*index) == Some(expected))
+            {
+                return Some(log.id);
+            }
+        }
+        None
+    }
+
+    /// Values of the event inputs in the declaration order.
+    fn decode(&self, log: &Log) -> Result<Vec<DynSolValue>> {
+        let topics = log
+            .topics
+            .iter()
+            .map(|topic| {
+                let bytes = hex::decode(topic.trim_start_matches("0x"))
+                    .map_err(|_| anyhow!("Couldn't decode topic from hex: {topic}"))?;
+                B256::try_from(bytes.as_slice()).map_err(|_| anyhow!("Invalid topic: {topic}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let data = hex::decode(log.data.trim_start_matches("0x"))
+            .map_err(|_| anyhow!("Couldn't decode log data from hex: {}", log.data))?;
+        let decoded = self
+            .event
+            .decode_log_parts(topics, &data)
+            .with_context(|| format!("Couldn't decode log {:?}", log.id))?;
+
+        let mut indexed = decoded.indexed.into_iter();
+        let mut body = decoded.body.into_iter();
+        self.event
+            .inputs
+            .iter()
+            .map(|param| {
+                if param.indexed {
+                    indexed.next()
+                } else {
+                    body.next()
+                }
+                .with_context(|| format!("Log {:?} misses {}", log.id, param.name))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventMatcher, Log};
+    use hot_validation_primitives::bridge::evm::EvmLogQuery;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    const SENDER_TOPIC: &str = "0x0000000000000000000000001111111111111111111111111111111111111111";
+
+    fn query(expected: &[(&str, &str)]) -> EvmLogQuery {
+        EvmLogQuery {
+            event: "Deposit(address indexed sender, uint256 amount, uint128 nonce)".to_string(),
+            topics: vec![Some(SENDER_TOPIC.to_string())],
+            from_block: 0,
+            to_block: None,
+            expected: expected
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    fn log(topic0: &str, amount: u8, nonce: u8) -> Log {
+        let data = format!("0x{:064x}{:064x}", amount, nonce);
+        serde_json::from_value(json!({
+            "address": "0x233c5370CCfb3cD7409d9A3fb98ab94dE94Cb4Cd",
+            "topics": [topic0, SENDER_TOPIC],
+            "data": data,
+            "blockNumber": "0x10",
+            "blockHash": "0xaa",
+            "transactionHash": format!("0x{nonce:02x}"),
+            "logIndex": "0x0",
+            "removed": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn match_log_fields() -> anyhow::Result<()> {
+        let matcher = EventMatcher::new(&query(&[
+            ("sender", "0x1111111111111111111111111111111111111111"),
+            ("nonce", "42"),
+        ]))?;
+        let topics = matcher.topics(&[Some(SENDER_TOPIC.to_string())])?;
+        let topic0 = topics[0].clone().unwrap();
+        assert_eq!(topics.len(), 2);
+
+        let matched = matcher.find(vec![log(&topic0, 1, 41), log(&topic0, 1, 42)]);
+        assert_eq!(matched.unwrap().transaction_hash, "0x2a");
+        assert!(matcher.find(vec![log(&topic0, 1, 41)]).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn undecodable_logs_are_skipped() -> anyhow::Result<()> {
+        let matcher = EventMatcher::new(&query(&[("nonce", "42")]))?;
+        let topic0 = matcher.topics(&[])?[0].clone().unwrap();
+        let mut truncated = log(&topic0, 1, 42);
+        truncated.data = "0x01".to_string();
+        truncated.id.transaction_hash = "0xff".to_string();
+        let matched = matcher.find(vec![truncated, log(&topic0, 1, 42)]);
+        assert_eq!(matched.unwrap().transaction_hash, "0x2a");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_queries() {
+        assert!(EventMatcher::new(&query(&[("receiver", "0x00")])).is_err());
+        assert!(EventMatcher::new(&query(&[("nonce", "forty two")])).is_err());
+        let matcher = EventMatcher::new(&query(&[])).unwrap();
+        assert!(matcher.topics(&[None, None, None, None]).is_err());
+    }
+}
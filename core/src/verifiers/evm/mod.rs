@@ -1,16 +1,27 @@
+mod logs;
 mod types;
 
+use crate::head_tracker::{HeadSource, HeadTracker, Heads};
 use crate::http_client::post_json_receive_json;
 use crate::threshold_verifier::{Identifiable, ThresholdVerifier};
-use crate::verifiers::evm::types::{BlockSpecifier, RpcRequest, RpcResponse, BLOCK_DELAY};
+use crate::verifiers::evm::logs::{EventMatcher, LogsResponse, MatchedLog};
+use crate::verifiers::evm::types::{
+    abi_function, resolve_function, BlockResponse, BlockSpecifier, CallResponse, ReceiptResponse,
+    RpcRequest, RpcResponse, BALANCE_OF_METHOD_NAME, BLOCK_DELAY, USED_NONCES_METHOD_NAME,
+};
 use crate::verifiers::Verifier;
-use crate::ChainValidationConfig;
+use crate::{ChainValidationConfig, HOT_VERIFY_METHOD_NAME};
 use alloy_dyn_abi::DynSolValue;
-use anyhow::Result;
+use alloy_json_abi::Function;
+use anyhow::{bail, ensure, Context, Result};
 use async_trait::async_trait;
-use hot_validation_primitives::bridge::evm::EvmInputData;
-use hot_validation_primitives::bridge::InputData;
-use hot_validation_primitives::{ChainId, ExtendedChainId};
+use hot_validation_primitives::bridge::evm::{EvmInputArg, EvmInputData, EvmLogQuery};
+use hot_validation_primitives::bridge::expectation::ReturnValue;
+use hot_validation_primitives::bridge::transaction::{TransactionId, TransactionInputData};
+use hot_validation_primitives::bridge::{CompletedWithdrawal, HotVerifyAuthCall, InputData};
+use hot_validation_primitives::{
+    ChainFamily, ChainId, ExtendedChainId, Finality, FinalityLevel, DEFAULT_MAX_LOG_BLOCK_RANGE,
+};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -18,42 +29,205 @@ pub struct EvmVerifier {
     client: Arc<reqwest::Client>,
     server: String,
     chain_id: ChainId,
+    finality: Finality,
+    max_log_block_range: u64,
+    head_tracker: Option<Arc<HeadTracker>>,
 }
 
 impl Identifiable for EvmVerifier {
     fn id(&self) -> String {
         self.server.clone()
     }
+
+    fn is_lagging(&self) -> bool {
+        self.head_tracker
+            .as_ref()
+            .is_some_and(|tracker| tracker.is_lagging(&self.server))
+    }
+}
+
+#[async_trait]
+impl HeadSource for EvmVerifier {
+    /// `finalized` is only read at the final level, and is optional,
+    /// not every chain supports this tag. `safe` is never read.
+    async fn fetch_heads(&self) -> Result<Heads> {
+        let latest = self.fetch_block_number(&BlockSpecifier::Latest).await?;
+        let heads = Heads::latest(latest);
+        if self.finality.level != FinalityLevel::Final {
+            return Ok(heads);
+        }
+        let finalized = self
+            .fetch_block_number(&BlockSpecifier::Finalized)
+            .await
+            .ok();
+        Ok(finalized.map_or(heads, |finalized| {
+            heads.with(FinalityLevel::Final, finalized)
+        }))
+    }
 }
 
 impl EvmVerifier {
-    pub fn new(client: Arc<reqwest::Client>, server: String, chain_id: ChainId) -> Self {
+    pub fn new(
+        client: Arc<reqwest::Client>,
+        server: String,
+        chain_id: ChainId,
+        finality: Finality,
+    ) -> Self {
         Self {
             client,
             server,
             chain_id,
+            finality,
+            max_log_block_range: DEFAULT_MAX_LOG_BLOCK_RANGE,
+            head_tracker: None,
         }
     }
 
-    async fn get_block(&self) -> Result<BlockSpecifier> {
-        let can_reorg = ExtendedChainId::try_from(self.chain_id)
-            .map_err(anyhow::Error::msg)?
+    /// Caps `to_block - from_block` of log queries, see [`ChainValidationConfig::max_log_block_range`].
+    #[must_use]
+    pub const fn with_max_log_block_range(mut self, max_log_block_range: u64) -> Self {
+        self.max_log_block_range = max_log_block_range;
+        self
+    }
+
+    /// Reads the heads from `head_tracker` instead of fetching them, while they are fresh.
+    #[must_use]
+    pub fn with_head_tracker(mut self, head_tracker: Arc<HeadTracker>) -> Self {
+        self.head_tracker = Some(head_tracker);
+        self
+    }
+
+    async fn get_block(&self, at: Option<u64>) -> Result<BlockSpecifier> {
+        if let Some(block_number) = at {
+            return Ok(BlockSpecifier::BlockNumber(block_number));
+        }
+        let can_reorg = self
+            .chain_id
+            .info()
+            .ok_or_else(|| anyhow::anyhow!("Chain {} is not in the registry", self.chain_id))?
             .can_reorg();
-        if !can_reorg {
-            return Ok(BlockSpecifier::Latest);
+        // On chains without reorgs `latest` is already final.
+        let (head, delay) = match self.finality.level {
+            FinalityLevel::Final if can_reorg => (BlockSpecifier::Finalized, 0),
+            // Ideally, we would want to use `safe` or `final` block here,
+            // but some networks have too much finality time (i.e. 15 minutes). So we use `latest - 1`,
+            // because in practice most reverts happen in the next block,
+            // so taking some delta from the latest block is good enough.
+            FinalityLevel::Confirmed if can_reorg => (BlockSpecifier::Latest, BLOCK_DELAY),
+            _ => (BlockSpecifier::Latest, 0),
+        };
+        let delay = delay.max(self.finality.min_confirmations());
+        if delay == 0 {
+            return Ok(head);
         }
-        let request = RpcRequest::build_block_number();
-        let response: RpcResponse =
-            post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
-        let block_number = response.as_u64()?;
 
-        // Ideally, we would want to use `safe` or `final` block here,
-        // but some networks have too much finality time (i.e. 15 minutes). So we use `latest - 1`,
-        // because in practice most reverts happen in the next block,
-        // so taking some delta from the latest block is good enough.
-        let safer_block_number = block_number - BLOCK_DELAY;
+        let block_number = self.get_block_number(&head).await?;
+        let safer_block_number = block_number
+            .checked_sub(delay)
+            .context("Not enough blocks for the required confirmations")?;
         Ok(BlockSpecifier::BlockNumber(safer_block_number))
     }
+
+    /// Returns the response together with the function it has to be decoded with.
+    async fn eth_call(
+        &self,
+        auth_contract_id: &str,
+        method_name: &str,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> Result<(RpcResponse, Function)> {
+        let input: EvmInputData = input_data.try_into()?;
+        let function = resolve_function(method_name, &input)?;
+        let args: Vec<DynSolValue> = From::from(input);
+        let block_specifier = self.get_block(at).await?;
+        let request =
+            RpcRequest::build_eth_call(auth_contract_id, &function, &args, &block_specifier)?;
+        let response: CallResponse =
+            post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
+        Ok((response.into_result()?, function))
+    }
+
+    async fn get_block_number(&self, block_specifier: &BlockSpecifier) -> Result<u64> {
+        let tracked = self
+            .head_tracker
+            .as_ref()
+            .and_then(|tracker| tracker.heads(&self.server))
+            .and_then(|heads| match block_specifier {
+                BlockSpecifier::Latest => Some(heads.latest),
+                BlockSpecifier::Safe => heads.safe,
+                BlockSpecifier::Finalized => heads.finalized,
+                BlockSpecifier::BlockNumber(number) => Some(*number),
+            });
+        match tracked {
+            Some(number) => Ok(number),
+            None => self.fetch_block_number(block_specifier).await,
+        }
+    }
+
+    async fn fetch_block_number(&self, block_specifier: &BlockSpecifier) -> Result<u64> {
+        if let BlockSpecifier::Latest = block_specifier {
+            let request = RpcRequest::build_block_number();
+            let response: RpcResponse =
+                post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
+            return response.as_u64();
+        }
+        let request = RpcRequest::build_block_by_number(block_specifier);
+        let response: BlockResponse =
+            post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
+        response.number()
+    }
+
+    /// Whether `block` is at or below the finality `head` and has at least `min_confirmations`
+    /// blocks on top of it up to `latest`, counting itself.
+    const fn is_deep_enough(block: u64, head: u64, latest: u64, min_confirmations: u64) -> bool {
+        block <= head && (latest + 1).saturating_sub(block) >= min_confirmations
+    }
+
+    /// Number of the block selected by the finality policy.
+    async fn head_number(&self, at: Option<u64>) -> Result<u64> {
+        match self.get_block(at).await? {
+            BlockSpecifier::BlockNumber(number) => Ok(number),
+            block_specifier => self.get_block_number(&block_specifier).await,
+        }
+    }
+
+    /// Rejects log queries over more than `max_range` blocks, RPC providers would refuse them
+    /// or take too long to answer.
+    fn ensure_log_range(from_block: u64, to_block: u64, max_range: u64) -> Result<()> {
+        let range = to_block.saturating_sub(from_block);
+        ensure!(
+            range <= max_range,
+            "Block range {from_block}..={to_block} spans {range} blocks, at most {max_range} are allowed"
+        );
+        Ok(())
+    }
+
+    /// The first log of `contract_id` matching the query. The range is capped by the block
+    /// selected by the finality policy, so logs that can still be reorged out aren't accepted.
+    async fn find_log(
+        &self,
+        contract_id: &str,
+        query: &EvmLogQuery,
+        at: Option<u64>,
+    ) -> Result<Option<MatchedLog>> {
+        let matcher = EventMatcher::new(query)?;
+        let topics = matcher.topics(&query.topics)?;
+        if let Some(to_block) = query.to_block {
+            Self::ensure_log_range(query.from_block, to_block, self.max_log_block_range)?;
+        }
+        let head = self.head_number(at).await?;
+        let to_block = query.to_block.map_or(head, |to_block| to_block.min(head));
+        ensure!(
+            query.from_block <= to_block,
+            "Block range {}..={to_block} is empty, head is {head}",
+            query.from_block
+        );
+        Self::ensure_log_range(query.from_block, to_block, self.max_log_block_range)?;
+        let request = RpcRequest::build_get_logs(contract_id, &topics, query.from_block, to_block);
+        let response: LogsResponse =
+            post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
+        Ok(matcher.find(response.result))
+    }
 }
 
 #[async_trait]
@@ -64,22 +238,103 @@ impl Verifier for EvmVerifier {
             .expect("Couldn't convert ChainId to ExtendedChainId")
     }
 
-    async fn verify(
+    async fn verify_at(
         &self,
         auth_contract_id: String,
         method_name: String,
         input_data: InputData,
+        at: Option<u64>,
     ) -> Result<bool> {
-        let input: EvmInputData = input_data.try_into()?;
-        let args: Vec<DynSolValue> = From::from(input);
-        let block_specifier = self.get_block().await?;
-        let request =
-            RpcRequest::build_eth_call(&auth_contract_id, &method_name, &args, &block_specifier)?;
-        let response: RpcResponse =
-            post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
-        let status = response.as_bool()?;
+        let (response, function) = self
+            .eth_call(&auth_contract_id, &method_name, input_data, at)
+            .await?;
+        let status = response.as_bool(&function)?;
         Ok(status)
     }
+
+    async fn call(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        let (response, function) = self
+            .eth_call(&auth_contract_id, &method_name, input_data, at)
+            .await?;
+        response.as_return_value(&function)
+    }
+
+    /// `balanceOf` of the ERC-20 `token_id`.
+    async fn token_balance(
+        &self,
+        token_id: String,
+        holder: String,
+        at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        let args =
+            [DynSolValue::Address(holder.parse().with_context(|| {
+                format!("Invalid EVM address {holder}")
+            })?)];
+        let balance_of = abi_function(BALANCE_OF_METHOD_NAME)?;
+        let block_specifier = self.get_block(at).await?;
+        let request = RpcRequest::build_eth_call(&token_id, balance_of, &args, &block_specifier)?;
+        let response: CallResponse =
+            post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
+        response.into_result()?.as_return_value(balance_of)
+    }
+
+    /// The receipt has to be successful, and its block has to be at or below the head selected
+    /// by the finality policy. `min_confirmations` can only demand more depth from the latest block.
+    async fn is_transaction_successful(&self, input: TransactionInputData) -> Result<bool> {
+        let TransactionId::Evm { hash } = input.transaction else {
+            bail!("Expected an EVM transaction, got {:?}", input.transaction);
+        };
+        let request = RpcRequest::build_transaction_receipt(&hash);
+        let response: ReceiptResponse =
+            post_json_receive_json(&self.client, &self.server, &request, self.chain_id).await?;
+        let Some(block_number) = response.successful_block_number()? else {
+            return Ok(false);
+        };
+        let head = self.head_number(None).await?;
+        let latest = if input.min_confirmations > 0 {
+            self.get_block_number(&BlockSpecifier::Latest).await?
+        } else {
+            head
+        };
+        Ok(Self::is_deep_enough(
+            block_number,
+            head,
+            latest,
+            input.min_confirmations,
+        ))
+    }
+
+    async fn is_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
+    ) -> Result<bool> {
+        let input = EvmInputData::Args(vec![EvmInputArg::Uint(withdrawal.nonce)]);
+        self.verify(
+            contract_id,
+            USED_NONCES_METHOD_NAME.to_string(),
+            input.into(),
+        )
+        .await
+    }
+}
+
+/// Whether the auth call resolves to a bridge function returning the verdict itself
+/// (`hot_verify`, `usedNonces`), whatever outputs its signature declares.
+pub(crate) fn returns_verdict(method_name: &str, input: &EvmInputData) -> bool {
+    let Ok(function) = resolve_function(method_name, input) else {
+        return false;
+    };
+    [HOT_VERIFY_METHOD_NAME, USED_NONCES_METHOD_NAME]
+        .into_iter()
+        .filter_map(|name| abi_function(name).ok())
+        .any(|verdict| verdict.selector() == function.selector())
 }
 
 impl ThresholdVerifier<EvmVerifier> {
@@ -89,11 +344,20 @@ impl ThresholdVerifier<EvmVerifier> {
         chain_id: ChainId,
     ) -> Self {
         let threshold = config.threshold;
+        let finality = config.finality_or_default(ChainFamily::Evm);
+        let max_log_block_range = config.max_log_block_range_or_default();
+        let head_tracker = HeadTracker::new(chain_id, config.head_poll_interval());
         let servers = config.servers;
-        let verifiers = servers
+        let verifiers: Vec<_> = servers
             .into_iter()
-            .map(|url| Arc::new(EvmVerifier::new(client.clone(), url, chain_id)))
+            .map(|url| {
+                let verifier = EvmVerifier::new(client.clone(), url, chain_id, finality)
+                    .with_max_log_block_range(max_log_block_range)
+                    .with_head_tracker(head_tracker.clone());
+                Arc::new(verifier)
+            })
             .collect();
+        head_tracker.spawn(&verifiers);
         Self {
             threshold,
             verifiers,
@@ -101,13 +365,44 @@ impl ThresholdVerifier<EvmVerifier> {
     }
 }
 
+impl ThresholdVerifier<EvmVerifier> {
+    /// Same as [`ThresholdVerifier::verify_auth_call`], but also accepts event log queries:
+    /// the verifiers have to agree on the log that satisfies the query.
+    pub async fn verify_evm_auth_call(
+        &self,
+        auth_call: HotVerifyAuthCall,
+        at: Option<u64>,
+    ) -> Result<bool> {
+        let InputData::EvmLogs(query) = &auth_call.input else {
+            return self.verify_auth_call(auth_call, at).await;
+        };
+        let contract_id = auth_call.contract_id.clone();
+        let query = query.clone();
+        let matched = self
+            .threshold_call(move |verifier| {
+                let contract_id = contract_id.clone();
+                let query = query.clone();
+                async move { verifier.find_log(&contract_id, &query, at).await }
+            })
+            .await?;
+        Ok(matched.is_some())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::EvmVerifier;
+    use crate::revert::RevertReason;
     use crate::test_data::base_rpc;
     use crate::threshold_verifier::ThresholdVerifier;
+    use crate::verifiers::evm::types::{
+        resolve_function, BlockSpecifier, CallResponse, ReceiptResponse, RpcRequest, RpcResponse,
+    };
     use crate::{ChainValidationConfig, HOT_VERIFY_METHOD_NAME};
+    use alloy_dyn_abi::DynSolValue;
     use anyhow::Result;
     use hot_validation_primitives::bridge::evm::EvmInputData;
+    use hot_validation_primitives::bridge::expectation::ReturnValue;
     use hot_validation_primitives::ChainId;
     use std::sync::Arc;
 
@@ -132,6 +427,9 @@ mod tests {
                     "http://localhost:1000".to_string(),
                     base_rpc(),
                 ],
+                finality: None,
+                head_poll_interval_ms: None,
+                max_log_block_range: None,
             },
             &Arc::new(reqwest::Client::new()),
             ChainId::Evm(8453),
@@ -147,4 +445,136 @@ mod tests {
         assert!(status);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn base_token_balance() -> Result<()> {
+        // USDC on Base.
+        let token_id = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+        let validation = ThresholdVerifier::new_evm(
+            ChainValidationConfig {
+                threshold: 1,
+                servers: vec![base_rpc()],
+                finality: None,
+                head_poll_interval_ms: None,
+                max_log_block_range: None,
+            },
+            &Arc::new(reqwest::Client::new()),
+            ChainId::Evm(8453),
+        );
+
+        let balance = validation
+            .token_balance(token_id.to_string(), token_id.to_string(), None)
+            .await?;
+        assert!(matches!(balance, ReturnValue::Uint(_)));
+        assert!(validation
+            .token_balance(token_id.to_string(), "alice.near".to_string(), None)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn function_from_signature() -> Result<()> {
+        let input: EvmInputData = serde_json::from_value(serde_json::json!({
+            "function": "function lastNonce(uint128 user) view returns (uint128)",
+            "args": [{ "type": "uint128", "value": "7" }]
+        }))?;
+        let function = resolve_function("lastNonce", &input)?;
+        let args: Vec<DynSolValue> = input.clone().into();
+        let request = RpcRequest::build_eth_call(
+            "0xf22Ef29d5Bb80256B569f4233a76EF09Cae996eC",
+            &function,
+            &args,
+            &BlockSpecifier::Latest,
+        )?;
+        let data = serde_json::to_value(&request)?["params"][0]["data"].clone();
+        assert_eq!(
+            data,
+            format!("0x{}{:064x}", hex::encode(function.selector()), 7)
+        );
+        let response: RpcResponse =
+            serde_json::from_value(serde_json::json!({ "result": format!("0x{:064x}", 42) }))?;
+        assert_eq!(
+            response.as_return_value(&function)?,
+            ReturnValue::from(42u128)
+        );
+
+        // the method picks the function the policy checks, so the signature has to agree with it
+        assert!(resolve_function("hot_verify", &input).is_err());
+        assert!(resolve_function("lastNonce", &EvmInputData::Args(vec![])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn receipt_status() -> Result<()> {
+        let receipt = |status: &str| -> Result<ReceiptResponse> {
+            Ok(serde_json::from_value(serde_json::json!({
+                "result": { "status": status, "blockNumber": "0x10", "gasUsed": "0x5208" }
+            }))?)
+        };
+        assert_eq!(receipt("0x1")?.successful_block_number()?, Some(16));
+        assert_eq!(receipt("0x0")?.successful_block_number()?, None);
+        let pending: ReceiptResponse = serde_json::from_str(r#"{"result":null}"#)?;
+        assert_eq!(pending.successful_block_number()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn revert_reason() -> Result<()> {
+        let revert = |error: serde_json::Value| -> Result<Option<RevertReason>> {
+            let response: CallResponse =
+                serde_json::from_value(serde_json::json!({ "error": error }))?;
+            let error = response.into_result().err().expect("Call should revert");
+            Ok(error.downcast_ref::<RevertReason>().cloned())
+        };
+        let message = "Nonce used";
+        let data = format!(
+            "0x08c379a0{:064x}{:064x}{:0<64}",
+            32,
+            message.len(),
+            hex::encode(message)
+        );
+        assert_eq!(
+            revert(
+                serde_json::json!({ "code": 3, "message": "execution reverted: Nonce used", "data": data })
+            )?,
+            Some(RevertReason::Message(message.to_string()))
+        );
+        let data = format!("0x4e487b71{:064x}", 0x11);
+        assert_eq!(
+            revert(
+                serde_json::json!({ "code": 3, "message": "execution reverted", "data": data })
+            )?,
+            Some(RevertReason::Panic(0x11))
+        );
+        assert_eq!(
+            revert(
+                serde_json::json!({ "code": 3, "message": "execution reverted", "data": "0xdeadbeef" })
+            )?,
+            Some(RevertReason::Raw("0xdeadbeef".to_string()))
+        );
+        assert_eq!(
+            revert(serde_json::json!({ "code": -32005, "message": "rate limit exceeded" }))?,
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn receipt_has_to_be_below_the_finality_head() {
+        // included after the finalized head, however many blocks are on top of it
+        assert!(!EvmVerifier::is_deep_enough(101, 100, 110, 0));
+        assert!(!EvmVerifier::is_deep_enough(101, 100, 200, 12));
+        assert!(EvmVerifier::is_deep_enough(100, 100, 100, 0));
+        // `min_confirmations` is counted from the latest block
+        assert!(EvmVerifier::is_deep_enough(90, 100, 101, 12));
+        assert!(!EvmVerifier::is_deep_enough(90, 100, 100, 12));
+    }
+
+    #[test]
+    fn wide_log_queries_are_rejected() {
+        assert!(EvmVerifier::ensure_log_range(100, 100, 0).is_ok());
+        assert!(EvmVerifier::ensure_log_range(100, 10_100, 10_000).is_ok());
+        assert!(EvmVerifier::ensure_log_range(100, 10_101, 10_000).is_err());
+    }
 }
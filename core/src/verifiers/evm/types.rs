@@ -1,7 +1,10 @@
-use crate::HOT_VERIFY_METHOD_NAME;
+use crate::revert::RevertReason;
 use alloy_contract::Interface;
-use alloy_dyn_abi::DynSolValue;
-use alloy_json_abi::JsonAbi;
+use alloy_dyn_abi::{DynSolType, DynSolValue, FunctionExt, JsonAbiExt};
+use alloy_json_abi::{Function, JsonAbi};
+use anyhow::{ensure, Context};
+use hot_validation_primitives::bridge::evm::EvmInputData;
+use hot_validation_primitives::bridge::expectation::ReturnValue;
 use serde::{Deserialize, Serialize};
 use serde_hex::SerHexSeq;
 use serde_hex::StrictPfx;
@@ -9,9 +12,16 @@ use serde_json::json;
 use std::fmt::Display;
 
 pub const BLOCK_DELAY: u64 = 1;
+pub const USED_NONCES_METHOD_NAME: &str = "usedNonces";
+pub const BALANCE_OF_METHOD_NAME: &str = "balanceOf";
+/// Selectors of the built-in `Error(string)` and `Panic(uint256)` errors.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
 
 pub(crate) enum BlockSpecifier {
     Latest,
+    Safe,
+    Finalized,
     BlockNumber(u64),
 }
 
@@ -19,6 +29,8 @@ impl Display for BlockSpecifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             BlockSpecifier::Latest => write!(f, "latest"),
+            BlockSpecifier::Safe => write!(f, "safe"),
+            BlockSpecifier::Finalized => write!(f, "finalized"),
             BlockSpecifier::BlockNumber(n) => write!(f, "0x{n:x}"),
         }
     }
@@ -35,19 +47,150 @@ impl RpcResponse {
             .map_err(|_| anyhow::anyhow!("Invalid u64: {}", self.result))
     }
 
-    pub fn as_bool(&self) -> anyhow::Result<bool> {
+    fn first_output(&self, function: &Function) -> anyhow::Result<DynSolValue> {
         let bytes = hex::decode(self.result.trim_start_matches("0x"))
             .map_err(|_| anyhow::anyhow!("Couldn't decode from hex: {}", self.result))?;
-        let result = INTERFACE.decode_output(HOT_VERIFY_METHOD_NAME, &bytes)?;
-        let value = result
-            .first()
-            .ok_or_else(|| anyhow::anyhow!("No elements in the output"))?;
+        let result = function.abi_decode_output(&bytes)?;
+        result
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("No elements in the output"))
+    }
+
+    pub fn as_bool(&self, function: &Function) -> anyhow::Result<bool> {
+        let value = self.first_output(function)?;
         if let DynSolValue::Bool(b) = value {
-            Ok(*b)
+            Ok(b)
         } else {
             anyhow::bail!("first value is not bool: {value:?}")
         }
     }
+
+    pub fn as_return_value(&self, function: &Function) -> anyhow::Result<ReturnValue> {
+        let value = match self.first_output(function)? {
+            DynSolValue::Bool(b) => ReturnValue::Bool(b),
+            DynSolValue::Uint(value, _) => ReturnValue::Uint(value),
+            DynSolValue::Int(value, _) => {
+                ReturnValue::from_signed(value.is_negative(), value.unsigned_abs())
+            }
+            value => anyhow::bail!("first value is neither bool nor integer: {value:?}"),
+        };
+        Ok(value)
+    }
+}
+
+/// Response of `eth_call`, which fails with a JSON-RPC error when the call reverts.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum CallResponse {
+    Success(RpcResponse),
+    Failure { error: RpcError },
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct RpcError {
+    code: i64,
+    message: String,
+    /// Revert data, `0x`-prefixed hex.
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+}
+
+impl CallResponse {
+    pub fn into_result(self) -> anyhow::Result<RpcResponse> {
+        let error = match self {
+            Self::Success(response) => return Ok(response),
+            Self::Failure { error } => error,
+        };
+        let data = error
+            .data
+            .as_ref()
+            .and_then(serde_json::Value::as_str)
+            .and_then(|data| hex::decode(data.trim_start_matches("0x")).ok());
+        let reason = match data {
+            Some(data) => decode_revert(&data),
+            None if error.message.starts_with("execution reverted") => {
+                error.message.split_once(": ").map_or_else(
+                    || RevertReason::Raw(error.message.clone()),
+                    |(_, message)| RevertReason::Message(message.to_string()),
+                )
+            }
+            None => anyhow::bail!("eth_call failed with {}: {}", error.code, error.message),
+        };
+        Err(anyhow::Error::new(reason).context(format!("eth_call reverted: {}", error.message)))
+    }
+}
+
+/// Decodes `Error(string)` and `Panic(uint256)`, other revert data is kept as raw hex.
+pub(crate) fn decode_revert(data: &[u8]) -> RevertReason {
+    let raw = || RevertReason::Raw(format!("0x{}", hex::encode(data)));
+    let Some((selector, args)) = data.split_first_chunk::<4>() else {
+        return raw();
+    };
+    let decode = |types: Vec<DynSolType>| match DynSolType::Tuple(types).abi_decode_sequence(args) {
+        Ok(DynSolValue::Tuple(values)) => Some(values),
+        _ => None,
+    };
+    let reason = match *selector {
+        ERROR_SELECTOR => decode(vec![DynSolType::String]).and_then(|values| match &values[..] {
+            [DynSolValue::String(message)] => Some(RevertReason::Message(message.clone())),
+            _ => None,
+        }),
+        PANIC_SELECTOR => {
+            decode(vec![DynSolType::Uint(256)]).and_then(|values| match &values[..] {
+                [DynSolValue::Uint(code, _)] => u64::try_from(*code).ok().map(RevertReason::Panic),
+                _ => None,
+            })
+        }
+        _ => None,
+    };
+    reason.unwrap_or_else(raw)
+}
+
+/// Response of `eth_getBlockByNumber`, only the fields we need.
+#[derive(Deserialize)]
+pub(crate) struct BlockResponse {
+    result: BlockHeader,
+}
+
+#[derive(Deserialize)]
+struct BlockHeader {
+    number: String,
+}
+
+impl BlockResponse {
+    pub fn number(&self) -> anyhow::Result<u64> {
+        u64::from_str_radix(self.result.number.trim_start_matches("0x"), 16)
+            .map_err(|_| anyhow::anyhow!("Invalid block number: {}", self.result.number))
+    }
+}
+
+/// Response of `eth_getTransactionReceipt`, `None` if the transaction isn't mined (yet).
+#[derive(Deserialize)]
+pub(crate) struct ReceiptResponse {
+    result: Option<Receipt>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Receipt {
+    status: String,
+    block_number: String,
+}
+
+impl ReceiptResponse {
+    /// Block number of a successful transaction, `None` if it's not mined or reverted.
+    pub fn successful_block_number(&self) -> anyhow::Result<Option<u64>> {
+        let Some(receipt) = &self.result else {
+            return Ok(None);
+        };
+        if receipt.status != "0x1" {
+            return Ok(None);
+        }
+        let number = u64::from_str_radix(receipt.block_number.trim_start_matches("0x"), 16)
+            .map_err(|_| anyhow::anyhow!("Invalid block number: {}", receipt.block_number))?;
+        Ok(Some(number))
+    }
 }
 
 #[derive(Serialize)]
@@ -68,9 +211,46 @@ impl RpcRequest {
         }
     }
 
+    pub fn build_block_by_number(block_specifier: &BlockSpecifier) -> Self {
+        RpcRequest {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "eth_getBlockByNumber",
+            params: json!([block_specifier.to_string(), false]),
+        }
+    }
+
+    pub fn build_transaction_receipt(hash: &str) -> Self {
+        RpcRequest {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "eth_getTransactionReceipt",
+            params: json!([hash]),
+        }
+    }
+
+    pub fn build_get_logs(
+        address: &str,
+        topics: &[Option<String>],
+        from_block: u64,
+        to_block: u64,
+    ) -> Self {
+        RpcRequest {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "eth_getLogs",
+            params: json!([{
+                "address": address,
+                "topics": topics,
+                "fromBlock": BlockSpecifier::BlockNumber(from_block).to_string(),
+                "toBlock": BlockSpecifier::BlockNumber(to_block).to_string(),
+            }]),
+        }
+    }
+
     pub fn build_eth_call(
         auth_contract_id: &str,
-        method_name: &str,
+        function: &Function,
         args: &[DynSolValue],
         block_specifier: &BlockSpecifier,
     ) -> anyhow::Result<Self> {
@@ -80,7 +260,7 @@ impl RpcRequest {
             #[serde(with = "SerHexSeq::<StrictPfx>")]
             data: Vec<u8>,
         }
-        let data = INTERFACE.encode_input(method_name, args)?;
+        let data = function.abi_encode_input(args)?;
 
         Ok(RpcRequest {
             jsonrpc: "2.0",
@@ -97,13 +277,43 @@ impl RpcRequest {
     }
 }
 
+/// Function of the bridge ABI.
+pub(crate) fn abi_function(method_name: &str) -> anyhow::Result<&'static Function> {
+    INTERFACE
+        .abi()
+        .function(method_name)
+        .and_then(|functions| functions.first())
+        .with_context(|| {
+            format!("{method_name} isn't in the bridge ABI, the input has to carry its signature")
+        })
+}
+
+/// The function an auth call is encoded and decoded with: the signature in the input if there's
+/// one, otherwise the function of the bridge ABI named after the method.
+pub(crate) fn resolve_function(
+    method_name: &str,
+    input: &EvmInputData,
+) -> anyhow::Result<Function> {
+    let Some(signature) = input.function() else {
+        return abi_function(method_name).cloned();
+    };
+    let function = Function::parse(signature)
+        .map_err(|err| anyhow::anyhow!("Invalid function signature {signature}: {err}"))?;
+    ensure!(
+        function.name == method_name,
+        "Function {} doesn't match the auth call method {method_name}",
+        function.name
+    );
+    Ok(function)
+}
+
 static INTERFACE: std::sync::LazyLock<Interface> = std::sync::LazyLock::new(|| {
     let abi: JsonAbi =
         serde_json::from_str(HOT_VERIFY_EVM_ABI).expect("Invalid JSON ABI for hot_verify");
     Interface::new(abi)
 });
 
-// JSON ABI for `hot_verify` method
+// JSON ABI for `hot_verify` method, and the standard methods we call (`usedNonces`, ERC-20 `balanceOf`)
 const HOT_VERIFY_EVM_ABI: &str = r#"
 [
   {
@@ -130,6 +340,17 @@ const HOT_VERIFY_EVM_ABI: &str = r#"
     ],
     "stateMutability": "view",
     "type": "function"
+  },
+  {
+    "inputs": [
+      { "internalType": "address", "name": "account", "type": "address" }
+    ],
+    "name": "balanceOf",
+    "outputs": [
+      { "internalType": "uint256", "name": "", "type": "uint256" }
+    ],
+    "stateMutability": "view",
+    "type": "function"
   }
 ]
 "#;
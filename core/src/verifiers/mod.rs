@@ -1,5 +1,7 @@
 use async_trait::async_trait;
-use hot_validation_primitives::bridge::InputData;
+use hot_validation_primitives::bridge::expectation::ReturnValue;
+use hot_validation_primitives::bridge::transaction::TransactionInputData;
+use hot_validation_primitives::bridge::{CompletedWithdrawal, InputData};
 use hot_validation_primitives::ExtendedChainId;
 
 pub mod cosmos;
@@ -18,5 +20,51 @@ pub trait Verifier: Sized + Send + Sync {
         auth_contract_id: String,
         method_name: String,
         input_data: InputData,
+    ) -> anyhow::Result<bool> {
+        self.verify_at(auth_contract_id, method_name, input_data, None)
+            .await
+    }
+
+    /// Same as [`Verifier::verify`], but against the state at `at` if given
+    /// (block height, slot or masterchain seqno, depending on the chain).
+    async fn verify_at(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> anyhow::Result<bool>;
+
+    /// Calls `method_name` like [`Verifier::verify_at`], but returns the decoded return value
+    /// instead of applying the implicit success rule of the chain. Used for auth calls that carry
+    /// an [`Expectation`](hot_validation_primitives::bridge::expectation::Expectation).
+    async fn call(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> anyhow::Result<ReturnValue>;
+
+    /// Balance of `holder` in the token `token_id`, queried the standard way of the chain,
+    /// see [`TokenBalanceInputData`](hot_validation_primitives::bridge::balance::TokenBalanceInputData).
+    async fn token_balance(
+        &self,
+        token_id: String,
+        holder: String,
+        at: Option<u64>,
+    ) -> anyhow::Result<ReturnValue>;
+
+    /// Whether the transaction was executed successfully and has enough confirmations,
+    /// see [`TransactionInputData`].
+    async fn is_transaction_successful(&self, input: TransactionInputData) -> anyhow::Result<bool>;
+
+    /// Whether the bridge `contract_id` on this chain has already executed `withdrawal`.
+    /// Solana and TON track the last nonce per receiver, so they also need
+    /// [`CompletedWithdrawal::receiver_address`].
+    async fn is_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
     ) -> anyhow::Result<bool>;
 }
@@ -1,16 +1,24 @@
 mod types;
 
+use crate::head_tracker::{HeadSource, HeadTracker, Heads};
 use crate::http_client::post_json_receive_json;
+use crate::revert::RevertReason;
 use crate::threshold_verifier::{Identifiable, ThresholdVerifier};
-use crate::verifiers::near::types::{GetWalletArgs, RpcRequest, RpcResponse, VerifyArgs};
+use crate::verifiers::near::types::{
+    query_error, BlockReference, BlockRequest, BlockResponse, GetWalletArgs, RpcRequest,
+    RpcResponse, TxStatusRequest, TxStatusResponse, VerifyArgs,
+};
 use crate::{
-    metrics, AuthMethod, ChainValidationConfig, WalletAuthMethods, HOT_VERIFY_METHOD_NAME,
-    MPC_GET_WALLET_METHOD, MPC_HOT_WALLET_CONTRACT,
+    metrics, AuthMethod, ChainValidationConfig, NetworkProfile, WalletAuthMethods,
+    FT_BALANCE_OF_METHOD, HOT_VERIFY_METHOD_NAME, MPC_GET_WALLET_METHOD,
 };
-use anyhow::Result;
-use hot_validation_primitives::bridge::HotVerifyResult;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use hot_validation_primitives::bridge::expectation::ReturnValue;
+use hot_validation_primitives::bridge::transaction::TransactionId;
+use hot_validation_primitives::bridge::{HotVerifyAuthCall, HotVerifyResult, InputData};
 use hot_validation_primitives::uid::WalletId;
-use hot_validation_primitives::ChainId;
+use hot_validation_primitives::{ChainFamily, ChainId, Finality, FinalityLevel};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -22,26 +30,104 @@ use tracing::instrument;
 pub struct NearVerifier {
     client: Arc<reqwest::Client>,
     server: String,
+    /// The contract that stores wallets, see [`NetworkProfile::near_registry_contract`].
+    registry_contract: String,
+    finality: Finality,
+    head_tracker: Option<Arc<HeadTracker>>,
 }
 
 impl Identifiable for NearVerifier {
     fn id(&self) -> String {
         self.server.clone()
     }
+
+    fn is_lagging(&self) -> bool {
+        self.head_tracker
+            .as_ref()
+            .is_some_and(|tracker| tracker.is_lagging(&self.server))
+    }
+}
+
+#[async_trait]
+impl HeadSource for NearVerifier {
+    /// Only the head of the configured finality is read besides the latest one.
+    async fn fetch_heads(&self) -> Result<Heads> {
+        let latest = self
+            .fetch_height(BlockReference::from(FinalityLevel::Optimistic))
+            .await?;
+        let heads = Heads::latest(latest);
+        let level = self.finality.level;
+        if level == FinalityLevel::Optimistic {
+            return Ok(heads);
+        }
+        let head = self.fetch_height(BlockReference::from(level)).await?;
+        Ok(heads.with(level, head))
+    }
 }
 
 impl NearVerifier {
-    fn new(client: Arc<reqwest::Client>, server: String) -> Self {
-        Self { client, server }
+    fn new(
+        client: Arc<reqwest::Client>,
+        server: String,
+        registry_contract: String,
+        finality: Finality,
+    ) -> Self {
+        Self {
+            client,
+            server,
+            registry_contract,
+            finality,
+            head_tracker: None,
+        }
+    }
+
+    /// Reads the heads from `head_tracker` instead of fetching them, while they are fresh.
+    #[must_use]
+    fn with_head_tracker(mut self, head_tracker: Arc<HeadTracker>) -> Self {
+        self.head_tracker = Some(head_tracker);
+        self
+    }
+
+    async fn fetch_height(&self, block_reference: BlockReference) -> Result<u64> {
+        let request = BlockRequest::build(block_reference);
+        let response: BlockResponse =
+            post_json_receive_json(&self.client, &self.server, &request, ChainId::Near).await?;
+        Ok(response.height())
+    }
+
+    /// The block view calls are executed against: `at` if pinned, otherwise the one selected by finality.
+    async fn block_reference(&self, at: Option<u64>) -> Result<BlockReference> {
+        if let Some(height) = at {
+            return Ok(BlockReference::BlockId(height));
+        }
+        let block_reference = BlockReference::from(self.finality.level);
+        let min_confirmations = self.finality.min_confirmations();
+        if min_confirmations == 0 {
+            return Ok(block_reference);
+        }
+        let tracked = self
+            .head_tracker
+            .as_ref()
+            .and_then(|tracker| tracker.heads(&self.server))
+            .and_then(|heads| heads.at(self.finality.level));
+        let height = match tracked {
+            Some(height) => height,
+            None => self.fetch_height(block_reference).await?,
+        };
+        let height = height
+            .checked_sub(min_confirmations)
+            .context("Not enough blocks for the required confirmations")?;
+        Ok(BlockReference::BlockId(height))
     }
 
     #[instrument(skip(self), err(Debug))]
-    async fn get_wallet(&self, wallet_id: WalletId) -> Result<WalletAuthMethods> {
+    async fn get_wallet(&self, wallet_id: WalletId, at: Option<u64>) -> Result<WalletAuthMethods> {
         let wallet_id = GetWalletArgs { wallet_id };
         self.call_view_method(
-            MPC_HOT_WALLET_CONTRACT.to_string(),
+            self.registry_contract.clone(),
             MPC_GET_WALLET_METHOD.to_string(),
             &wallet_id,
+            at,
         )
         .await
     }
@@ -53,6 +139,7 @@ impl NearVerifier {
         message: Vec<u8>,
         message_body: String,
         user_payload: String,
+        at: Option<u64>,
     ) -> Result<HotVerifyResult> {
         #[derive(Debug, Deserialize)]
         struct MethodName {
@@ -76,23 +163,65 @@ impl NearVerifier {
             msg_body: message_body.clone(),
         };
 
-        self.call_view_method(auth_method.account_id, method_name, &args)
+        self.call_view_method(auth_method.account_id, method_name, &args, at)
             .await
     }
 
+    /// `ft_balance_of` of the NEP-141 token `token_id`.
+    async fn ft_balance_of(
+        &self,
+        token_id: String,
+        holder: String,
+        at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        #[derive(Serialize)]
+        struct BalanceArgs {
+            account_id: String,
+        }
+        let balance: String = self
+            .call_view_method(
+                token_id,
+                FT_BALANCE_OF_METHOD.to_string(),
+                BalanceArgs { account_id: holder },
+                at,
+            )
+            .await?;
+        balance.parse()
+    }
+
+    /// Final outcome of the transaction, `SuccessReceiptId` counts as success,
+    /// since the outcome of the receipts isn't checked.
+    async fn is_transaction_successful(&self, hash: String, sender_id: String) -> Result<bool> {
+        let request = TxStatusRequest::build(&hash, &sender_id);
+        let response: TxStatusResponse =
+            post_json_receive_json(&self.client, &self.server, &request, ChainId::Near).await?;
+        Ok(response.is_successful())
+    }
+
     async fn call_view_method<R, T>(
         &self,
         account_id: String,
         method_name: String,
         args: T,
+        at: Option<u64>,
     ) -> Result<R>
     where
         T: Serialize + Sized,
         R: DeserializeOwned,
     {
-        let rpc_args = RpcRequest::build(&account_id, &method_name, &args);
-        let result: RpcResponse<R> =
+        let block_reference = self.block_reference(at).await?;
+        let rpc_args = RpcRequest::build(&account_id, &method_name, &args, block_reference);
+        let response: serde_json::Value =
             post_json_receive_json(&self.client, &self.server, &rpc_args, ChainId::Near).await?;
+        if let Some(error) = query_error(&response) {
+            if let Some(reason) = RevertReason::from_near_error(error) {
+                return Err(reason)
+                    .with_context(|| format!("{method_name} of {account_id} failed: {error}"));
+            }
+            bail!("{method_name} of {account_id} failed: {error}");
+        }
+        let result: RpcResponse<R> = serde_json::from_value(response)
+            .with_context(|| format!("Invalid response of {method_name} of {account_id}"))?;
         Ok(result.unpack())
     }
 }
@@ -101,31 +230,51 @@ impl ThresholdVerifier<NearVerifier> {
     pub(crate) fn new_near(
         near_validation_config: ChainValidationConfig,
         client: &Arc<reqwest::Client>,
+        network: &NetworkProfile,
     ) -> Self {
         let threshold = near_validation_config.threshold;
+        let finality = near_validation_config.finality_or_default(ChainFamily::Near);
+        let head_tracker =
+            HeadTracker::new(ChainId::Near, near_validation_config.head_poll_interval());
         let servers = near_validation_config.servers;
-        let callers = servers
+        let callers: Vec<_> = servers
             .iter()
             .map(|s| {
-                let verifier = NearVerifier::new(client.clone(), s.clone());
+                let verifier = NearVerifier::new(
+                    client.clone(),
+                    s.clone(),
+                    network.near_registry_contract.clone(),
+                    finality,
+                )
+                .with_head_tracker(head_tracker.clone());
                 Arc::new(verifier)
             })
             .collect();
+        head_tracker.spawn(&callers);
         Self {
             threshold,
             verifiers: callers,
         }
     }
 
-    #[instrument(skip(self), err(Debug))]
     pub async fn get_wallet_auth_methods(
         self: &Arc<Self>,
         wallet_id: WalletId,
+    ) -> Result<WalletAuthMethods> {
+        self.get_wallet_auth_methods_at(wallet_id, None).await
+    }
+
+    /// Same as [`Self::get_wallet_auth_methods`], at block height `at` if given.
+    #[instrument(skip(self), err(Debug))]
+    pub async fn get_wallet_auth_methods_at(
+        self: &Arc<Self>,
+        wallet_id: WalletId,
+        at: Option<u64>,
     ) -> Result<WalletAuthMethods> {
         let _timer = metrics::RPC_GET_AUTH_METHODS_DURATION.start_timer();
         self.threshold_call(move |verifier| {
             let wallet_id = wallet_id.clone();
-            async move { verifier.get_wallet(wallet_id).await }
+            async move { verifier.get_wallet(wallet_id, at).await }
         })
         .await
     }
@@ -137,6 +286,27 @@ impl ThresholdVerifier<NearVerifier> {
         message: Vec<u8>,
         message_body: String,
         user_payload: String,
+    ) -> Result<HotVerifyResult> {
+        self.verify_at(
+            wallet_id,
+            auth_method,
+            message,
+            message_body,
+            user_payload,
+            None,
+        )
+        .await
+    }
+
+    /// Same as [`Self::verify`], at block height `at` if given.
+    pub async fn verify_at(
+        &self,
+        wallet_id: WalletId,
+        auth_method: AuthMethod,
+        message: Vec<u8>,
+        message_body: String,
+        user_payload: String,
+        at: Option<u64>,
     ) -> Result<HotVerifyResult> {
         self.threshold_call(move |verifier| {
             let wallet_id = wallet_id.clone();
@@ -146,13 +316,55 @@ impl ThresholdVerifier<NearVerifier> {
             let user_payload = user_payload.clone();
             async move {
                 verifier
-                    .verify(wallet_id, auth_method, message, message_body, user_payload)
+                    .verify(
+                        wallet_id,
+                        auth_method,
+                        message,
+                        message_body,
+                        user_payload,
+                        at,
+                    )
                     .await
             }
         })
         .await
     }
 
+    /// Checks an auth call to NEAR, which can only be a built-in check:
+    /// a token balance or a transaction outcome.
+    pub async fn verify_auth_call(
+        &self,
+        auth_call: HotVerifyAuthCall,
+        at: Option<u64>,
+    ) -> Result<bool> {
+        match auth_call.input {
+            InputData::TokenBalance(input) => {
+                let token_id = auth_call.contract_id;
+                let holder = input.holder.clone();
+                let balance = self
+                    .threshold_call(move |verifier| {
+                        let token_id = token_id.clone();
+                        let holder = holder.clone();
+                        async move { verifier.ft_balance_of(token_id, holder, at).await }
+                    })
+                    .await?;
+                input.is_satisfied_by(&balance)
+            }
+            InputData::Transaction(input) => {
+                let TransactionId::Near { hash, sender_id } = input.transaction else {
+                    bail!("Expected a NEAR transaction, got {:?}", input.transaction);
+                };
+                self.threshold_call(move |verifier| {
+                    let hash = hash.clone();
+                    let sender_id = sender_id.clone();
+                    async move { verifier.is_transaction_successful(hash, sender_id).await }
+                })
+                .await
+            }
+            _ => bail!("Auth call should not lead to NEAR, unless it's a built-in check"),
+        }
+    }
+
     pub async fn call_view_method<T, R>(
         &self,
         account_id: String,
@@ -169,7 +381,7 @@ impl ThresholdVerifier<NearVerifier> {
             let args = args.clone();
             async move {
                 verifier
-                    .call_view_method(account_id, method_name, args)
+                    .call_view_method(account_id, method_name, args, None)
                     .await
             }
         })
@@ -183,14 +395,28 @@ pub(crate) mod tests {
 
     use crate::test_data::near_rpc;
     use crate::threshold_verifier::ThresholdVerifier;
+    use crate::verifiers::near::types::{BlockReference, RpcRequest};
     use crate::verifiers::near::NearVerifier;
-    use crate::{AuthMethod, WalletAuthMethods};
+    use crate::{AuthMethod, NetworkProfile, WalletAuthMethods, MPC_HOT_WALLET_CONTRACT};
     use anyhow::Result;
     use hot_validation_primitives::uid::WalletId;
-    use hot_validation_primitives::ChainValidationConfig;
+    use hot_validation_primitives::{ChainFamily, ChainValidationConfig, Finality, FinalityLevel};
     use std::str::FromStr;
     use std::sync::Arc;
 
+    #[test]
+    fn rpc_request_block_reference() {
+        let args = serde_json::json!({});
+        let request = RpcRequest::build("a.near", "foo", &args, BlockReference::BlockId(42));
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["params"]["block_id"], 42);
+        assert!(json["params"].get("finality").is_none());
+
+        let request = RpcRequest::build("a.near", "foo", &args, FinalityLevel::Final.into());
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["params"]["finality"], "final");
+    }
+
     fn sample_wallet_id() -> WalletId {
         WalletId::from_str("A8NpkSkn1HZPYjxJRCpD4iPhDHzP81bbduZTqPpHmEgn").unwrap()
     }
@@ -198,7 +424,12 @@ pub(crate) mod tests {
     #[tokio::test]
     async fn near_single_verifier() {
         let client = Arc::new(reqwest::Client::new());
-        let rpc_caller = NearVerifier::new(client, near_rpc());
+        let rpc_caller = NearVerifier::new(
+            client,
+            near_rpc(),
+            MPC_HOT_WALLET_CONTRACT.to_string(),
+            Finality::default_for(ChainFamily::Near),
+        );
 
         let wallet_id = sample_wallet_id();
 
@@ -215,7 +446,14 @@ pub(crate) mod tests {
         let user_payload = r#"{"auth_method":0,"signatures":["HZUhhJamfp8GJLL8gEa2F2qZ6TXPu4PYzzWkDqsTQsMcW9rQsG2Hof4eD2Vex6he2fVVy3UNhgi631CY8E9StAH"]}"#.to_string();
 
         rpc_caller
-            .verify(wallet_id, auth_method, message, message_body, user_payload)
+            .verify(
+                wallet_id,
+                auth_method,
+                message,
+                message_body,
+                user_payload,
+                None,
+            )
             .await
             .unwrap();
     }
@@ -224,7 +462,12 @@ pub(crate) mod tests {
     #[should_panic]
     async fn near_single_verifier_bad_wallet() {
         let client = Arc::new(reqwest::Client::new());
-        let rpc_caller = NearVerifier::new(client, near_rpc());
+        let rpc_caller = NearVerifier::new(
+            client,
+            near_rpc(),
+            MPC_HOT_WALLET_CONTRACT.to_string(),
+            Finality::default_for(ChainFamily::Near),
+        );
 
         let wallet_id = WalletId::from_str("B8NpkSkn1HZPYjxJRCpD4iPhDHzP81bbduZTqPpHmEgn").unwrap();
 
@@ -241,7 +484,14 @@ pub(crate) mod tests {
         let user_payload = r#"{"auth_method":0,"signatures":["HZUhhJamfp8GJLL8gEa2F2qZ6TXPu4PYzzWkDqsTQsMcW9rQsG2Hof4eD2Vex6he2fVVy3UNhgi631CY8E9StAH"]}"#.to_string();
 
         rpc_caller
-            .verify(wallet_id, auth_method, message, message_body, user_payload)
+            .verify(
+                wallet_id,
+                auth_method,
+                message,
+                message_body,
+                user_payload,
+                None,
+            )
             .await
             .unwrap();
     }
@@ -250,7 +500,12 @@ pub(crate) mod tests {
     #[should_panic]
     async fn near_single_verifier_bad_auth_contract() {
         let client = Arc::new(reqwest::Client::new());
-        let rpc_caller = NearVerifier::new(client, near_rpc());
+        let rpc_caller = NearVerifier::new(
+            client,
+            near_rpc(),
+            MPC_HOT_WALLET_CONTRACT.to_string(),
+            Finality::default_for(ChainFamily::Near),
+        );
 
         let wallet_id = sample_wallet_id();
 
@@ -267,7 +522,14 @@ pub(crate) mod tests {
         let user_payload = r#"{"auth_method":0,"signatures":["HZUhhJamfp8GJLL8gEa2F2qZ6TXPu4PYzzWkDqsTQsMcW9rQsG2Hof4eD2Vex6he2fVVy3UNhgi631CY8E9StAH"]}"#.to_string();
 
         rpc_caller
-            .verify(wallet_id, auth_method, message, message_body, user_payload)
+            .verify(
+                wallet_id,
+                auth_method,
+                message,
+                message_body,
+                user_payload,
+                None,
+            )
             .await
             .unwrap();
     }
@@ -275,7 +537,12 @@ pub(crate) mod tests {
     #[tokio::test]
     async fn near_single_verifier_bad_msg_hash() -> Result<()> {
         let client = Arc::new(reqwest::Client::new());
-        let rpc_caller = NearVerifier::new(client, near_rpc());
+        let rpc_caller = NearVerifier::new(
+            client,
+            near_rpc(),
+            MPC_HOT_WALLET_CONTRACT.to_string(),
+            Finality::default_for(ChainFamily::Near),
+        );
 
         let wallet_id = sample_wallet_id();
 
@@ -292,7 +559,14 @@ pub(crate) mod tests {
         let user_payload = r#"{"auth_method":0,"signatures":["HZUhhJamfp8GJLL8gEa2F2qZ6TXPu4PYzzWkDqsTQsMcW9rQsG2Hof4eD2Vex6he2fVVy3UNhgi631CY8E9StAH"]}"#.to_string();
 
         let result = rpc_caller
-            .verify(wallet_id, auth_method, message, message_body, user_payload)
+            .verify(
+                wallet_id,
+                auth_method,
+                message,
+                message_body,
+                user_payload,
+                None,
+            )
             .await;
         assert!(result.is_err());
         Ok(())
@@ -309,8 +583,12 @@ pub(crate) mod tests {
                     "https://nearrpc.aurora.dev".to_string(),
                     near_rpc(),
                 ],
+                finality: None,
+                head_poll_interval_ms: None,
+                max_log_block_range: None,
             },
             &Arc::new(reqwest::Client::new()),
+            &NetworkProfile::mainnet(),
         );
 
         let wallet_id = sample_wallet_id();
@@ -351,8 +629,12 @@ pub(crate) mod tests {
                     "https://hello.com".to_string(),
                     near_rpc(),
                 ],
+                finality: None,
+                head_poll_interval_ms: None,
+                max_log_block_range: None,
             },
             &Arc::new(reqwest::Client::new()),
+            &NetworkProfile::mainnet(),
         );
 
         let wallet_id = sample_wallet_id();
@@ -378,7 +660,12 @@ pub(crate) mod tests {
     #[tokio::test]
     async fn near_single_verifier_get_wallet() {
         let client = Arc::new(reqwest::Client::new());
-        let rpc_caller = NearVerifier::new(client, near_rpc());
+        let rpc_caller = NearVerifier::new(
+            client,
+            near_rpc(),
+            MPC_HOT_WALLET_CONTRACT.to_string(),
+            Finality::default_for(ChainFamily::Near),
+        );
 
         let wallet_id = sample_wallet_id();
 
@@ -389,14 +676,19 @@ pub(crate) mod tests {
             }],
         };
 
-        let actual = rpc_caller.get_wallet(wallet_id).await.unwrap();
+        let actual = rpc_caller.get_wallet(wallet_id, None).await.unwrap();
         assert_eq!(actual.access_list, expected.access_list);
     }
 
     #[tokio::test]
     async fn near_single_verifier_get_wallet_with_meta() {
         let client = Arc::new(reqwest::Client::new());
-        let rpc_caller = NearVerifier::new(client, near_rpc());
+        let rpc_caller = NearVerifier::new(
+            client,
+            near_rpc(),
+            MPC_HOT_WALLET_CONTRACT.to_string(),
+            Finality::default_for(ChainFamily::Near),
+        );
 
         let wallet_id = WalletId::from_str("Puvk3GR7bvBmJqg2Sdzs4D2AFGAW3rXq9iwpJraBkGJ").unwrap();
         let expected = WalletAuthMethods {
@@ -406,7 +698,7 @@ pub(crate) mod tests {
             }],
         };
 
-        let actual = rpc_caller.get_wallet(wallet_id).await.unwrap();
+        let actual = rpc_caller.get_wallet(wallet_id, None).await.unwrap();
         assert_eq!(actual.access_list, expected.access_list);
     }
 
@@ -421,8 +713,12 @@ pub(crate) mod tests {
                     "https://nearrpc.aurora.dev".to_string(),
                     near_rpc(),
                 ],
+                finality: None,
+                head_poll_interval_ms: None,
+                max_log_block_range: None,
             },
             &Arc::new(reqwest::Client::new()),
+            &NetworkProfile::mainnet(),
         );
 
         let wallet_id = sample_wallet_id();
@@ -456,8 +752,12 @@ pub(crate) mod tests {
                     "https://nearrpc.aurora.dev".to_string(),
                     near_rpc(),
                 ],
+                finality: None,
+                head_poll_interval_ms: None,
+                max_log_block_range: None,
             },
             &Arc::new(reqwest::Client::new()),
+            &NetworkProfile::mainnet(),
         );
 
         let expected = WalletAuthMethods {
@@ -1,5 +1,6 @@
 use crate::verifiers::near::types::base64_json::Base64OfJson;
 use hot_validation_primitives::uid::WalletId;
+use hot_validation_primitives::FinalityLevel;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::serde_as;
@@ -40,16 +41,130 @@ impl<'a, T> RpcRequest<'a, T>
 where
     T: Serialize + ?Sized,
 {
-    pub fn build(account_id: &'a str, method_name: &'a str, args: &'a T) -> Self {
+    pub fn build(
+        account_id: &'a str,
+        method_name: &'a str,
+        args: &'a T,
+        block_reference: BlockReference,
+    ) -> Self {
         Self {
             jsonrpc: "2.0",
             id: "dontcare",
             method: "query",
-            params: RpcParams::build(account_id, method_name, args),
+            params: RpcParams::build(account_id, method_name, args, block_reference),
         }
     }
 }
 
+/// Which block a query is executed against, flattened into the request params.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BlockReference {
+    Finality(&'static str),
+    BlockId(u64),
+}
+
+impl From<FinalityLevel> for BlockReference {
+    fn from(level: FinalityLevel) -> Self {
+        match level {
+            FinalityLevel::Optimistic => Self::Finality("optimistic"),
+            FinalityLevel::Confirmed => Self::Finality("near-final"),
+            FinalityLevel::Final => Self::Finality("final"),
+        }
+    }
+}
+
+/// `block` request, used to find the head for the given finality.
+#[derive(Serialize)]
+pub(crate) struct BlockRequest {
+    jsonrpc: &'static str,
+    id: &'static str,
+    method: &'static str,
+    params: BlockReference,
+}
+
+impl BlockRequest {
+    pub fn build(block_reference: BlockReference) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "block",
+            params: block_reference,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BlockResponse {
+    result: BlockResult,
+}
+
+#[derive(Deserialize)]
+struct BlockResult {
+    header: BlockHeader,
+}
+
+#[derive(Deserialize)]
+struct BlockHeader {
+    height: u64,
+}
+
+impl BlockResponse {
+    pub fn height(&self) -> u64 {
+        self.result.header.height
+    }
+}
+
+/// `tx` request, waits until the transaction is final.
+#[derive(Serialize)]
+pub(crate) struct TxStatusRequest<'a> {
+    jsonrpc: &'static str,
+    id: &'static str,
+    method: &'static str,
+    params: TxStatusParams<'a>,
+}
+
+#[derive(Serialize)]
+struct TxStatusParams<'a> {
+    tx_hash: &'a str,
+    sender_account_id: &'a str,
+    wait_until: &'static str,
+}
+
+impl<'a> TxStatusRequest<'a> {
+    pub fn build(tx_hash: &'a str, sender_account_id: &'a str) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "tx",
+            params: TxStatusParams {
+                tx_hash,
+                sender_account_id,
+                wait_until: "FINAL",
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TxStatusResponse {
+    result: TxStatusResult,
+}
+
+#[derive(Deserialize)]
+struct TxStatusResult {
+    /// `{"SuccessValue": ...}`, `{"SuccessReceiptId": ...}` or `{"Failure": ...}`.
+    status: serde_json::Value,
+}
+
+impl TxStatusResponse {
+    pub fn is_successful(&self) -> bool {
+        self.result.status.get("Failure").is_none()
+            && (self.result.status.get("SuccessValue").is_some()
+                || self.result.status.get("SuccessReceiptId").is_some())
+    }
+}
+
 #[serde_as]
 #[derive(Serialize)]
 struct RpcParams<'a, T>
@@ -57,7 +172,8 @@ where
     T: Serialize + ?Sized,
 {
     request_type: &'static str,
-    finality: &'static str,
+    #[serde(flatten)]
+    block_reference: BlockReference,
     account_id: &'a str,
     method_name: &'a str,
     #[serde_as(as = "Base64OfJson")]
@@ -69,10 +185,15 @@ impl<'a, T> RpcParams<'a, T>
 where
     T: Serialize + ?Sized,
 {
-    pub fn build(account_id: &'a str, method_name: &'a str, args: &'a T) -> Self {
+    pub fn build(
+        account_id: &'a str,
+        method_name: &'a str,
+        args: &'a T,
+        block_reference: BlockReference,
+    ) -> Self {
         Self {
             request_type: "call_function",
-            finality: "final",
+            block_reference,
             account_id,
             method_name,
             args,
@@ -97,6 +218,14 @@ pub mod base64_json {
     }
 }
 
+/// Error of a failed `query`: contract failures are returned in `result.error` by older nodes,
+/// and as a JSON-RPC error by newer ones.
+pub(crate) fn query_error(response: &serde_json::Value) -> Option<&str> {
+    ["/result/error", "/error/data", "/error/message"]
+        .into_iter()
+        .find_map(|pointer| response.pointer(pointer)?.as_str())
+}
+
 #[derive(serde::Deserialize)]
 #[serde(bound(deserialize = "T: DeserializeOwned"))]
 pub(crate) struct RpcResponse<T> {
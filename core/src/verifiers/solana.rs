@@ -1,232 +0,0 @@
-use crate::http_client::TIMEOUT;
-use crate::threshold_verifier::{Identifiable, ThresholdVerifier};
-use crate::verifiers::Verifier;
-use anyhow::{anyhow, ensure, Context, Result};
-use async_trait::async_trait;
-use borsh::BorshDeserialize;
-use hot_validation_primitives::bridge::solana::{
-    anchor, DepositWithProof, SolanaInputData, UserAccount,
-};
-use hot_validation_primitives::bridge::{CompletedWithdrawal, InputData};
-use hot_validation_primitives::{ChainValidationConfig, ExtendedChainId};
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_client::rpc_config::RpcSimulateTransactionConfig;
-use solana_commitment_config::CommitmentConfig;
-use solana_sdk::message::Address;
-use solana_sdk::pubkey::Pubkey;
-use solana_sdk::transaction::Transaction;
-use std::str::FromStr;
-use std::sync::Arc;
-
-pub struct SolanaVerifier {
-    client: RpcClient,
-    server: String,
-}
-
-impl Identifiable for SolanaVerifier {
-    fn id(&self) -> String {
-        self.server.clone()
-    }
-}
-
-impl SolanaVerifier {
-    pub fn new(server: String) -> Self {
-        Self {
-            client: RpcClient::new_with_timeout(server.clone(), TIMEOUT),
-            server,
-        }
-    }
-
-    fn get_simulation_config() -> RpcSimulateTransactionConfig {
-        RpcSimulateTransactionConfig {
-            sig_verify: false,
-            replace_recent_blockhash: true,
-            commitment: Some(CommitmentConfig::confirmed()),
-            ..RpcSimulateTransactionConfig::default()
-        }
-    }
-
-    async fn handle_deposit(
-        &self,
-        program_id: &Address,
-        method_name: &str,
-        deposit_data: DepositWithProof,
-    ) -> Result<()> {
-        let simulation_config = Self::get_simulation_config();
-        let message = deposit_data.get_message(program_id, method_name)?;
-        let tx = Transaction::new_unsigned(message);
-        let resp = self
-            .client
-            .simulate_transaction_with_config(&tx, simulation_config)
-            .await?;
-        if let Some(err) = resp.value.err {
-            return Err(anyhow!(
-                "hot_verify_deposit reverted: {err:?}; logs={:?}",
-                resp.value.logs
-            ));
-        }
-        Ok(())
-    }
-
-    async fn handle_completed_withdrawal(
-        &self,
-        program_id: &Address,
-        completed_withdrawal_data: CompletedWithdrawal,
-    ) -> Result<()> {
-        let user_pk = completed_withdrawal_data.get_user_address(program_id)?;
-
-        let data = self
-            .client
-            .get_account_data(&user_pk)
-            .await
-            .with_context(|| format!("failed to fetch account data for {user_pk}"))?;
-
-        let disc = anchor::account_discriminator("User");
-        if data.len() < 8 || data[..8] != disc {
-            return Err(anyhow!(
-                "account {user_pk} is not an Anchor `User` (bad discriminator)"
-            ));
-        }
-
-        // Deserialize the struct after the 8-byte discriminator
-        let user = UserAccount::try_from_slice(&data[8..])
-            .context("failed to Borsh-deserialize `User`")?;
-        ensure!(
-            completed_withdrawal_data.nonce <= user.last_withdraw_nonce,
-            "Nonce is not used: got {}, last used: {}",
-            completed_withdrawal_data.nonce,
-            user.last_withdraw_nonce
-        );
-        Ok(())
-    }
-}
-
-#[async_trait]
-impl Verifier for SolanaVerifier {
-    fn chain_id(&self) -> ExtendedChainId {
-        ExtendedChainId::Solana
-    }
-
-    async fn verify(
-        &self,
-        auth_contract_id: String,
-        method_name: String,
-        input_data: InputData,
-    ) -> Result<bool> {
-        let input: SolanaInputData = input_data.try_into()?;
-        let program_id = Pubkey::from_str(&auth_contract_id)?;
-        match input {
-            SolanaInputData::Deposit(deposit_with_proof) => {
-                self.handle_deposit(&program_id, &method_name, deposit_with_proof)
-                    .await?;
-            }
-            SolanaInputData::CheckCompletedWithdrawal(completed_withdrawal_data) => {
-                self.handle_completed_withdrawal(&program_id, completed_withdrawal_data)
-                    .await?;
-            }
-        }
-        Ok(true)
-    }
-}
-
-impl ThresholdVerifier<SolanaVerifier> {
-    pub fn new_solana(config: &ChainValidationConfig) -> Self {
-        let verifiers = config
-            .servers
-            .iter()
-            .map(|server| Arc::new(SolanaVerifier::new(server.clone())))
-            .collect::<Vec<_>>();
-        Self {
-            threshold: config.threshold,
-            verifiers,
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::SolanaVerifier;
-
-    use hot_validation_primitives::bridge::solana::{DepositWithProof, SolanaInputData};
-
-    use crate::verifiers::Verifier;
-    use hot_validation_primitives::bridge::{CompletedWithdrawal, DepositData};
-    use serde_json::json;
-
-    fn get_deposit_data() -> DepositData {
-        let json = json!({
-                "sender": "5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho",
-                "receiver": "BJu6S7gT4gnx7AXPnghM7aYiS5dPfSUixqAZJq1Uqf4V",
-                "token_id": "BYPsjxa3YuZESQz1dKuBw1QSFCSpecsm8nCQhY5xbU1Z",
-                "amount": "10000000",
-                "nonce": "1757984522000007228"
-            }
-        );
-        serde_json::from_value(json).unwrap()
-    }
-
-    fn get_deposit_with_proof() -> DepositWithProof {
-        let deposit_data = get_deposit_data();
-        let proof: [u8; 32] =
-            hex::decode("47b8b751a0d90d113e4e16678ebda646a01a02d376f49f666ddd17ee9f383c2f")
-                .unwrap()
-                .try_into()
-                .unwrap();
-        DepositWithProof {
-            proof,
-            deposit_data,
-        }
-    }
-
-    fn get_completed_withdrawal_data(nonce: &str) -> CompletedWithdrawal {
-        let json = json!({
-            "nonce": nonce,
-            "receiver_address": "5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho",
-        });
-        serde_json::from_value(json).unwrap()
-    }
-
-    #[tokio::test]
-    async fn deposit_verification() -> anyhow::Result<()> {
-        let verifier = SolanaVerifier::new("https://api.mainnet-beta.solana.com".to_string());
-        let auth_contract = "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC".to_string();
-        let method_name = "hot_verify_deposit".to_string();
-        let input = SolanaInputData::Deposit(get_deposit_with_proof());
-
-        verifier
-            .verify(auth_contract, method_name, input.into())
-            .await?;
-        Ok(())
-    }
-
-    #[tokio::test]
-    async fn completed_withdrawal_verification_low() -> anyhow::Result<()> {
-        let verifier = SolanaVerifier::new("https://api.mainnet-beta.solana.com".to_string());
-        let auth_contract = "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC".to_string();
-        let method_name = String::new();
-        let input = SolanaInputData::CheckCompletedWithdrawal(get_completed_withdrawal_data(
-            "1749390032000000032243",
-        ));
-
-        verifier
-            .verify(auth_contract, method_name, input.into())
-            .await?;
-        Ok(())
-    }
-
-    #[tokio::test]
-    async fn completed_withdrawal_verification_high() -> anyhow::Result<()> {
-        let verifier = SolanaVerifier::new("https://api.mainnet-beta.solana.com".to_string());
-        let auth_contract = "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC".to_string();
-        let method_name = String::new();
-        let input = SolanaInputData::CheckCompletedWithdrawal(get_completed_withdrawal_data(
-            "2749390032000000032243",
-        ));
-
-        let result = verifier
-            .verify(auth_contract, method_name, input.into())
-            .await;
-        result.expect_err("expected error");
-        Ok(())
-    }
-}
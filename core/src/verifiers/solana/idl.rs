@@ -0,0 +1,538 @@
+//! Decoding of Anchor accounts with the IDL of their program, see [`AnchorAccountField`].
+//!
+//! Only the part of the IDL that describes the account layouts is read: `accounts` and `types`,
+//! in both the legacy (< 0.30) and the current format.
+//!
+//! [`AnchorAccountField`]: hot_validation_primitives::bridge::solana::AnchorAccountField
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use hot_validation_primitives::bridge::expectation::ReturnValue;
+use hot_validation_primitives::bridge::solana::anchor;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Guards against self-referencing types in a malformed IDL.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Deserialize)]
+pub struct AnchorIdl {
+    /// Program id, only present in the current format.
+    #[serde(default)]
+    address: Option<String>,
+    #[serde(default)]
+    accounts: Vec<IdlAccount>,
+    #[serde(default)]
+    types: Vec<IdlTypeDef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlAccount {
+    name: String,
+    /// Missing in the legacy format, where it's derived from the name.
+    #[serde(default)]
+    discriminator: Option<Vec<u8>>,
+    /// The layout is inlined in the legacy format, and is in `types` in the current one.
+    #[serde(rename = "type", default)]
+    ty: Option<IdlTypeDefTy>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlTypeDef {
+    name: String,
+    /// `borsh` if not set, zero-copy accounts use `bytemuck`.
+    #[serde(default)]
+    serialization: Option<String>,
+    #[serde(rename = "type")]
+    ty: IdlTypeDefTy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum IdlTypeDefTy {
+    Struct {
+        #[serde(default)]
+        fields: Option<IdlFields>,
+    },
+    Enum {
+        variants: Vec<IdlEnumVariant>,
+    },
+    Type {
+        alias: IdlType,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlEnumVariant {
+    name: String,
+    #[serde(default)]
+    fields: Option<IdlFields>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum IdlFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlField {
+    name: String,
+    #[serde(rename = "type")]
+    ty: IdlType,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum IdlType {
+    /// `bool`, `u64`, `pubkey`, `string`, ...
+    Primitive(String),
+    Option {
+        option: Box<IdlType>,
+    },
+    Vec {
+        vec: Box<IdlType>,
+    },
+    Array {
+        array: (Box<IdlType>, IdlArrayLen),
+    },
+    Defined {
+        defined: IdlDefined,
+    },
+    /// Generics and other types we can't decode, the IDL still loads.
+    Unsupported(serde_json::Value),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum IdlArrayLen {
+    Value(usize),
+    Generic(serde_json::Value),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum IdlDefined {
+    /// Legacy format.
+    Name(String),
+    Object {
+        name: String,
+    },
+}
+
+impl IdlDefined {
+    fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Object { name } => name,
+        }
+    }
+}
+
+/// A decoded Borsh value. Fields of tuple structs are named by their index.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Bool(bool),
+    Uint(u128),
+    Int(i128),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Pubkey(Pubkey),
+    Option(Option<Box<Value>>),
+    List(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+    Enum {
+        variant: String,
+        fields: Vec<(String, Value)>,
+    },
+}
+
+impl Value {
+    /// The field at the dot-separated `path`: struct fields by name, list elements by index.
+    /// `Some` options are looked through.
+    pub(crate) fn field(&self, path: &str) -> Result<&Self> {
+        let mut value = self;
+        for segment in path.split('.') {
+            while let Self::Option(option) = value {
+                value = option
+                    .as_deref()
+                    .with_context(|| format!("`{path}` is in a `None` option"))?;
+            }
+            value = match value {
+                Self::Struct(fields) => fields
+                    .iter()
+                    .find_map(|(name, field)| (name == segment).then_some(field))
+                    .with_context(|| format!("No field `{segment}` in `{path}`"))?,
+                Self::List(items) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get(index))
+                    .with_context(|| format!("No element `{segment}` in `{path}`"))?,
+                value => bail!("Can't get `{segment}` of `{path}` from {value:?}"),
+            };
+        }
+        Ok(value)
+    }
+
+    /// Only bools and integers can be checked by an expectation.
+    pub(crate) fn to_return_value(&self) -> Result<ReturnValue> {
+        match self {
+            Self::Bool(b) => Ok(ReturnValue::Bool(*b)),
+            Self::Uint(value) => Ok((*value).into()),
+            Self::Int(value) => Ok((*value).into()),
+            Self::Option(Some(value)) => value.to_return_value(),
+            value => bail!("Expected a bool or an integer, got {value:?}"),
+        }
+    }
+}
+
+/// Loads the IDLs of `NetworkProfile::solana_idls`, checking they belong to their program.
+pub fn load_idls(paths: &HashMap<String, PathBuf>) -> Result<HashMap<Pubkey, AnchorIdl>> {
+    paths
+        .iter()
+        .map(|(program_id, path)| {
+            let program_id = Pubkey::from_str(program_id)
+                .map_err(|e| anyhow!("Invalid program id {program_id}: {e}"))?;
+            let idl = AnchorIdl::from_file(path)?;
+            if let Some(address) = &idl.address {
+                ensure!(
+                    *address == program_id.to_string(),
+                    "IDL {} is of program {address}, not {program_id}",
+                    path.display()
+                );
+            }
+            Ok((program_id, idl))
+        })
+        .collect()
+}
+
+impl AnchorIdl {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read Anchor IDL {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Couldn't parse Anchor IDL {}", path.display()))
+    }
+
+    fn type_def(&self, name: &str) -> Result<&IdlTypeDef> {
+        self.types
+            .iter()
+            .find(|ty| ty.name == name)
+            .with_context(|| format!("No type `{name}` in the IDL"))
+    }
+
+    /// Checks the discriminator of the account `account_type` and decodes the rest of `data`.
+    pub(crate) fn decode_account(&self, account_type: &str, data: &[u8]) -> Result<Value> {
+        let account = self
+            .accounts
+            .iter()
+            .find(|account| account.name == account_type)
+            .with_context(|| format!("No account `{account_type}` in the IDL"))?;
+        let discriminator = account
+            .discriminator
+            .clone()
+            .unwrap_or_else(|| anchor::account_discriminator(account_type).to_vec());
+        ensure!(
+            data.starts_with(&discriminator),
+            "Not an Anchor `{account_type}` account (bad discriminator)"
+        );
+
+        let layout = match &account.ty {
+            Some(ty) => ty,
+            None => {
+                let type_def = self.type_def(account_type)?;
+                if let Some(serialization) = &type_def.serialization {
+                    ensure!(
+                        serialization == "borsh",
+                        "`{account_type}` uses {serialization} serialization, only borsh is supported"
+                    );
+                }
+                &type_def.ty
+            }
+        };
+        let mut decoder = Decoder {
+            idl: self,
+            data: &data[discriminator.len()..],
+            depth: 0,
+        };
+        // Accounts can be allocated with more space than they use, so trailing bytes are fine.
+        decoder.type_def(layout)
+    }
+}
+
+struct Decoder<'a> {
+    idl: &'a AnchorIdl,
+    data: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        ensure!(self.data.len() >= len, "Account data is too short");
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("Took N bytes"))
+    }
+
+    /// Length prefix of strings, bytes and vectors.
+    fn read_len(&mut self) -> Result<usize> {
+        let len = usize::try_from(u32::from_le_bytes(self.take_array()?))?;
+        // Every element takes at least a byte, so longer lengths are malformed.
+        ensure!(
+            len <= self.data.len(),
+            "Length {len} exceeds the account data"
+        );
+        Ok(len)
+    }
+
+    fn type_def(&mut self, ty: &IdlTypeDefTy) -> Result<Value> {
+        self.depth += 1;
+        ensure!(self.depth <= MAX_DEPTH, "Types are nested too deep");
+        let value = match ty {
+            IdlTypeDefTy::Struct { fields } => Value::Struct(self.fields(fields.as_ref())?),
+            IdlTypeDefTy::Enum { variants } => {
+                let index = self.take_array::<1>()?[0];
+                let variant = variants
+                    .get(usize::from(index))
+                    .with_context(|| format!("Invalid enum variant {index}"))?;
+                Value::Enum {
+                    variant: variant.name.clone(),
+                    fields: self.fields(variant.fields.as_ref())?,
+                }
+            }
+            IdlTypeDefTy::Type { alias } => self.value(alias)?,
+        };
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    fn fields(&mut self, fields: Option<&IdlFields>) -> Result<Vec<(String, Value)>> {
+        match fields {
+            None => Ok(vec![]),
+            Some(IdlFields::Named(fields)) => fields
+                .iter()
+                .map(|field| Ok((field.name.clone(), self.value(&field.ty)?)))
+                .collect(),
+            Some(IdlFields::Tuple(types)) => types
+                .iter()
+                .enumerate()
+                .map(|(index, ty)| Ok((index.to_string(), self.value(ty)?)))
+                .collect(),
+        }
+    }
+
+    fn value(&mut self, ty: &IdlType) -> Result<Value> {
+        let value = match ty {
+            IdlType::Primitive(name) => self.primitive(name)?,
+            IdlType::Option { option } => match self.take_array::<1>()?[0] {
+                0 => Value::Option(None),
+                1 => Value::Option(Some(Box::new(self.value(option)?))),
+                tag => bail!("Invalid option tag {tag}"),
+            },
+            IdlType::Vec { vec } => {
+                let len = self.read_len()?;
+                Value::List((0..len).map(|_| self.value(vec)).collect::<Result<_>>()?)
+            }
+            IdlType::Array {
+                array: (ty, IdlArrayLen::Value(len)),
+            } => Value::List((0..*len).map(|_| self.value(ty)).collect::<Result<_>>()?),
+            IdlType::Defined { defined } => {
+                let type_def = self.idl.type_def(defined.name())?;
+                self.type_def(&type_def.ty)?
+            }
+            IdlType::Array { .. } | IdlType::Unsupported(_) => {
+                bail!("Unsupported IDL type {ty:?}")
+            }
+        };
+        Ok(value)
+    }
+
+    fn primitive(&mut self, name: &str) -> Result<Value> {
+        let value = match name {
+            "bool" => match self.take_array::<1>()?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                byte => bail!("Invalid bool {byte}"),
+            },
+            "u8" => Value::Uint(u8::from_le_bytes(self.take_array()?).into()),
+            "u16" => Value::Uint(u16::from_le_bytes(self.take_array()?).into()),
+            "u32" => Value::Uint(u32::from_le_bytes(self.take_array()?).into()),
+            "u64" => Value::Uint(u64::from_le_bytes(self.take_array()?).into()),
+            "u128" => Value::Uint(u128::from_le_bytes(self.take_array()?)),
+            "i8" => Value::Int(i8::from_le_bytes(self.take_array()?).into()),
+            "i16" => Value::Int(i16::from_le_bytes(self.take_array()?).into()),
+            "i32" => Value::Int(i32::from_le_bytes(self.take_array()?).into()),
+            "i64" => Value::Int(i64::from_le_bytes(self.take_array()?).into()),
+            "i128" => Value::Int(i128::from_le_bytes(self.take_array()?)),
+            "f32" => Value::Float(f32::from_le_bytes(self.take_array()?).into()),
+            "f64" => Value::Float(f64::from_le_bytes(self.take_array()?)),
+            "string" => {
+                let len = self.read_len()?;
+                let bytes = self.take(len)?.to_vec();
+                Value::String(String::from_utf8(bytes).context("Invalid UTF-8 string")?)
+            }
+            "bytes" => {
+                let len = self.read_len()?;
+                Value::Bytes(self.take(len)?.to_vec())
+            }
+            "pubkey" | "publicKey" => Value::Pubkey(Pubkey::new_from_array(self.take_array()?)),
+            name => bail!("Unsupported IDL type `{name}`"),
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnchorIdl, Value};
+    use hot_validation_primitives::bridge::expectation::ReturnValue;
+    use hot_validation_primitives::bridge::solana::anchor;
+    use serde_json::json;
+
+    /// `User` of the bridge program with a nested config after the nonces.
+    fn user_data(discriminator: [u8; 8]) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend([1, 254]); // version, bump
+        data.extend(7u128.to_le_bytes()); // last_deposit_nonce
+        data.extend(42u128.to_le_bytes()); // last_withdraw_nonce
+        data.extend([1, 3, 0, 0, 0]); // label: Some(..) of 3 bytes
+        data.extend(b"hot");
+        data.extend([2, 0, 0, 0]); // limits: 2 elements
+        data.extend((-5i64).to_le_bytes());
+        data.extend(10i64.to_le_bytes());
+        data.push(1); // paused: true
+        data.extend([0; 16]); // unused space of the account
+        data
+    }
+
+    fn user_idl(config_fields: serde_json::Value) -> AnchorIdl {
+        serde_json::from_value(json!({
+            "address": "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC",
+            "metadata": { "name": "bridge", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [],
+            "accounts": [{ "name": "User", "discriminator": anchor::account_discriminator("User") }],
+            "types": [
+                {
+                    "name": "User",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "version", "type": "u8" },
+                            { "name": "bump", "type": "u8" },
+                            { "name": "last_deposit_nonce", "type": "u128" },
+                            { "name": "last_withdraw_nonce", "type": "u128" },
+                            { "name": "config", "type": { "defined": { "name": "UserConfig" } } }
+                        ]
+                    }
+                },
+                {
+                    "name": "UserConfig",
+                    "type": { "kind": "struct", "fields": config_fields }
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn decode_current_format() -> anyhow::Result<()> {
+        let data = user_data(anchor::account_discriminator("User"));
+        let idl = user_idl(json!([
+            { "name": "label", "type": { "option": "string" } },
+            { "name": "limits", "type": { "vec": "i64" } },
+            { "name": "paused", "type": "bool" }
+        ]));
+        let user = idl.decode_account("User", &data)?;
+        assert_eq!(
+            user.field("last_withdraw_nonce")?.to_return_value()?,
+            ReturnValue::from(42u128)
+        );
+        assert_eq!(
+            user.field("config.label")?,
+            &Value::Option(Some(Box::new(Value::String("hot".to_string()))))
+        );
+        assert_eq!(
+            user.field("config.limits.0")?.to_return_value()?,
+            ReturnValue::from(-5i128)
+        );
+        assert_eq!(
+            user.field("config.paused")?.to_return_value()?,
+            ReturnValue::Bool(true)
+        );
+        assert!(user.field("config.label")?.to_return_value().is_err());
+        assert!(user.field("config.limits.2").is_err());
+        assert!(user.field("nonce").is_err());
+
+        assert!(idl.decode_account("Deposit", &data).is_err());
+        assert!(idl.decode_account("User", &data[..40]).is_err());
+        let mut other = data.clone();
+        other[0] ^= 1;
+        assert!(idl.decode_account("User", &other).is_err());
+
+        // The IDL loads with generic types, but accounts that use them can't be decoded.
+        let idl = user_idl(json!([
+            { "name": "label", "type": { "option": "string" } },
+            { "name": "values", "type": { "array": ["u8", { "generic": "N" }] } }
+        ]));
+        assert!(idl.decode_account("User", &data).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn decode_legacy_format() -> anyhow::Result<()> {
+        let idl: AnchorIdl = serde_json::from_value(json!({
+            "version": "0.1.0",
+            "name": "bridge",
+            "instructions": [],
+            "accounts": [{
+                "name": "User",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "version", "type": "u8" },
+                        { "name": "bump", "type": "u8" },
+                        { "name": "lastDepositNonce", "type": "u128" },
+                        { "name": "lastWithdrawNonce", "type": "u128" },
+                        { "name": "status", "type": { "defined": "Status" } }
+                    ]
+                }
+            }],
+            "types": [{
+                "name": "Status",
+                "type": {
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "Active" },
+                        { "name": "Frozen", "fields": [{ "name": "until", "type": "i64" }] }
+                    ]
+                }
+            }]
+        }))?;
+        let mut data = anchor::account_discriminator("User").to_vec();
+        data.extend([1, 254]);
+        data.extend(7u128.to_le_bytes());
+        data.extend(42u128.to_le_bytes());
+        data.push(1);
+        data.extend(1_700_000_000i64.to_le_bytes());
+
+        let user = idl.decode_account("User", &data)?;
+        assert_eq!(
+            user.field("lastWithdrawNonce")?.to_return_value()?,
+            ReturnValue::from(42u128)
+        );
+        assert_eq!(
+            user.field("status")?,
+            &Value::Enum {
+                variant: "Frozen".to_string(),
+                fields: vec![("until".to_string(), Value::Int(1_700_000_000))],
+            }
+        );
+        Ok(())
+    }
+}
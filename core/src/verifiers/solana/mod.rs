@@ -0,0 +1,909 @@
+mod idl;
+
+pub use idl::AnchorIdl;
+
+use crate::head_tracker::{HeadSource, HeadTracker, Heads};
+use crate::http_client::TIMEOUT;
+use crate::revert::RevertReason;
+use crate::threshold_verifier::{Identifiable, ThresholdVerifier, VerificationError};
+use crate::verifiers::Verifier;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use async_trait::async_trait;
+use borsh::BorshDeserialize;
+use futures_util::future::join_all;
+use hot_validation_primitives::bridge::expectation::{Expectation, ReturnValue};
+use hot_validation_primitives::bridge::solana::{
+    anchor, AnchorAccountField, AnchorInstruction, DepositWithProof, MessageVersion,
+    SolanaInputData, UserAccount,
+};
+use hot_validation_primitives::bridge::transaction::{TransactionId, TransactionInputData};
+use hot_validation_primitives::bridge::{CompletedWithdrawal, HotVerifyAuthCall, InputData};
+use hot_validation_primitives::{
+    ChainFamily, ChainId, ChainValidationConfig, ExtendedChainId, Finality, FinalityLevel,
+    NetworkProfile,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcSimulateTransactionConfig};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{v0, Address, AddressLookupTableAccount, Message, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::hash::Hash as StdHash;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// SPL Token and Token-2022 programs, both keep the base token account layout.
+const TOKEN_PROGRAM_IDS: [&str; 2] = [
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+];
+const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: &str = "AddressLookupTab1e1111111111111111111111111";
+/// Lookup table layout: metadata followed by 32-byte addresses.
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+/// Token account layout: `mint` (32 bytes), `owner` (32 bytes), `amount` (u64 LE).
+const TOKEN_ACCOUNT_MINT: std::ops::Range<usize> = 0..32;
+const TOKEN_ACCOUNT_OWNER: std::ops::Range<usize> = 32..64;
+const TOKEN_ACCOUNT_AMOUNT: std::ops::Range<usize> = 64..72;
+/// Answers read more than this many slots after the agreed slot don't count,
+/// so a quorum is only formed by endpoints that read the state at about the same time.
+const MAX_SLOT_LAG: u64 = 32;
+
+pub struct SolanaVerifier {
+    client: RpcClient,
+    server: String,
+    commitment: CommitmentConfig,
+    level: FinalityLevel,
+    head_tracker: Option<Arc<HeadTracker>>,
+    /// IDLs of the programs whose accounts can be decoded, by program id.
+    idls: Arc<HashMap<Pubkey, AnchorIdl>>,
+}
+
+impl Identifiable for SolanaVerifier {
+    fn id(&self) -> String {
+        self.server.clone()
+    }
+
+    fn is_lagging(&self) -> bool {
+        self.head_tracker
+            .as_ref()
+            .is_some_and(|tracker| tracker.is_lagging(&self.server))
+    }
+}
+
+#[async_trait]
+impl HeadSource for SolanaVerifier {
+    /// Only the slot of the configured commitment is read besides the latest one.
+    async fn fetch_heads(&self) -> Result<Heads> {
+        let latest = self
+            .client
+            .get_slot_with_commitment(CommitmentConfig::processed())
+            .await?;
+        let heads = Heads::latest(latest);
+        if self.level == FinalityLevel::Optimistic {
+            return Ok(heads);
+        }
+        let head = self.current_slot().await?;
+        Ok(heads.with(self.level, head))
+    }
+}
+
+impl SolanaVerifier {
+    /// `min_confirmations` of the finality is not supported: Solana RPC can only
+    /// execute against the bank of the selected commitment, configs with it are rejected
+    /// by [`Finality::ensure_supported_by`].
+    pub fn new(server: String, finality: Finality) -> Self {
+        let commitment = match finality.level {
+            FinalityLevel::Optimistic => CommitmentConfig::processed(),
+            FinalityLevel::Confirmed => CommitmentConfig::confirmed(),
+            FinalityLevel::Final => CommitmentConfig::finalized(),
+        };
+        Self {
+            client: RpcClient::new_with_timeout_and_commitment(server.clone(), TIMEOUT, commitment),
+            server,
+            commitment,
+            level: finality.level,
+            head_tracker: None,
+            idls: Arc::default(),
+        }
+    }
+
+    /// Excludes the endpoint from threshold calls while its slot lags behind the others,
+    /// and pins reads to the median slot of the endpoints, see [`Self::min_context_slot`].
+    #[must_use]
+    pub fn with_head_tracker(mut self, head_tracker: Arc<HeadTracker>) -> Self {
+        self.head_tracker = Some(head_tracker);
+        self
+    }
+
+    #[must_use]
+    pub fn with_idls(mut self, idls: Arc<HashMap<Pubkey, AnchorIdl>>) -> Self {
+        self.idls = idls;
+        self
+    }
+
+    /// The median slot of the configured commitment across the endpoints, so that all of them
+    /// answer at a comparable slot: nodes that haven't reached it fail instead of voting with
+    /// stale state. Without a fresh median, the slot the endpoint is at.
+    ///
+    /// Solana RPC can't execute against a past bank, `min_context_slot` only rejects nodes
+    /// behind it, so pinning `at` is refused instead of silently reading the current state.
+    async fn min_context_slot(&self, at: Option<u64>) -> Result<u64> {
+        if let Some(slot) = at {
+            bail!("Solana can't read state at a past slot ({slot}), only the current one");
+        }
+        match self.median_slot() {
+            Some(slot) => Ok(slot),
+            None => self.current_slot().await,
+        }
+    }
+
+    fn median_slot(&self) -> Option<u64> {
+        self.head_tracker
+            .as_ref()
+            .and_then(|tracker| tracker.median_at(self.level))
+    }
+
+    async fn current_slot(&self) -> Result<u64> {
+        self.client
+            .get_slot_with_commitment(self.commitment)
+            .await
+            .with_context(|| format!("failed to fetch the slot of {}", self.server))
+    }
+
+    /// The RPC has to honour `min_context_slot`, otherwise its answer is stale.
+    fn ensure_context_slot(context_slot: u64, min_context_slot: u64) -> Result<()> {
+        ensure!(
+            context_slot >= min_context_slot,
+            "RPC answered at slot {context_slot}, expected at least {min_context_slot}"
+        );
+        Ok(())
+    }
+
+    /// A threshold call only counts answers read within `slot..=slot + MAX_SLOT_LAG`.
+    fn ensure_slot_window(context_slot: u64, slot: u64) -> Result<()> {
+        Self::ensure_context_slot(context_slot, slot)?;
+        ensure!(
+            context_slot - slot <= MAX_SLOT_LAG,
+            "RPC answered at slot {context_slot}, more than {MAX_SLOT_LAG} slots after {slot}"
+        );
+        Ok(())
+    }
+
+    fn get_simulation_config(&self, min_context_slot: u64) -> RpcSimulateTransactionConfig {
+        RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.commitment),
+            min_context_slot: Some(min_context_slot),
+            ..RpcSimulateTransactionConfig::default()
+        }
+    }
+
+    /// Simulates `tx`, which succeeds if none of its instructions fails.
+    /// Returns the slot the simulation ran at.
+    async fn simulate(
+        &self,
+        tx: &VersionedTransaction,
+        method_name: &str,
+        min_context_slot: u64,
+    ) -> Result<u64> {
+        let simulation_config = self.get_simulation_config(min_context_slot);
+        let resp = self
+            .client
+            .simulate_transaction_with_config(tx, simulation_config)
+            .await?;
+        Self::ensure_context_slot(resp.context.slot, min_context_slot)?;
+        if let Some(err) = resp.value.err {
+            let err = format!("{err:?}");
+            let logs = resp.value.logs.unwrap_or_default();
+            let reason = RevertReason::from_anchor_logs(&logs)
+                .or_else(|| RevertReason::from_solana_error(&err))
+                .unwrap_or_else(|| RevertReason::Raw(err));
+            return Err(reason).with_context(|| format!("{method_name} reverted; logs={logs:?}"));
+        }
+        Ok(resp.context.slot)
+    }
+
+    async fn handle_deposit(
+        &self,
+        program_id: &Address,
+        method_name: &str,
+        deposit_data: DepositWithProof,
+        min_context_slot: u64,
+    ) -> Result<u64> {
+        let message = deposit_data.get_message(program_id, method_name)?;
+        let tx = Transaction::new_unsigned(message);
+        self.simulate(&tx.into(), method_name, min_context_slot)
+            .await
+    }
+
+    /// Simulates an arbitrary Anchor instruction, compiled into a v0 message if requested.
+    async fn handle_instruction(
+        &self,
+        program_id: &Address,
+        method_name: &str,
+        instruction: AnchorInstruction,
+        min_context_slot: u64,
+    ) -> Result<u64> {
+        let lookup_table_keys = instruction.get_address_lookup_tables()?;
+        let ix = instruction.get_instruction(program_id, method_name)?;
+        let payer = instruction.get_payer(program_id)?;
+        let message = match instruction.message_version {
+            MessageVersion::Legacy => VersionedMessage::Legacy(Message::new(&[ix], Some(&payer))),
+            MessageVersion::V0 => {
+                let mut lookup_tables = Vec::with_capacity(lookup_table_keys.len());
+                for key in lookup_table_keys {
+                    lookup_tables.push(self.address_lookup_table(key, min_context_slot).await?);
+                }
+                // The blockhash is replaced by the simulation.
+                let message =
+                    v0::Message::try_compile(&payer, &[ix], &lookup_tables, Hash::default())
+                        .map_err(|e| anyhow!("Couldn't compile v0 message: {e:?}"))?;
+                VersionedMessage::V0(message)
+            }
+        };
+        // Signatures aren't verified by the simulation, but have to be present.
+        let signatures =
+            vec![Signature::default(); usize::from(message.header().num_required_signatures)];
+        let tx = VersionedTransaction {
+            signatures,
+            message,
+        };
+        self.simulate(&tx, method_name, min_context_slot).await
+    }
+
+    async fn address_lookup_table(
+        &self,
+        key: Pubkey,
+        min_context_slot: u64,
+    ) -> Result<AddressLookupTableAccount> {
+        let config = RpcAccountInfoConfig {
+            commitment: Some(self.commitment),
+            min_context_slot: Some(min_context_slot),
+            ..RpcAccountInfoConfig::default()
+        };
+        let response = self
+            .client
+            .get_account_with_config(&key, config)
+            .await
+            .with_context(|| format!("failed to fetch lookup table {key}"))?;
+        Self::ensure_context_slot(response.context.slot, min_context_slot)?;
+        let account = response
+            .value
+            .with_context(|| format!("lookup table {key} not found"))?;
+        ensure!(
+            account.owner.to_string() == ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+            "account {key} is not a lookup table, owner: {}",
+            account.owner
+        );
+        let addresses = account
+            .data
+            .get(LOOKUP_TABLE_META_SIZE..)
+            .with_context(|| format!("lookup table {key} is too short"))?;
+        ensure!(
+            addresses.len() % 32 == 0,
+            "lookup table {key} has a partial address"
+        );
+        let addresses = addresses
+            .chunks_exact(32)
+            .map(|address| Pubkey::try_from(address).expect("Chunk is 32 bytes long"))
+            .collect();
+        Ok(AddressLookupTableAccount { key, addresses })
+    }
+
+    /// `last_withdraw_nonce` of the receiver's `User` account, `None` if the account
+    /// doesn't exist yet (nothing was withdrawn to the receiver), and the slot it was read at.
+    async fn last_withdraw_nonce(
+        &self,
+        program_id: &Address,
+        completed_withdrawal_data: &CompletedWithdrawal,
+        min_context_slot: u64,
+    ) -> Result<(Option<u128>, u64)> {
+        let user_pk = completed_withdrawal_data.get_user_address(program_id)?;
+
+        let config = RpcAccountInfoConfig {
+            commitment: Some(self.commitment),
+            min_context_slot: Some(min_context_slot),
+            ..RpcAccountInfoConfig::default()
+        };
+        let response = self
+            .client
+            .get_account_with_config(&user_pk, config)
+            .await
+            .with_context(|| format!("failed to fetch account data for {user_pk}"))?;
+        let slot = response.context.slot;
+        Self::ensure_context_slot(slot, min_context_slot)?;
+        let Some(account) = response.value else {
+            return Ok((None, slot));
+        };
+        let data = account.data;
+
+        let disc = anchor::account_discriminator("User");
+        if data.len() < 8 || data[..8] != disc {
+            return Err(anyhow!(
+                "account {user_pk} is not an Anchor `User` (bad discriminator)"
+            ));
+        }
+
+        // Deserialize the struct after the 8-byte discriminator
+        let user = UserAccount::try_from_slice(&data[8..])
+            .context("failed to Borsh-deserialize `User`")?;
+        Ok((Some(user.last_withdraw_nonce), slot))
+    }
+
+    /// The field of an Anchor account of the program, decoded with the program's IDL,
+    /// and the slot it was read at.
+    async fn account_field(
+        &self,
+        program_id: &Address,
+        query: &AnchorAccountField,
+        min_context_slot: u64,
+    ) -> Result<(ReturnValue, u64)> {
+        let idl = self
+            .idls
+            .get(program_id)
+            .with_context(|| format!("No Anchor IDL configured for {program_id}"))?;
+        let address = query.address.resolve(program_id)?;
+
+        let config = RpcAccountInfoConfig {
+            commitment: Some(self.commitment),
+            min_context_slot: Some(min_context_slot),
+            ..RpcAccountInfoConfig::default()
+        };
+        let response = self
+            .client
+            .get_account_with_config(&address, config)
+            .await
+            .with_context(|| format!("failed to fetch account data for {address}"))?;
+        let slot = response.context.slot;
+        Self::ensure_context_slot(slot, min_context_slot)?;
+        let account = response
+            .value
+            .with_context(|| format!("account {address} not found"))?;
+        ensure!(
+            account.owner == *program_id,
+            "account {address} is not owned by {program_id}, owner: {}",
+            account.owner
+        );
+        let value = idl
+            .decode_account(&query.account_type, &account.data)
+            .with_context(|| format!("failed to decode `{}` {address}", query.account_type))?;
+        let value = value
+            .field(&query.field)?
+            .to_return_value()
+            .with_context(|| format!("invalid field `{}`", query.field))?;
+        Ok((value, slot))
+    }
+
+    /// Amount of `mint` held by `owner` in its associated token account, of either token program,
+    /// and the slot it was read at. An owner without the account holds none of the token.
+    async fn token_account_amount(
+        &self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        min_context_slot: u64,
+    ) -> Result<(ReturnValue, u64)> {
+        let associated_program = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID)?;
+        let token_programs = TOKEN_PROGRAM_IDS
+            .iter()
+            .map(|id| Pubkey::from_str(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let token_accounts = token_programs
+            .iter()
+            .map(|program| {
+                Pubkey::find_program_address(
+                    &[owner.as_ref(), program.as_ref(), mint.as_ref()],
+                    &associated_program,
+                )
+                .0
+            })
+            .collect::<Vec<_>>();
+        let config = RpcAccountInfoConfig {
+            commitment: Some(self.commitment),
+            min_context_slot: Some(min_context_slot),
+            ..RpcAccountInfoConfig::default()
+        };
+        let response = self
+            .client
+            .get_multiple_accounts_with_config(&token_accounts, config)
+            .await
+            .with_context(|| format!("failed to fetch token accounts of {owner} for {mint}"))?;
+        let slot = response.context.slot;
+        Self::ensure_context_slot(slot, min_context_slot)?;
+        let account = token_programs
+            .iter()
+            .zip(response.value)
+            .find_map(|(program, account)| account.filter(|account| &account.owner == program));
+        let amount = match account {
+            Some(account) => Self::token_account_balance(&account.data, mint, owner)?,
+            None => 0,
+        };
+        Ok((u128::from(amount).into(), slot))
+    }
+
+    /// Amount of a token account, which has to hold `mint` for `owner`.
+    fn token_account_balance(data: &[u8], mint: &Pubkey, owner: &Pubkey) -> Result<u64> {
+        ensure!(
+            data.len() >= TOKEN_ACCOUNT_AMOUNT.end,
+            "token account of {owner} is too short"
+        );
+        ensure!(
+            data[TOKEN_ACCOUNT_MINT] == mint.to_bytes(),
+            "token account of {owner} doesn't belong to mint {mint}"
+        );
+        ensure!(
+            data[TOKEN_ACCOUNT_OWNER] == owner.to_bytes(),
+            "token account for mint {mint} doesn't belong to {owner}"
+        );
+        let amount = data[TOKEN_ACCOUNT_AMOUNT]
+            .try_into()
+            .map(u64::from_le_bytes)
+            .expect("Range is 8 bytes long");
+        Ok(amount)
+    }
+
+    /// Reads the state `input` asks for at `min_context_slot` or later, along with the slot
+    /// it was read at. Simulations have no return value, so a successful one returns `true`.
+    /// A completed withdrawal check returns `last_withdraw_nonce` of the receiver,
+    /// an account field query returns the field.
+    async fn read(
+        &self,
+        program_id: &Address,
+        method_name: &str,
+        input: SolanaInputData,
+        min_context_slot: u64,
+    ) -> Result<(ReturnValue, u64)> {
+        match input {
+            SolanaInputData::Deposit(deposit_with_proof) => {
+                let slot = self
+                    .handle_deposit(
+                        program_id,
+                        method_name,
+                        deposit_with_proof,
+                        min_context_slot,
+                    )
+                    .await?;
+                Ok((ReturnValue::Bool(true), slot))
+            }
+            SolanaInputData::Instruction(instruction) => {
+                let slot = self
+                    .handle_instruction(program_id, method_name, instruction, min_context_slot)
+                    .await?;
+                Ok((ReturnValue::Bool(true), slot))
+            }
+            SolanaInputData::AccountField(query) => {
+                self.account_field(program_id, &query, min_context_slot)
+                    .await
+            }
+            SolanaInputData::CheckCompletedWithdrawal(completed_withdrawal_data) => {
+                let (last_withdraw_nonce, slot) = self
+                    .last_withdraw_nonce(program_id, &completed_withdrawal_data, min_context_slot)
+                    .await?;
+                let last_withdraw_nonce = last_withdraw_nonce.with_context(|| {
+                    format!(
+                        "user account of {:?} not found",
+                        completed_withdrawal_data.receiver_address
+                    )
+                })?;
+                Ok((last_withdraw_nonce.into(), slot))
+            }
+        }
+    }
+
+    /// The rule the value read for `input` has to satisfy without an expectation:
+    /// the withdrawal nonce has to be used, the account field has to be `true`.
+    fn ensure_implicit_rule(input: &SolanaInputData, value: ReturnValue) -> Result<()> {
+        match input {
+            SolanaInputData::CheckCompletedWithdrawal(completed_withdrawal_data) => {
+                let nonce = completed_withdrawal_data.nonce;
+                ensure!(
+                    Expectation::GreaterOrEqual(nonce.into()).evaluate(&value)?,
+                    "Nonce is not used: got {nonce}, last used: {value}"
+                );
+            }
+            SolanaInputData::AccountField(query) => {
+                ensure!(
+                    value == ReturnValue::Bool(true),
+                    "`{}.{}` is {value}, expected true",
+                    query.account_type,
+                    query.field
+                );
+            }
+            SolanaInputData::Deposit(_) | SolanaInputData::Instruction(_) => {}
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Verifier for SolanaVerifier {
+    fn chain_id(&self) -> ExtendedChainId {
+        ExtendedChainId::Solana
+    }
+
+    /// Solana RPC can't execute against a past bank, so `at` is rejected,
+    /// see [`SolanaVerifier::min_context_slot`].
+    async fn verify_at(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> Result<bool> {
+        let input: SolanaInputData = input_data.try_into()?;
+        let program_id = Pubkey::from_str(&auth_contract_id)?;
+        let min_context_slot = self.min_context_slot(at).await?;
+        let (value, _) = self
+            .read(&program_id, &method_name, input.clone(), min_context_slot)
+            .await?;
+        Self::ensure_implicit_rule(&input, value)?;
+        Ok(true)
+    }
+
+    /// See [`SolanaVerifier::read`].
+    async fn call(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        let input: SolanaInputData = input_data.try_into()?;
+        let program_id = Pubkey::from_str(&auth_contract_id)?;
+        let min_context_slot = self.min_context_slot(at).await?;
+        let (value, _) = self
+            .read(&program_id, &method_name, input, min_context_slot)
+            .await?;
+        Ok(value)
+    }
+
+    /// Amount of the mint `token_id` in the associated token account of `holder`.
+    async fn token_balance(
+        &self,
+        token_id: String,
+        holder: String,
+        at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        let mint = Pubkey::from_str(&token_id)?;
+        let owner = Pubkey::from_str(&holder)?;
+        let min_context_slot = self.min_context_slot(at).await?;
+        let (amount, _) = self
+            .token_account_amount(&mint, &owner, min_context_slot)
+            .await?;
+        Ok(amount)
+    }
+
+    /// The signature has to be finalized without an error, regardless of the configured commitment.
+    async fn is_transaction_successful(&self, input: TransactionInputData) -> Result<bool> {
+        let TransactionId::Solana { signature } = input.transaction else {
+            bail!("Expected a Solana transaction, got {:?}", input.transaction);
+        };
+        let signature = Signature::from_str(&signature)?;
+        let status = self
+            .client
+            .get_signature_status_with_commitment_and_history(
+                &signature,
+                CommitmentConfig::finalized(),
+                true,
+            )
+            .await
+            .with_context(|| format!("failed to fetch status of {signature}"))?;
+        Ok(matches!(status, Some(Ok(()))))
+    }
+
+    async fn is_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
+    ) -> Result<bool> {
+        let program_id = Pubkey::from_str(&contract_id)?;
+        let min_context_slot = self.min_context_slot(None).await?;
+        let (last_withdraw_nonce, _) = self
+            .last_withdraw_nonce(&program_id, &withdrawal, min_context_slot)
+            .await?;
+        Ok(last_withdraw_nonce.is_some_and(|last| withdrawal.nonce <= last))
+    }
+}
+
+impl ThresholdVerifier<SolanaVerifier> {
+    pub fn new_solana(config: &ChainValidationConfig, network: &NetworkProfile) -> Result<Self> {
+        let finality = config.finality_or_default(ChainFamily::Solana);
+        let head_tracker = HeadTracker::new(ChainId::Solana, config.head_poll_interval());
+        let idls = Arc::new(idl::load_idls(&network.solana_idls)?);
+        let verifiers = config
+            .servers
+            .iter()
+            .map(|server| {
+                let verifier = SolanaVerifier::new(server.clone(), finality)
+                    .with_head_tracker(head_tracker.clone())
+                    .with_idls(idls.clone());
+                Arc::new(verifier)
+            })
+            .collect::<Vec<_>>();
+        head_tracker.spawn(&verifiers);
+        Ok(Self {
+            threshold: config.threshold,
+            verifiers,
+        })
+    }
+
+    /// The slot all endpoints have to read at or after: the median of the tracked heads,
+    /// or, while the tracker has no fresh one, the highest slot `threshold` endpoints reached.
+    async fn agreed_slot(&self) -> Result<u64> {
+        if let Some(slot) = self
+            .verifiers
+            .first()
+            .and_then(|verifier| verifier.median_slot())
+        {
+            return Ok(slot);
+        }
+        let mut slots: Vec<u64> = join_all(
+            self.verifiers
+                .iter()
+                .map(|verifier| verifier.current_slot()),
+        )
+        .await
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect();
+        slots.sort_unstable_by(|a, b| b.cmp(a));
+        slots
+            .get(self.threshold.saturating_sub(1))
+            .copied()
+            .with_context(|| {
+                format!(
+                    "Less than {} Solana endpoints reported their slot",
+                    self.threshold
+                )
+            })
+    }
+
+    /// Threshold call on the value `functor` reads at the agreed slot or later,
+    /// answers read too late don't count, see [`MAX_SLOT_LAG`].
+    async fn slot_threshold_call<F, Fut, R>(&self, functor: F) -> Result<R>
+    where
+        R: Eq + StdHash + Clone + Debug,
+        F: Fn(Arc<SolanaVerifier>, u64) -> Fut + Clone,
+        Fut: Future<Output = Result<(R, u64)>> + Send + 'static,
+    {
+        let slot = self.agreed_slot().await?;
+        self.threshold_call(move |verifier| {
+            let read = functor(verifier, slot);
+            async move {
+                let (value, context_slot) = read.await?;
+                SolanaVerifier::ensure_slot_window(context_slot, slot)?;
+                Ok(value)
+            }
+        })
+        .await
+    }
+
+    /// Same as [`Self::verify_auth_call`], but only the answers read at about the agreed slot
+    /// count, so that the quorum isn't formed by reads of different states.
+    pub async fn verify_solana_auth_call(
+        &self,
+        auth_call: HotVerifyAuthCall,
+        at: Option<u64>,
+    ) -> Result<bool> {
+        if let Some(slot) = at {
+            bail!("Solana can't read state at a past slot ({slot}), only the current one");
+        }
+        let HotVerifyAuthCall {
+            contract_id,
+            method,
+            input,
+            expectation,
+            ..
+        } = auth_call;
+        let program_id = Pubkey::from_str(&contract_id)?;
+        match input {
+            InputData::TokenBalance(input) => {
+                let owner = Pubkey::from_str(&input.holder)?;
+                let balance = self
+                    .slot_threshold_call(move |verifier, slot| async move {
+                        verifier
+                            .token_account_amount(&program_id, &owner, slot)
+                            .await
+                    })
+                    .await?;
+                input.is_satisfied_by(&balance)
+            }
+            InputData::Transaction(input) => self.is_transaction_successful(input).await,
+            input_data => {
+                let input: SolanaInputData = input_data.clone().try_into()?;
+                let read_input = input.clone();
+                let read_method = method.clone();
+                let read = self
+                    .slot_threshold_call(move |verifier, slot| {
+                        let method = read_method.clone();
+                        let input = read_input.clone();
+                        async move { verifier.read(&program_id, &method, input, slot).await }
+                    })
+                    .await;
+                let Some(expectation) = expectation else {
+                    return read
+                        .and_then(|value| SolanaVerifier::ensure_implicit_rule(&input, value))
+                        .map(|()| true)
+                        .map_err(|kind| {
+                            VerificationError::new(
+                                ExtendedChainId::Solana,
+                                contract_id,
+                                method,
+                                input_data,
+                                kind,
+                            )
+                            .into()
+                        });
+                };
+                expectation.evaluate(&read?)
+            }
+        }
+    }
+
+    /// Same as [`Self::is_nonce_used`], counting only the nonces read at about the agreed slot.
+    pub async fn is_solana_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
+    ) -> Result<bool> {
+        let program_id = Pubkey::from_str(&contract_id)?;
+        let nonce = withdrawal.nonce;
+        let last_withdraw_nonce = self
+            .slot_threshold_call(move |verifier, slot| {
+                let withdrawal = withdrawal.clone();
+                async move {
+                    verifier
+                        .last_withdraw_nonce(&program_id, &withdrawal, slot)
+                        .await
+                }
+            })
+            .await?;
+        Ok(last_withdraw_nonce.is_some_and(|last| nonce <= last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SolanaVerifier;
+
+    use hot_validation_primitives::bridge::solana::{DepositWithProof, SolanaInputData};
+
+    use crate::verifiers::Verifier;
+    use hot_validation_primitives::bridge::{CompletedWithdrawal, DepositData};
+    use hot_validation_primitives::{ChainFamily, Finality};
+    use serde_json::json;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn get_deposit_data() -> DepositData {
+        let json = json!({
+                "sender": "5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho",
+                "receiver": "BJu6S7gT4gnx7AXPnghM7aYiS5dPfSUixqAZJq1Uqf4V",
+                "token_id": "BYPsjxa3YuZESQz1dKuBw1QSFCSpecsm8nCQhY5xbU1Z",
+                "amount": "10000000",
+                "nonce": "1757984522000007228"
+            }
+        );
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn get_deposit_with_proof() -> DepositWithProof {
+        let deposit_data = get_deposit_data();
+        let proof: [u8; 32] =
+            hex::decode("47b8b751a0d90d113e4e16678ebda646a01a02d376f49f666ddd17ee9f383c2f")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        DepositWithProof {
+            proof,
+            deposit_data,
+        }
+    }
+
+    fn get_completed_withdrawal_data(nonce: &str) -> CompletedWithdrawal {
+        let json = json!({
+            "nonce": nonce,
+            "receiver_address": "5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho",
+        });
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn deposit_verification() -> anyhow::Result<()> {
+        let verifier = SolanaVerifier::new(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            Finality::default_for(ChainFamily::Solana),
+        );
+        let auth_contract = "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC".to_string();
+        let method_name = "hot_verify_deposit".to_string();
+        let input = SolanaInputData::Deposit(get_deposit_with_proof());
+
+        verifier
+            .verify(auth_contract, method_name, input.into())
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn completed_withdrawal_verification_low() -> anyhow::Result<()> {
+        let verifier = SolanaVerifier::new(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            Finality::default_for(ChainFamily::Solana),
+        );
+        let auth_contract = "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC".to_string();
+        let method_name = String::new();
+        let input = SolanaInputData::CheckCompletedWithdrawal(get_completed_withdrawal_data(
+            "1749390032000000032243",
+        ));
+
+        verifier
+            .verify(auth_contract, method_name, input.into())
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn completed_withdrawal_verification_high() -> anyhow::Result<()> {
+        let verifier = SolanaVerifier::new(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            Finality::default_for(ChainFamily::Solana),
+        );
+        let auth_contract = "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC".to_string();
+        let method_name = String::new();
+        let input = SolanaInputData::CheckCompletedWithdrawal(get_completed_withdrawal_data(
+            "2749390032000000032243",
+        ));
+
+        let result = verifier
+            .verify(auth_contract, method_name, input.into())
+            .await;
+        result.expect_err("expected error");
+        Ok(())
+    }
+
+    #[test]
+    fn context_slot_must_reach_pinned_slot() {
+        assert!(SolanaVerifier::ensure_context_slot(100, 100).is_ok());
+        assert!(SolanaVerifier::ensure_context_slot(99, 100).is_err());
+    }
+
+    #[tokio::test]
+    async fn past_slots_cant_be_pinned() {
+        let verifier = SolanaVerifier::new(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            Finality::default_for(ChainFamily::Solana),
+        );
+        assert!(verifier.min_context_slot(Some(100)).await.is_err());
+    }
+
+    #[test]
+    fn answers_count_within_the_slot_window() {
+        // honest endpoints a few slots apart agree, whichever side of a boundary they're on
+        assert!(SolanaVerifier::ensure_slot_window(100, 100).is_ok());
+        assert!(SolanaVerifier::ensure_slot_window(131, 100).is_ok());
+        assert!(SolanaVerifier::ensure_slot_window(132, 100).is_ok());
+        assert!(SolanaVerifier::ensure_slot_window(133, 100).is_err());
+        assert!(SolanaVerifier::ensure_slot_window(99, 100).is_err());
+    }
+
+    #[test]
+    fn token_account_belongs_to_mint_and_owner() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut data = [mint.to_bytes(), owner.to_bytes()].concat();
+        data.extend_from_slice(&42u64.to_le_bytes());
+        assert_eq!(
+            SolanaVerifier::token_account_balance(&data, &mint, &owner).unwrap(),
+            42
+        );
+        let stranger = Pubkey::new_unique();
+        assert!(SolanaVerifier::token_account_balance(&data, &mint, &stranger).is_err());
+        assert!(SolanaVerifier::token_account_balance(&data, &stranger, &owner).is_err());
+        assert!(SolanaVerifier::token_account_balance(&data[..64], &mint, &owner).is_err());
+    }
+}
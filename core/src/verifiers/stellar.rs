@@ -1,37 +1,130 @@
-use crate::http_client::TIMEOUT;
+use crate::head_tracker::{HeadSource, HeadTracker, Heads};
+use crate::http_client::{post_json_receive_json, TIMEOUT};
+use crate::revert::RevertReason;
 use crate::threshold_verifier::{Identifiable, ThresholdVerifier};
 use crate::verifiers::Verifier;
-use crate::ChainValidationConfig;
-use anyhow::{Context, Result};
+use crate::{ChainValidationConfig, NetworkProfile, HOT_VERIFY_METHOD_NAME};
+use alloy_primitives::U256;
+use anyhow::{bail, ensure, Context, Result};
 use async_trait::async_trait;
-use hot_validation_primitives::bridge::stellar::StellarInputData;
-use hot_validation_primitives::bridge::InputData;
-use hot_validation_primitives::ExtendedChainId;
+use hot_validation_primitives::bridge::expectation::{Expectation, ReturnValue};
+use hot_validation_primitives::bridge::stellar::{
+    StellarInputArg, StellarInputData, StellarStorageDurability, StellarStorageQuery,
+};
+use hot_validation_primitives::bridge::transaction::{TransactionId, TransactionInputData};
+use hot_validation_primitives::bridge::{CompletedWithdrawal, HotVerifyAuthCall, InputData};
+use hot_validation_primitives::{ChainId, ExtendedChainId};
+use serde::Deserialize;
+use serde_json::json;
 use soroban_client::account::{Account, AccountBehavior};
 use soroban_client::contract::{ContractBehavior, Contracts};
 use soroban_client::keypair::{Keypair, KeypairBehavior};
-use soroban_client::network::{NetworkPassphrase, Networks};
 use soroban_client::transaction::ScVal;
 use soroban_client::transaction_builder::{TransactionBuilder, TransactionBuilderBehavior};
+use soroban_client::xdr::{
+    ContractDataDurability, Duration, LedgerEntryData, LedgerKey, LedgerKeyContractData, Limits,
+    ReadXdr, ScAddress, ScSymbol, TimePoint, WriteXdr,
+};
 use soroban_client::{xdr, Options, Server};
+use std::str::FromStr;
 use std::sync::Arc;
 
+const IS_EXECUTED_METHOD_NAME: &str = "is_executed";
+/// Bridge contract functions returning the verdict itself.
+pub(crate) const VERDICT_METHODS: [&str; 2] = [HOT_VERIFY_METHOD_NAME, IS_EXECUTED_METHOD_NAME];
+
+/// A live contract storage entry read with `getLedgerEntries`,
+/// threshold consensus is reached on it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StorageEntry {
+    pub value: ScVal,
+    /// Ledger that last changed the entry, the evidence of the read.
+    pub last_modified_ledger: u32,
+}
+
+/// Outcome of [`ThresholdVerifier::verify_stellar_auth_call`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StellarVerdict {
+    pub status: bool,
+    /// Ledger that last changed the storage entry the auth call read, if it read one.
+    pub last_modified_ledger: Option<u32>,
+}
+
+/// Soroban RPC simulates and reads storage only at the latest ledger, and Stellar has
+/// deterministic finality, so [`hot_validation_primitives::Finality`] has no effect here
+/// and pinned verification is not supported.
 #[derive(Clone)]
 pub struct StellarVerifier {
     client: Arc<Server>,
+    /// For the RPC methods that `soroban_client` doesn't wrap.
+    http_client: Arc<reqwest::Client>,
     server: String,
+    network_passphrase: String,
+    head_tracker: Option<Arc<HeadTracker>>,
 }
 
 impl Identifiable for StellarVerifier {
     fn id(&self) -> String {
         self.server.clone()
     }
+
+    fn is_lagging(&self) -> bool {
+        self.head_tracker
+            .as_ref()
+            .is_some_and(|tracker| tracker.is_lagging(&self.server))
+    }
+}
+
+#[async_trait]
+impl HeadSource for StellarVerifier {
+    /// Closed ledgers are final.
+    async fn fetch_heads(&self) -> Result<Heads> {
+        #[derive(Deserialize)]
+        struct Response {
+            result: LatestLedger,
+        }
+        #[derive(Deserialize)]
+        struct LatestLedger {
+            sequence: u64,
+        }
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "dontcare",
+            "method": "getLatestLedger",
+        });
+        let response: Response =
+            post_json_receive_json(&self.http_client, &self.server, &request, ChainId::Stellar)
+                .await?;
+        let ledger = response.result.sequence;
+        Ok(Heads {
+            latest: ledger,
+            safe: Some(ledger),
+            finalized: Some(ledger),
+        })
+    }
 }
 
 impl StellarVerifier {
-    pub fn new(server: String) -> Result<Self> {
+    pub fn new(
+        http_client: Arc<reqwest::Client>,
+        server: String,
+        network_passphrase: String,
+    ) -> Result<Self> {
         let client = Arc::new(Server::new(&server, Options::default())?);
-        Ok(Self { client, server })
+        Ok(Self {
+            client,
+            http_client,
+            server,
+            network_passphrase,
+            head_tracker: None,
+        })
+    }
+
+    /// Excludes the endpoint from threshold calls while its ledger lags behind the others.
+    #[must_use]
+    pub fn with_head_tracker(mut self, head_tracker: Arc<HeadTracker>) -> Self {
+        self.head_tracker = Some(head_tracker);
+        self
     }
 
     fn build_contract_call(
@@ -44,30 +137,28 @@ impl StellarVerifier {
         let operation = contract.call(method_name, Some(sc_args));
         Ok(operation)
     }
-}
 
-#[async_trait]
-impl Verifier for StellarVerifier {
-    fn chain_id(&self) -> ExtendedChainId {
-        ExtendedChainId::Stellar
-    }
-
-    async fn verify(
+    /// Simulates the call and returns what the contract returned.
+    async fn simulate(
         &self,
-        auth_contract_id: String,
-        method_name: String,
+        auth_contract_id: &str,
+        method_name: &str,
         input_data: InputData,
-    ) -> Result<bool> {
+        at: Option<u64>,
+    ) -> Result<ScVal> {
+        if let Some(ledger) = at {
+            bail!("Stellar can't simulate at a past ledger ({ledger}), only the latest one");
+        }
         let input: StellarInputData = input_data.try_into()?;
 
-        let operation = Self::build_contract_call(&auth_contract_id, &method_name, input)?;
+        let operation = Self::build_contract_call(auth_contract_id, method_name, input)?;
 
         let mut source_account = {
             let kp = Keypair::random().map_err(|e| anyhow::anyhow!(e.to_string()))?;
             Account::new(&kp.public_key(), "1").map_err(|e| anyhow::anyhow!(e.to_string()))?
         };
 
-        let tx = TransactionBuilder::new(&mut source_account, Networks::public(), None)
+        let tx = TransactionBuilder::new(&mut source_account, &self.network_passphrase, None)
             .fee(100u32) // An exact value doesn't matter, it's just a placeholder.
             .set_timeout(
                 TIMEOUT
@@ -83,25 +174,384 @@ impl Verifier for StellarVerifier {
 
         // if there was an RPC‐side error, show it:
         if let Some(err) = simulation.error {
-            anyhow::bail!("simulation failed: {err:?}");
+            let err = format!("{err:?}");
+            if let Some(reason) = RevertReason::from_soroban_error(&err) {
+                return Err(reason).with_context(|| format!("simulation failed: {err}"));
+            }
+            anyhow::bail!("simulation failed: {err}");
         }
         // extract the return‐value:
-        if let Some((ScVal::Bool(b), _auths)) = simulation.to_result() {
-            Ok(b)
+        if let Some((value, _auths)) = simulation.to_result() {
+            Ok(value)
         } else {
             anyhow::bail!("unexpected simulation result: {simulation:?}");
         }
     }
+
+    /// Reads the storage entry of the query at the latest ledger, `None` if the contract
+    /// doesn't store the key or its TTL has expired (the entry is archived or deleted).
+    async fn read_storage(
+        &self,
+        contract_id: &str,
+        query: &StellarStorageQuery,
+    ) -> Result<Option<StorageEntry>> {
+        #[derive(Deserialize)]
+        struct Response {
+            result: LedgerEntries,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct LedgerEntries {
+            #[serde(default)]
+            entries: Option<Vec<LedgerEntry>>,
+            latest_ledger: u64,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct LedgerEntry {
+            xdr: String,
+            last_modified_ledger_seq: u32,
+            #[serde(default)]
+            live_until_ledger_seq: Option<u32>,
+        }
+
+        let contract = ScAddress::from_str(contract_id)
+            .map_err(|e| anyhow::anyhow!("Invalid contract id {contract_id}: {e}"))?;
+        let key: ScVal = query
+            .key
+            .clone()
+            .try_into()
+            .context("Failed to convert the storage key")?;
+        let (ledger_key_value, durability) = match query.durability {
+            StellarStorageDurability::Persistent => {
+                (key.clone(), ContractDataDurability::Persistent)
+            }
+            StellarStorageDurability::Temporary => (key.clone(), ContractDataDurability::Temporary),
+            StellarStorageDurability::Instance => (
+                ScVal::LedgerKeyContractInstance,
+                ContractDataDurability::Persistent,
+            ),
+        };
+        let ledger_key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract,
+            key: ledger_key_value,
+            durability,
+        });
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "dontcare",
+            "method": "getLedgerEntries",
+            "params": { "keys": [ledger_key.to_xdr_base64(Limits::none())?] },
+        });
+        let response: Response =
+            post_json_receive_json(&self.http_client, &self.server, &request, ChainId::Stellar)
+                .await?;
+        let result = response.result;
+
+        let Some(entry) = result.entries.unwrap_or_default().into_iter().next() else {
+            return Ok(None);
+        };
+        if Self::is_expired(entry.live_until_ledger_seq, result.latest_ledger) {
+            return Ok(None);
+        }
+        let LedgerEntryData::ContractData(data) =
+            LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none())?
+        else {
+            bail!("Ledger entry of {contract_id} is not contract data");
+        };
+        let value = match query.durability {
+            StellarStorageDurability::Instance => Self::instance_value(data.val, &key)?,
+            StellarStorageDurability::Persistent | StellarStorageDurability::Temporary => {
+                Some(data.val)
+            }
+        };
+        Ok(value.map(|value| StorageEntry {
+            value,
+            last_modified_ledger: entry.last_modified_ledger_seq,
+        }))
+    }
+
+    /// Entries past their TTL are still returned by the RPC until they are evicted,
+    /// but contracts can't read them anymore.
+    fn is_expired(live_until_ledger: Option<u32>, latest_ledger: u64) -> bool {
+        live_until_ledger.is_some_and(|live_until| u64::from(live_until) < latest_ledger)
+    }
+
+    /// The value of `key` in the storage of a contract instance.
+    fn instance_value(instance: ScVal, key: &ScVal) -> Result<Option<ScVal>> {
+        let ScVal::ContractInstance(instance) = instance else {
+            bail!("Expected a contract instance, got {instance:?}");
+        };
+        let Some(storage) = instance.storage else {
+            return Ok(None);
+        };
+        Ok(storage
+            .iter()
+            .find(|entry| entry.key == *key)
+            .map(|entry| entry.val.clone()))
+    }
+
+    fn to_return_value(value: ScVal) -> Result<ReturnValue> {
+        let value = match value {
+            ScVal::Bool(b) => ReturnValue::Bool(b),
+            ScVal::U32(n) => u128::from(n).into(),
+            ScVal::I32(n) => i128::from(n).into(),
+            ScVal::U64(n) => u128::from(n).into(),
+            ScVal::I64(n) => i128::from(n).into(),
+            ScVal::U128(parts) => ((u128::from(parts.hi) << 64) | u128::from(parts.lo)).into(),
+            ScVal::I128(parts) => ((i128::from(parts.hi) << 64) | i128::from(parts.lo)).into(),
+            ScVal::Timepoint(TimePoint(n)) | ScVal::Duration(Duration(n)) => u128::from(n).into(),
+            ScVal::U256(parts) => ReturnValue::Uint(U256::from_limbs([
+                parts.lo_lo,
+                parts.lo_hi,
+                parts.hi_lo,
+                parts.hi_hi,
+            ])),
+            ScVal::I256(parts) => {
+                // Two's complement, the sign is in the highest limb.
+                let hi_hi = u64::from_be_bytes(parts.hi_hi.to_be_bytes());
+                let raw = U256::from_limbs([parts.lo_lo, parts.lo_hi, parts.hi_lo, hi_hi]);
+                if parts.hi_hi < 0 {
+                    ReturnValue::from_signed(true, raw.wrapping_neg())
+                } else {
+                    ReturnValue::Uint(raw)
+                }
+            }
+            value => bail!("unsupported return value: {value:?}"),
+        };
+        Ok(value)
+    }
+
+    /// The value at the dot-separated `path`: fields of structs (maps with symbol keys)
+    /// by name, elements of tuples and vectors by index.
+    fn select_field(value: ScVal, path: &str) -> Result<ScVal> {
+        let mut value = value;
+        for segment in path.split('.') {
+            value = match value {
+                ScVal::Map(Some(map)) => {
+                    let key = segment
+                        .try_into()
+                        .ok()
+                        .map(|symbol| ScVal::Symbol(ScSymbol(symbol)));
+                    map.iter()
+                        .find(|entry| Some(&entry.key) == key.as_ref())
+                        .map(|entry| entry.val.clone())
+                        .with_context(|| format!("No field `{segment}` in `{path}`"))?
+                }
+                ScVal::Vec(Some(items)) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get(index).cloned())
+                    .with_context(|| format!("No element `{segment}` in `{path}`"))?,
+                value => bail!("Can't get `{segment}` of `{path}` from {value:?}"),
+            };
+        }
+        Ok(value)
+    }
+
+    /// Checks the value at `field` against the expectation, without one it has to be `true`.
+    fn check_value(
+        value: ScVal,
+        field: Option<&str>,
+        expectation: Option<Expectation>,
+    ) -> Result<bool> {
+        let value = match field {
+            Some(field) => Self::select_field(value, field)?,
+            None => value,
+        };
+        match (expectation, value) {
+            (Some(expectation), value) => expectation.evaluate(&Self::to_return_value(value)?),
+            (None, ScVal::Bool(b)) => Ok(b),
+            (None, value) => bail!("Expected a bool, got {value:?}"),
+        }
+    }
+}
+
+#[async_trait]
+impl Verifier for StellarVerifier {
+    fn chain_id(&self) -> ExtendedChainId {
+        ExtendedChainId::Stellar
+    }
+
+    async fn verify_at(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> Result<bool> {
+        let value = self
+            .simulate(&auth_contract_id, &method_name, input_data, at)
+            .await?;
+        if let ScVal::Bool(b) = value {
+            Ok(b)
+        } else {
+            anyhow::bail!("unexpected simulation result: {value:?}");
+        }
+    }
+
+    async fn call(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        let value = self
+            .simulate(&auth_contract_id, &method_name, input_data, at)
+            .await?;
+        Self::to_return_value(value)
+    }
+
+    async fn token_balance(
+        &self,
+        token_id: String,
+        _holder: String,
+        _at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        bail!("Token balances are not supported on Stellar, got token {token_id}")
+    }
+
+    /// Stellar has deterministic finality, so a transaction that is found is final.
+    async fn is_transaction_successful(&self, input: TransactionInputData) -> Result<bool> {
+        #[derive(Deserialize)]
+        struct Response {
+            result: TransactionResult,
+        }
+        #[derive(Deserialize)]
+        struct TransactionResult {
+            status: String,
+        }
+        let TransactionId::Stellar { hash } = input.transaction else {
+            bail!(
+                "Expected a Stellar transaction, got {:?}",
+                input.transaction
+            );
+        };
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "dontcare",
+            "method": "getTransaction",
+            "params": { "hash": hash },
+        });
+        let response: Response =
+            post_json_receive_json(&self.http_client, &self.server, &request, ChainId::Stellar)
+                .await?;
+        Ok(response.result.status == "SUCCESS")
+    }
+
+    async fn is_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
+    ) -> Result<bool> {
+        let input = StellarInputData(vec![StellarInputArg::U128(withdrawal.nonce)]);
+        self.verify(
+            contract_id,
+            IS_EXECUTED_METHOD_NAME.to_string(),
+            input.into(),
+        )
+        .await
+    }
 }
 
 impl ThresholdVerifier<StellarVerifier> {
-    pub fn new_stellar(config: ChainValidationConfig) -> Result<Self> {
+    /// Same as [`ThresholdVerifier::verify_auth_call`], but also accepts storage reads
+    /// (the verifiers have to agree on the entry and the ledger that last modified it,
+    /// which is returned as the evidence of the read) and fields of structured return values,
+    /// see [`HotVerifyAuthCall::return_field`]. Only the latest ledger can be read, so `at`
+    /// is rejected.
+    pub async fn verify_stellar_auth_call(
+        &self,
+        auth_call: HotVerifyAuthCall,
+        at: Option<u64>,
+    ) -> Result<StellarVerdict> {
+        if let Some(ledger) = at {
+            bail!("Stellar can't read state at a past ledger ({ledger}), only the latest one");
+        }
+        let contract_id = auth_call.contract_id.clone();
+        let (value, last_modified_ledger) = match &auth_call.input {
+            InputData::StellarStorage(query) => {
+                let query = query.clone();
+                let entry = self
+                    .threshold_call(move |verifier| {
+                        let contract_id = contract_id.clone();
+                        let query = query.clone();
+                        async move { verifier.read_storage(&contract_id, &query).await }
+                    })
+                    .await?;
+                let Some(entry) = entry else {
+                    ensure!(
+                        auth_call.expectation.is_none() && auth_call.return_field.is_none(),
+                        "No storage entry {:?} in {}",
+                        auth_call.input,
+                        auth_call.contract_id
+                    );
+                    return Ok(StellarVerdict {
+                        status: false,
+                        last_modified_ledger: None,
+                    });
+                };
+                let implicit = auth_call.expectation.is_none() && auth_call.return_field.is_none();
+                if implicit && !matches!(entry.value, ScVal::Bool(_)) {
+                    return Ok(StellarVerdict {
+                        status: true,
+                        last_modified_ledger: Some(entry.last_modified_ledger),
+                    });
+                }
+                (entry.value, Some(entry.last_modified_ledger))
+            }
+            InputData::Stellar(_) if auth_call.return_field.is_some() => {
+                let method = auth_call.method.clone();
+                let input = auth_call.input.clone();
+                let value = self
+                    .threshold_call(move |verifier| {
+                        let contract_id = contract_id.clone();
+                        let method = method.clone();
+                        let input = input.clone();
+                        async move { verifier.simulate(&contract_id, &method, input, None).await }
+                    })
+                    .await?;
+                (value, None)
+            }
+            _ => {
+                return Ok(StellarVerdict {
+                    status: self.verify_auth_call(auth_call, None).await?,
+                    last_modified_ledger: None,
+                });
+            }
+        };
+        let status = StellarVerifier::check_value(
+            value,
+            auth_call.return_field.as_deref(),
+            auth_call.expectation,
+        )?;
+        Ok(StellarVerdict {
+            status,
+            last_modified_ledger,
+        })
+    }
+
+    pub fn new_stellar(
+        config: ChainValidationConfig,
+        client: &Arc<reqwest::Client>,
+        network: &NetworkProfile,
+    ) -> Result<Self> {
         let threshold = config.threshold;
+        let head_tracker = HeadTracker::new(ChainId::Stellar, config.head_poll_interval());
         let servers = config.servers;
         let verifiers = servers
             .iter()
-            .map(|s| StellarVerifier::new(s.clone()).map(Arc::new))
+            .map(|s| {
+                StellarVerifier::new(
+                    client.clone(),
+                    s.clone(),
+                    network.stellar_network_passphrase.clone(),
+                )
+                .map(|verifier| Arc::new(verifier.with_head_tracker(head_tracker.clone())))
+            })
             .collect::<Result<Vec<_>>>()?;
+        head_tracker.spawn(&verifiers);
         Ok(Self {
             threshold,
             verifiers,
@@ -113,10 +563,12 @@ impl ThresholdVerifier<StellarVerifier> {
 mod tests {
     use crate::verifiers::stellar::StellarVerifier;
     use crate::verifiers::Verifier;
-    use crate::HOT_VERIFY_METHOD_NAME;
+    use crate::{HOT_VERIFY_METHOD_NAME, STELLAR_PUBLIC_PASSPHRASE};
     use anyhow::Result;
+    use hot_validation_primitives::bridge::expectation::ReturnValue;
     use hot_validation_primitives::bridge::stellar::{StellarInputArg, StellarInputData};
     use hot_validation_primitives::bridge::HotVerifyAuthCall;
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn single_verifier() -> Result<()> {
@@ -124,7 +576,11 @@ mod tests {
         let user_payload = "000000000000005ee4a2fbf444c19970b2289e4ab3eb2ae2e73063a5f5dfc450db7b07413f2d905db96414e0c33eb204".to_string();
         let auth_contract_id =
             "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG".to_string();
-        let validation = StellarVerifier::new("https://mainnet.sorobanrpc.com".to_string())?;
+        let validation = StellarVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            "https://mainnet.sorobanrpc.com".to_string(),
+            STELLAR_PUBLIC_PASSPHRASE.to_string(),
+        )?;
 
         validation
             .verify(
@@ -142,7 +598,11 @@ mod tests {
         let msg_hash = String::new();
         let user_payload = "000000000000005f1d038ae3e890ca50c9a9f00772fcf664b4a8fefb93170d1a6f0e9843a2a816797bab71b6a99ca881".to_string();
         let auth_contract_id = "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG";
-        let validation = StellarVerifier::new("https://mainnet.sorobanrpc.com".to_string())?;
+        let validation = StellarVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            "https://mainnet.sorobanrpc.com".to_string(),
+            STELLAR_PUBLIC_PASSPHRASE.to_string(),
+        )?;
 
         validation
             .verify(
@@ -159,7 +619,11 @@ mod tests {
     async fn stellar_locker_nonce_executed() -> Result<()> {
         let nonce = 1_754_631_474_000_000_070_075_u128;
         let auth_contract_id = "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG";
-        let validation = StellarVerifier::new("https://mainnet.sorobanrpc.com".to_string())?;
+        let validation = StellarVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            "https://mainnet.sorobanrpc.com".to_string(),
+            STELLAR_PUBLIC_PASSPHRASE.to_string(),
+        )?;
 
         validation
             .verify(
@@ -172,6 +636,153 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn stellar_locker_nonce_executed_call() -> Result<()> {
+        let nonce = 1_754_631_474_000_000_070_075_u128;
+        let auth_contract_id = "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG";
+        let validation = StellarVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            "https://mainnet.sorobanrpc.com".to_string(),
+            STELLAR_PUBLIC_PASSPHRASE.to_string(),
+        )?;
+
+        let value = validation
+            .call(
+                auth_contract_id.to_string(),
+                "is_executed".to_string(),
+                StellarInputData(vec![StellarInputArg::U128(nonce)]).into(),
+                None,
+            )
+            .await?;
+        assert_eq!(value, ReturnValue::Bool(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn expired_storage_entries() {
+        assert!(!StellarVerifier::is_expired(None, 100));
+        assert!(!StellarVerifier::is_expired(Some(100), 100));
+        assert!(StellarVerifier::is_expired(Some(99), 100));
+    }
+
+    #[test]
+    fn instance_storage_value() -> Result<()> {
+        use soroban_client::xdr::{
+            ContractExecutable, Hash, ScContractInstance, ScMap, ScMapEntry, ScSymbol, ScVal,
+        };
+
+        let key = |name: &str| ScVal::Symbol(ScSymbol(name.try_into().unwrap()));
+        let instance = |storage| {
+            ScVal::ContractInstance(ScContractInstance {
+                executable: ContractExecutable::Wasm(Hash([0; 32])),
+                storage,
+            })
+        };
+        let storage = ScMap(
+            vec![ScMapEntry {
+                key: key("Admin"),
+                val: ScVal::Bool(true),
+            }]
+            .try_into()?,
+        );
+
+        assert_eq!(
+            StellarVerifier::instance_value(instance(Some(storage.clone())), &key("Admin"))?,
+            Some(ScVal::Bool(true))
+        );
+        assert_eq!(
+            StellarVerifier::instance_value(instance(Some(storage)), &key("Paused"))?,
+            None
+        );
+        assert_eq!(
+            StellarVerifier::instance_value(instance(None), &key("Admin"))?,
+            None
+        );
+        assert!(StellarVerifier::instance_value(ScVal::Void, &key("Admin")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn struct_return_field() -> Result<()> {
+        use hot_validation_primitives::bridge::expectation::Expectation;
+        use soroban_client::xdr::{ScMap, ScMapEntry, ScSymbol, ScVal, ScVec};
+
+        let symbol = |name: &str| ScVal::Symbol(ScSymbol(name.try_into().unwrap()));
+        // `struct State { nonce: u64, paused: bool, limits: (u32, u32) }`
+        let state = ScVal::Map(Some(ScMap(
+            vec![
+                ScMapEntry {
+                    key: symbol("limits"),
+                    val: ScVal::Vec(Some(ScVec(vec![ScVal::U32(1), ScVal::U32(5)].try_into()?))),
+                },
+                ScMapEntry {
+                    key: symbol("nonce"),
+                    val: ScVal::U64(42),
+                },
+                ScMapEntry {
+                    key: symbol("paused"),
+                    val: ScVal::Bool(false),
+                },
+            ]
+            .try_into()?,
+        )));
+
+        let nonce_at_least = |n: u128| Some(Expectation::GreaterOrEqual(ReturnValue::from(n)));
+        assert!(StellarVerifier::check_value(
+            state.clone(),
+            Some("nonce"),
+            nonce_at_least(42)
+        )?);
+        assert!(!StellarVerifier::check_value(
+            state.clone(),
+            Some("nonce"),
+            nonce_at_least(43)
+        )?);
+        assert!(!StellarVerifier::check_value(
+            state.clone(),
+            Some("paused"),
+            None
+        )?);
+        assert_eq!(
+            StellarVerifier::select_field(state.clone(), "limits.1")?,
+            ScVal::U32(5)
+        );
+        assert!(StellarVerifier::select_field(state.clone(), "limits.2").is_err());
+        assert!(StellarVerifier::select_field(state.clone(), "owner").is_err());
+        assert!(StellarVerifier::check_value(state, Some("nonce"), None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn stellar_return_value() -> Result<()> {
+        use soroban_client::xdr::{Int128Parts, Int256Parts, ScVal, UInt128Parts, UInt256Parts};
+
+        let value = StellarVerifier::to_return_value(ScVal::U128(UInt128Parts { hi: 1, lo: 2 }))?;
+        assert_eq!(value, ReturnValue::from((1u128 << 64) | 2));
+        let value = StellarVerifier::to_return_value(ScVal::I128(Int128Parts {
+            hi: -1,
+            lo: u64::MAX,
+        }))?;
+        assert_eq!(value, ReturnValue::from(-1i128));
+        let value = StellarVerifier::to_return_value(ScVal::I256(Int256Parts {
+            hi_hi: -1,
+            hi_lo: u64::MAX,
+            lo_hi: u64::MAX,
+            lo_lo: u64::MAX - 4,
+        }))?;
+        assert_eq!(value, ReturnValue::from(-5i128));
+        let value = StellarVerifier::to_return_value(ScVal::U256(UInt256Parts {
+            hi_hi: 0,
+            hi_lo: 0,
+            lo_hi: 1,
+            lo_lo: 0,
+        }))?;
+        assert_eq!(value, ReturnValue::from(1u128 << 64));
+        assert!(StellarVerifier::to_return_value(ScVal::Void).is_err());
+        Ok(())
+    }
+
     #[test]
     fn check_stellar_bridge_validation_format() {
         let x = r#"
@@ -0,0 +1,218 @@
+//! APIs a [`TonVerifier`](super::TonVerifier) can read TON through, selected by the server URL:
+//! * `liteserver://<host>:<port>?key=<base64 ed25519 key>`: a lite-server over ADNL,
+//!   the account state is checked with Merkle proofs against the masterchain block
+//!   whose root hash the threshold agreed on,
+//! * `https://.../api/v3`: toncenter API v3, which doesn't serve raw transactions,
+//! * anything else: toncenter API v2 JSON-RPC, e.g. `https://toncenter.com/api/v2/jsonRPC`.
+//!
+//! Mixing them in one threshold verifier avoids failures shared by a single API or provider.
+use crate::http_client::post_json_receive_json;
+use crate::verifiers::ton::liteserver::LiteServer;
+use crate::verifiers::ton::toncenter_v3::ToncenterV3;
+use crate::verifiers::ton::types::{
+    LookupBlockRequest, LookupBlockResponse, MasterchainInfoRequest, MasterchainInfoResponse,
+    RpcRequest, RpcResponse, TransactionsRequest, TransactionsResponse,
+};
+use alloy_primitives::U256;
+use anyhow::{anyhow, bail, ensure, Result};
+use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use hot_validation_primitives::bridge::expectation::ReturnValue;
+use hot_validation_primitives::bridge::ton::StackItem;
+use hot_validation_primitives::ChainId;
+use std::sync::Arc;
+use tonlib_core::cell::Cell;
+use tonlib_core::TonAddress;
+
+/// Masterchain block get-methods run at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MasterchainBlock {
+    pub(crate) seqno: u64,
+    /// Root hash the threshold agreed on, lite-servers check their proofs against it.
+    /// `None` outside threshold calls.
+    pub(crate) root_hash: Option<[u8; 32]>,
+}
+
+/// Get-method exit codes of accounts that aren't deployed: toncenter reports -13,
+/// lite-servers -256.
+const NOT_DEPLOYED_EXIT_CODES: [i32; 2] = [-13, -256];
+
+/// The get-method was run on an account that isn't deployed.
+#[derive(thiserror::Error, Debug)]
+#[error("Account {0} isn't deployed")]
+pub(crate) struct AccountNotDeployed(pub(crate) String);
+
+/// Checks that a get-method succeeded, [`AccountNotDeployed`] if there was no account to run it on.
+pub(crate) fn ensure_exit_code(address: &TonAddress, method: &str, exit_code: i32) -> Result<()> {
+    if NOT_DEPLOYED_EXIT_CODES.contains(&exit_code) {
+        return Err(AccountNotDeployed(address.to_base64_url()).into());
+    }
+    // 0 and 1 are the successful TVM exit codes.
+    ensure!(
+        matches!(exit_code, 0 | 1),
+        "Get-method {method} failed with exit code {exit_code}"
+    );
+    Ok(())
+}
+
+#[async_trait]
+pub(crate) trait TonBackend: Send + Sync {
+    /// Seqno of the last masterchain block.
+    async fn last_seqno(&self) -> Result<u64>;
+
+    /// Root hash of the masterchain block `seqno`.
+    async fn block_root_hash(&self, seqno: u64) -> Result<[u8; 32]>;
+
+    /// Runs a get-method at the masterchain `block`, the last one if `None`.
+    /// Numbers in the returned stack are in [`format_num`].
+    async fn run_get_method(
+        &self,
+        address: &TonAddress,
+        method: &str,
+        stack: Vec<StackItem>,
+        block: Option<MasterchainBlock>,
+    ) -> Result<Vec<StackItem>>;
+
+    /// Transaction of `address` with logical time `lt`, starting the lookup at `hash`.
+    /// `None` if the account has no such transaction.
+    async fn transaction(
+        &self,
+        address: &TonAddress,
+        lt: u64,
+        hash: &[u8; 32],
+    ) -> Result<Option<Cell>>;
+
+    /// Up to `count` last transactions of `address`, the newest first.
+    async fn last_transactions(&self, address: &TonAddress, count: u32) -> Result<Vec<Cell>>;
+
+    /// Whether [`Self::transaction`] and [`Self::last_transactions`] are supported,
+    /// threshold calls that read transactions leave the other backends out.
+    fn serves_transactions(&self) -> bool {
+        true
+    }
+}
+
+pub(crate) fn connect(client: Arc<reqwest::Client>, server: &str) -> Result<Box<dyn TonBackend>> {
+    let backend: Box<dyn TonBackend> = if server.starts_with(LiteServer::SCHEME) {
+        Box::new(LiteServer::from_url(server)?)
+    } else if server.trim_end_matches('/').ends_with("/api/v3") {
+        Box::new(ToncenterV3::new(client, server))
+    } else {
+        Box::new(ToncenterV2 {
+            client,
+            server: server.to_string(),
+        })
+    };
+    Ok(backend)
+}
+
+/// Canonical form of stack numbers, `0x`-prefixed lowercase hex like toncenter v2 returns
+/// at the top level, so that backends agree on them.
+pub(crate) fn format_num(negative: bool, magnitude: U256) -> String {
+    match ReturnValue::from_signed(negative, magnitude) {
+        ReturnValue::Negative(magnitude) => format!("-0x{magnitude:x}"),
+        _ => format!("0x{magnitude:x}"),
+    }
+}
+
+/// Brings the numbers of a returned stack item to [`format_num`], toncenter returns
+/// decimal numbers in tuples.
+pub(crate) fn canonical(item: StackItem) -> Result<StackItem> {
+    let item = match item {
+        StackItem::Num(num) => match num.parse::<ReturnValue>()? {
+            ReturnValue::Uint(magnitude) => StackItem::Num(format_num(false, magnitude)),
+            ReturnValue::Negative(magnitude) => StackItem::Num(format_num(true, magnitude)),
+            ReturnValue::Bool(_) => bail!("Expected a number, got {num}"),
+        },
+        StackItem::Tuple(items) => {
+            StackItem::Tuple(items.into_iter().map(canonical).collect::<Result<_>>()?)
+        }
+        StackItem::List(items) => {
+            StackItem::List(items.into_iter().map(canonical).collect::<Result<_>>()?)
+        }
+        item @ (StackItem::Cell(_) | StackItem::Slice(_)) => item,
+    };
+    Ok(item)
+}
+
+/// A base64 block or transaction hash.
+pub(crate) fn decode_hash(hash: &str) -> Result<[u8; 32]> {
+    BASE64_STANDARD
+        .decode(hash)?
+        .try_into()
+        .map_err(|_| anyhow!("Hash must be 32 bytes: {hash}"))
+}
+
+/// The only item of a stack.
+pub(crate) fn single_item(stack: Vec<StackItem>) -> Result<StackItem> {
+    let [item] = <[StackItem; 1]>::try_from(stack)
+        .map_err(|stack| anyhow!("Expected 1 item in stack, got {stack:?}"))?;
+    Ok(item)
+}
+
+/// toncenter API v2 JSON-RPC.
+struct ToncenterV2 {
+    client: Arc<reqwest::Client>,
+    server: String,
+}
+
+#[async_trait]
+impl TonBackend for ToncenterV2 {
+    async fn last_seqno(&self) -> Result<u64> {
+        let request = MasterchainInfoRequest::build();
+        let response: MasterchainInfoResponse =
+            post_json_receive_json(&self.client, &self.server, &request, ChainId::TON_V2).await?;
+        Ok(response.last_seqno())
+    }
+
+    async fn block_root_hash(&self, seqno: u64) -> Result<[u8; 32]> {
+        let request = LookupBlockRequest::masterchain(seqno);
+        let response: LookupBlockResponse =
+            post_json_receive_json(&self.client, &self.server, &request, ChainId::TON_V2).await?;
+        decode_hash(response.root_hash())
+    }
+
+    async fn run_get_method(
+        &self,
+        address: &TonAddress,
+        method: &str,
+        stack: Vec<StackItem>,
+        block: Option<MasterchainBlock>,
+    ) -> Result<Vec<StackItem>> {
+        let request =
+            RpcRequest::build(address, method, stack).at_seqno(block.map(|block| block.seqno));
+        let response: RpcResponse =
+            post_json_receive_json(&self.client, &self.server, &request, ChainId::TON_V2).await?;
+        ensure_exit_code(address, method, response.exit_code())?;
+        response.into_stack().into_iter().map(canonical).collect()
+    }
+
+    async fn transaction(
+        &self,
+        address: &TonAddress,
+        lt: u64,
+        hash: &[u8; 32],
+    ) -> Result<Option<Cell>> {
+        let request = TransactionsRequest::build(address, lt, hash);
+        let response: TransactionsResponse =
+            post_json_receive_json(&self.client, &self.server, &request, ChainId::TON_V2).await?;
+        let Some(transaction) = response.into_first() else {
+            return Ok(None);
+        };
+        ensure!(
+            transaction.transaction_id.lt == lt.to_string(),
+            "RPC returned another transaction: lt={}, hash={}",
+            transaction.transaction_id.lt,
+            transaction.transaction_id.hash
+        );
+        Ok(Some(Cell::from_boc_b64(&transaction.data)?))
+    }
+
+    async fn last_transactions(&self, address: &TonAddress, count: u32) -> Result<Vec<Cell>> {
+        let request = TransactionsRequest::latest(address, count);
+        let response: TransactionsResponse =
+            post_json_receive_json(&self.client, &self.server, &request, ChainId::TON_V2).await?;
+        response.into_cells()
+    }
+}
@@ -0,0 +1,255 @@
+//! Jetton deposits confirmed by the `transfer_notification` the treasury got,
+//! see [`JettonTransfer`](hot_validation_primitives::bridge::ton::JettonTransfer).
+use crate::verifiers::ton::types;
+use anyhow::{anyhow, ensure, Context, Result};
+use hot_validation_primitives::bridge::DepositData;
+use std::str::FromStr;
+use tonlib_core::cell::{Cell, CellParser};
+use tonlib_core::TonAddress;
+
+const TRANSFER_NOTIFICATION: u32 = 0x7362_d09c;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct TransferNotification {
+    pub(super) amount: u128,
+    pub(super) sender: TonAddress,
+    pub(super) nonce: u128,
+    pub(super) receiver: Vec<u8>,
+}
+
+impl TransferNotification {
+    /// The notification `transaction` of `treasury` got from its jetton `wallet`,
+    /// `None` if the transaction was triggered by another message.
+    pub(super) fn from_transaction(
+        transaction: &Cell,
+        treasury: &TonAddress,
+        wallet: &TonAddress,
+    ) -> Result<Option<Self>> {
+        let Some(message) = types::in_message(transaction)? else {
+            return Ok(None);
+        };
+        if &message.src != wallet || &message.dest != treasury || message.bounced {
+            return Ok(None);
+        }
+        Self::parse(&message.body)
+    }
+
+    /// `None` if the body isn't a transfer notification.
+    fn parse(body: &Cell) -> Result<Option<Self>> {
+        let mut parser = body.parser();
+        if parser.remaining_bits() < 32 || parser.load_u32(32)? != TRANSFER_NOTIFICATION {
+            return Ok(None);
+        }
+        // query_id
+        parser.skip_bits(64)?;
+        let amount = parser.load_coins()?.to_string().parse()?;
+        let sender = parser.load_address()?;
+        let (nonce, receiver) = if parser.load_bit()? {
+            let payload = parser.next_reference()?;
+            load_payload(&mut payload.parser())?
+        } else {
+            load_payload(&mut parser)?
+        };
+        Ok(Some(Self {
+            amount,
+            sender,
+            nonce,
+            receiver,
+        }))
+    }
+
+    pub(super) fn check(&self, deposit: &DepositData) -> Result<()> {
+        let amount = deposit.amount.context("Deposit amount not set")?;
+        ensure!(
+            self.amount == amount,
+            "Transferred {} instead of {amount}",
+            self.amount
+        );
+        let sender = deposit.sender.as_ref().context("Deposit sender not set")?;
+        ensure!(
+            self.sender == basechain_address(sender)?,
+            "Transfer sent by {} instead of {}",
+            self.sender,
+            hex::encode(sender)
+        );
+        ensure!(
+            self.nonce == deposit.nonce,
+            "Transfer nonce {} instead of {}",
+            self.nonce,
+            deposit.nonce
+        );
+        let receiver = deposit
+            .receiver
+            .as_ref()
+            .context("Deposit receiver not set")?;
+        ensure!(
+            &self.receiver == receiver,
+            "Transfer to {} instead of {}",
+            hex::encode(&self.receiver),
+            hex::encode(receiver)
+        );
+        Ok(())
+    }
+}
+
+/// Jetton master of the deposit.
+pub(super) fn master_address(deposit: &DepositData) -> Result<TonAddress> {
+    let token_id = deposit.token_id.as_ref().context("Deposit token not set")?;
+    basechain_address(token_id)
+}
+
+fn basechain_address(account_id: &[u8]) -> Result<TonAddress> {
+    ensure!(
+        account_id.len() == 32,
+        "Expected a 32-byte basechain account id, got {} bytes",
+        account_id.len()
+    );
+    Ok(TonAddress::from_str(&format!(
+        "0:{}",
+        hex::encode(account_id)
+    ))?)
+}
+
+/// `nonce:uint128 receiver:^Cell`
+fn load_payload(parser: &mut CellParser) -> Result<(u128, Vec<u8>)> {
+    let nonce = parser
+        .load_bits(128)?
+        .try_into()
+        .map(u128::from_be_bytes)
+        .map_err(|_| anyhow!("Expected 128 bits"))?;
+    let receiver = load_snake_bytes(&parser.next_reference()?)?;
+    Ok((nonce, receiver))
+}
+
+/// Bytes of a cell, continued in its first reference.
+fn load_snake_bytes(cell: &Cell) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut cell = cell;
+    loop {
+        ensure!(
+            cell.bit_len() % 8 == 0,
+            "Snake cell of {} bits isn't made of bytes",
+            cell.bit_len()
+        );
+        bytes.extend(cell.parser().load_bits(cell.bit_len())?);
+        match cell.references().first() {
+            Some(next) => cell = next.as_ref(),
+            None => return Ok(bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TransferNotification, TRANSFER_NOTIFICATION};
+    use crate::verifiers::ton::types;
+    use anyhow::Result;
+    use hot_validation_primitives::bridge::DepositData;
+    use std::str::FromStr;
+    use tonlib_core::cell::{ArcCell, Cell, CellBuilder};
+    use tonlib_core::TonAddress;
+
+    const TREASURY: &str = "EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ";
+    const WALLET: &str = "EQAgwUhaRZwU77BXUVEbtnEN8tplzDWMqUr0TbXWfez58tTL";
+
+    fn deposit() -> DepositData {
+        DepositData {
+            sender: Some(vec![0x11; 32]),
+            receiver: Some(b"alice.near".to_vec()),
+            token_id: Some(vec![0x22; 32]),
+            amount: Some(1_000_000),
+            nonce: 1_753_218_716_000_000_003_679,
+        }
+    }
+
+    fn notification_body(deposit: &DepositData) -> Result<Cell> {
+        let receiver = CellBuilder::new()
+            .store_slice(deposit.receiver.as_deref().unwrap_or_default())?
+            .build()?;
+        let payload = CellBuilder::new()
+            .store_slice(&deposit.nonce.to_be_bytes())?
+            .store_reference(&ArcCell::new(receiver))?
+            .build()?;
+        let amount = deposit.amount.unwrap_or_default().to_be_bytes();
+        let amount = &amount[amount.iter().take_while(|byte| **byte == 0).count()..];
+        let sender = TonAddress::from_str(&format!(
+            "0:{}",
+            hex::encode(deposit.sender.as_deref().unwrap_or_default())
+        ))?;
+        Ok(CellBuilder::new()
+            .store_u32(32, TRANSFER_NOTIFICATION)?
+            .store_u64(64, 0)?
+            .store_u8(4, u8::try_from(amount.len())?)?
+            .store_slice(amount)?
+            .store_address(&sender)?
+            .store_bit(true)?
+            .store_reference(&ArcCell::new(payload))?
+            .build()?)
+    }
+
+    /// An ordinary transaction of `dest` triggered by an internal message with `body`,
+    /// only the parts that are read are filled in.
+    fn transaction(src: &TonAddress, dest: &TonAddress, body: Cell) -> Result<Cell> {
+        let message = CellBuilder::new()
+            .store_bit(false)?
+            .store_bit(true)?
+            .store_bit(true)?
+            .store_bit(false)?
+            .store_address(src)?
+            .store_address(dest)?
+            .store_u8(4, 0)?
+            .store_bit(false)?
+            .store_u8(4, 0)?
+            .store_u8(4, 0)?
+            .store_u64(64, 0)?
+            .store_u32(32, 0)?
+            .store_bit(false)?
+            .store_bit(true)?
+            .store_reference(&ArcCell::new(body))?
+            .build()?;
+        let messages = CellBuilder::new()
+            .store_bit(true)?
+            .store_reference(&ArcCell::new(message))?
+            .store_bit(false)?
+            .build()?;
+        let empty = ArcCell::new(CellBuilder::new().build()?);
+        Ok(CellBuilder::new()
+            .store_u8(4, 0b0111)?
+            .store_slice(&[0; 32])?
+            .store_u64(64, 2)?
+            .store_slice(&[0x33; 32])?
+            .store_u64(64, 1)?
+            .store_reference(&ArcCell::new(messages))?
+            .store_reference(&empty)?
+            .store_reference(&empty)?
+            .build()?)
+    }
+
+    #[test]
+    fn transfer_notification() -> Result<()> {
+        let treasury = TonAddress::from_base64_url(TREASURY)?;
+        let wallet = TonAddress::from_base64_url(WALLET)?;
+        let deposit = deposit();
+        let transaction = transaction(&wallet, &treasury, notification_body(&deposit)?)?;
+        assert_eq!(types::prev_transaction_hash(&transaction)?, [0x33; 32]);
+
+        let notification =
+            TransferNotification::from_transaction(&transaction, &treasury, &wallet)?
+                .expect("transfer notification");
+        assert_eq!(notification.amount, 1_000_000);
+        assert_eq!(notification.receiver, b"alice.near");
+        notification.check(&deposit)?;
+
+        let other = DepositData {
+            amount: Some(1),
+            ..deposit
+        };
+        assert!(notification.check(&other).is_err());
+
+        // Only the treasury's jetton wallet can notify about transfers.
+        assert!(
+            TransferNotification::from_transaction(&transaction, &treasury, &treasury)?.is_none()
+        );
+        Ok(())
+    }
+}
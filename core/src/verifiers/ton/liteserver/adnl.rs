@@ -0,0 +1,200 @@
+//! ADNL over TCP, the transport of lite-servers:
+//! <https://docs.ton.org/v3/documentation/network/protocols/adnl/tcp>.
+//!
+//! The client sends its ephemeral ed25519 key and 160 random bytes encrypted with the x25519
+//! secret shared with the server, the session is then AES-CTR encrypted with keys and nonces
+//! taken from those bytes.
+use super::tl::{
+    TlReader, TlWriter, ADNL_MESSAGE_ANSWER, ADNL_MESSAGE_QUERY, LITE_SERVER_QUERY, PUB_ED25519,
+};
+use aes::Aes256;
+use anyhow::{ensure, Context, Result};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+type Cipher = ctr::Ctr128BE<Aes256>;
+
+const MAX_PACKET_SIZE: usize = 1 << 24;
+
+/// Packets are `size:u32 nonce:[32] payload sha256(nonce ++ payload):[32]`, all encrypted.
+pub(super) struct AdnlConnection {
+    stream: TcpStream,
+    read_cipher: Cipher,
+    write_cipher: Cipher,
+}
+
+impl AdnlConnection {
+    /// Connects to the server with the ed25519 public key `server_key`,
+    /// the server confirms the handshake with an empty packet.
+    pub(super) async fn connect(address: &str, server_key: &[u8; 32]) -> Result<Self> {
+        let mut stream = TcpStream::connect(address).await?;
+
+        let mut secret = [0; 32];
+        rand::rng().fill_bytes(&mut secret);
+        let public = EdwardsPoint::mul_base_clamped(secret).compress().to_bytes();
+        let shared = shared_secret(secret, server_key)?;
+
+        let mut params = [0; 160];
+        rand::rng().fill_bytes(&mut params);
+        let params_hash: [u8; 32] = Sha256::digest(params).into();
+        let mut encrypted = params;
+        handshake_cipher(&shared, &params_hash).apply_keystream(&mut encrypted);
+
+        let mut handshake = Vec::with_capacity(256);
+        handshake.extend_from_slice(&key_id(server_key));
+        handshake.extend_from_slice(&public);
+        handshake.extend_from_slice(&params_hash);
+        handshake.extend_from_slice(&encrypted);
+        stream.write_all(&handshake).await?;
+
+        let (read_cipher, write_cipher) = session_ciphers(&params, true);
+        let mut connection = Self {
+            stream,
+            read_cipher,
+            write_cipher,
+        };
+        let confirmation = connection.receive().await?;
+        ensure!(
+            confirmation.is_empty(),
+            "Unexpected ADNL handshake confirmation"
+        );
+        Ok(connection)
+    }
+
+    /// Server side of [`Self::connect`], for the local lite-server of the tests.
+    #[cfg(test)]
+    pub(super) async fn accept(mut stream: TcpStream, secret: [u8; 32]) -> Result<Self> {
+        let mut handshake = [0; 256];
+        stream.read_exact(&mut handshake).await?;
+        let public = EdwardsPoint::mul_base_clamped(secret).compress().to_bytes();
+        ensure!(
+            handshake[..32] == key_id(&public),
+            "Handshake for another key"
+        );
+
+        let client_key: [u8; 32] = handshake[32..64].try_into()?;
+        let params_hash: [u8; 32] = handshake[64..96].try_into()?;
+        let mut params: [u8; 160] = handshake[96..].try_into()?;
+        let shared = shared_secret(secret, &client_key)?;
+        handshake_cipher(&shared, &params_hash).apply_keystream(&mut params);
+        ensure!(
+            Sha256::digest(params).as_slice() == params_hash,
+            "Invalid handshake checksum"
+        );
+
+        let (read_cipher, write_cipher) = session_ciphers(&params, false);
+        let mut connection = Self {
+            stream,
+            read_cipher,
+            write_cipher,
+        };
+        connection.send(&[]).await?;
+        Ok(connection)
+    }
+
+    /// Sends `liteServer.query` wrapped into `adnl.message.query`, returns the answer.
+    pub(super) async fn query(&mut self, query: &[u8]) -> Result<Vec<u8>> {
+        let mut query_id = [0; 32];
+        rand::rng().fill_bytes(&mut query_id);
+
+        let mut lite_query = TlWriter::default();
+        lite_query.u32(LITE_SERVER_QUERY).bytes(query)?;
+        let mut message = TlWriter::default();
+        message
+            .u32(ADNL_MESSAGE_QUERY)
+            .int256(&query_id)
+            .bytes(&lite_query.into_inner())?;
+        self.send(&message.into_inner()).await?;
+
+        loop {
+            let packet = self.receive().await?;
+            let mut reader = TlReader::new(&packet);
+            reader.expect(ADNL_MESSAGE_ANSWER)?;
+            // Answers to abandoned queries are skipped.
+            if reader.int256()? == query_id {
+                return reader.bytes();
+            }
+        }
+    }
+
+    pub(super) async fn send(&mut self, payload: &[u8]) -> Result<()> {
+        let mut nonce = [0; 32];
+        rand::rng().fill_bytes(&mut nonce);
+        let hash = Sha256::new()
+            .chain_update(nonce)
+            .chain_update(payload)
+            .finalize();
+
+        let mut packet = Vec::with_capacity(4 + 64 + payload.len());
+        packet.extend_from_slice(&u32::try_from(64 + payload.len())?.to_le_bytes());
+        packet.extend_from_slice(&nonce);
+        packet.extend_from_slice(payload);
+        packet.extend_from_slice(&hash);
+        self.write_cipher.apply_keystream(&mut packet);
+        self.stream.write_all(&packet).await?;
+        Ok(())
+    }
+
+    pub(super) async fn receive(&mut self) -> Result<Vec<u8>> {
+        let mut size = [0; 4];
+        self.stream.read_exact(&mut size).await?;
+        self.read_cipher.apply_keystream(&mut size);
+        let size = usize::try_from(u32::from_le_bytes(size))?;
+        ensure!(
+            (64..=MAX_PACKET_SIZE).contains(&size),
+            "Invalid ADNL packet size {size}"
+        );
+
+        let mut packet = vec![0; size];
+        self.stream.read_exact(&mut packet).await?;
+        self.read_cipher.apply_keystream(&mut packet);
+        let (data, hash) = packet.split_at(size - 32);
+        ensure!(
+            Sha256::digest(data).as_slice() == hash,
+            "Invalid ADNL packet checksum"
+        );
+        Ok(data[32..].to_vec())
+    }
+}
+
+/// `sha256` of the TL `pub.ed25519 key:int256`.
+fn key_id(key: &[u8; 32]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(PUB_ED25519.to_le_bytes())
+        .chain_update(key)
+        .finalize()
+        .into()
+}
+
+/// x25519 of our clamped `secret` and the peer ed25519 key converted to the Montgomery form.
+fn shared_secret(secret: [u8; 32], peer_key: &[u8; 32]) -> Result<[u8; 32]> {
+    let peer = CompressedEdwardsY(*peer_key)
+        .decompress()
+        .context("Invalid ed25519 key")?;
+    Ok(peer.to_montgomery().mul_clamped(secret).to_bytes())
+}
+
+fn cipher(key: &[u8], nonce: &[u8]) -> Cipher {
+    Cipher::new_from_slices(key, nonce).expect("Key and nonce lengths are fixed")
+}
+
+fn handshake_cipher(shared: &[u8; 32], params_hash: &[u8; 32]) -> Cipher {
+    let key = [&shared[..16], &params_hash[16..]].concat();
+    let nonce = [&params_hash[..4], &shared[20..]].concat();
+    cipher(&key, &nonce)
+}
+
+/// `(read, write)` ciphers of the client or of the server.
+fn session_ciphers(params: &[u8; 160], client: bool) -> (Cipher, Cipher) {
+    let first = cipher(&params[..32], &params[64..80]);
+    let second = cipher(&params[32..64], &params[80..96]);
+    if client {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
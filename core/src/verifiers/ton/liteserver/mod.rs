@@ -0,0 +1,695 @@
+//! Lite-server backend. Get-methods run on the lite-server, and the account state they ran on
+//! is checked with Merkle proofs: account state → shard block → masterchain block.
+//! The proofs only bind the state to the root hash of the masterchain block, so in threshold
+//! calls that root hash is the one the servers agreed on beforehand, not the one the
+//! lite-server reports, see [`MasterchainBlock::root_hash`].
+mod adnl;
+mod proof;
+mod tl;
+mod vm_stack;
+
+use crate::verifiers::ton::backend::{
+    ensure_exit_code, AccountNotDeployed, MasterchainBlock, TonBackend,
+};
+use crate::verifiers::ton::types;
+use adnl::AdnlConnection;
+use anyhow::{anyhow, ensure, Context, Result};
+use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use hot_validation_primitives::bridge::ton::StackItem;
+use proof::{MASTERCHAIN, MASTERCHAIN_SHARD};
+use std::time::Duration;
+use tl::{
+    BlockIdExt, TlReader, TlWriter, ACCOUNT_STATE, BLOCK_HEADER, GET_ACCOUNT_STATE,
+    GET_MASTERCHAIN_INFO, GET_TRANSACTIONS, LOOKUP_BLOCK, MASTERCHAIN_INFO, RUN_METHOD_RESULT,
+    RUN_SMC_METHOD, TRANSACTION_LIST,
+};
+use tokio::sync::Mutex;
+use tonlib_core::cell::{BagOfCells, Cell};
+use tonlib_core::{TonAddress, TonHash};
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// `runSmcMethod` mode: shard and state proofs (bit 0), account state proof (bit 1), result (bit 2).
+const RUN_METHOD_MODE: u32 = 0b111;
+const INIT_C7_MODE: u32 = 1 << 3;
+const LIB_EXTRAS_MODE: u32 = 1 << 4;
+
+pub(super) struct LiteServer {
+    /// `host:port`
+    address: String,
+    key: [u8; 32],
+    /// Queries go one by one, the connection is reopened after a failure.
+    connection: Mutex<Option<AdnlConnection>>,
+}
+
+impl LiteServer {
+    pub(super) const SCHEME: &'static str = "liteserver://";
+
+    /// `server` is `liteserver://<host>:<port>?key=<base64 ed25519 public key>`.
+    pub(super) fn from_url(server: &str) -> Result<Self> {
+        let rest = server
+            .strip_prefix(Self::SCHEME)
+            .with_context(|| format!("Not a lite-server URL: {server}"))?;
+        let (address, key) = rest
+            .split_once("?key=")
+            .with_context(|| format!("Lite-server key is missing in {server}"))?;
+        let key = BASE64_STANDARD
+            .decode(key)?
+            .try_into()
+            .map_err(|_| anyhow!("Lite-server key must be 32 bytes: {server}"))?;
+        Ok(Self {
+            address: address.to_string(),
+            key,
+            connection: Mutex::default(),
+        })
+    }
+
+    async fn query(&self, query: Vec<u8>) -> Result<Vec<u8>> {
+        let mut connection = self.connection.lock().await;
+        let answer = tokio::time::timeout(QUERY_TIMEOUT, async {
+            if connection.is_none() {
+                *connection = Some(AdnlConnection::connect(&self.address, &self.key).await?);
+            }
+            let connection = connection.as_mut().context("Lite-server isn't connected")?;
+            connection.query(&query).await
+        })
+        .await
+        .unwrap_or_else(|_| Err(anyhow!("Lite-server {} timed out", self.address)));
+        if answer.is_err() {
+            *connection = None;
+        }
+        answer
+    }
+
+    /// `liteServer.getMasterchainInfo = liteServer.MasterchainInfo`
+    async fn last_block(&self) -> Result<BlockIdExt> {
+        let mut request = TlWriter::default();
+        request.u32(GET_MASTERCHAIN_INFO);
+        let answer = self.query(request.into_inner()).await?;
+        let mut reader = TlReader::new(&answer);
+        reader.expect(MASTERCHAIN_INFO)?;
+        reader.block_id()
+    }
+
+    /// `liteServer.lookupBlock mode:# id:tonNode.blockId ... = liteServer.BlockHeader`,
+    /// the header proof has to match the returned root hash.
+    async fn masterchain_block(&self, seqno: u64) -> Result<BlockIdExt> {
+        let seqno = u32::try_from(seqno)?;
+        let mut request = TlWriter::default();
+        // Mode 1: by seqno.
+        request
+            .u32(LOOKUP_BLOCK)
+            .u32(1)
+            .i32(MASTERCHAIN)
+            .u64(MASTERCHAIN_SHARD)
+            .u32(seqno);
+        let answer = self.query(request.into_inner()).await?;
+        let mut reader = TlReader::new(&answer);
+        reader.expect(BLOCK_HEADER)?;
+        let id = reader.block_id()?;
+        let _mode = reader.u32()?;
+        let header_proof = reader.bytes()?;
+        ensure!(
+            id.workchain == MASTERCHAIN && id.seqno == seqno,
+            "Lite-server returned block {id:?} instead of masterchain block {seqno}"
+        );
+        proof::check_block_header(&header_proof, &id)?;
+        Ok(id)
+    }
+
+    /// `liteServer.getTransactions count:# account:liteServer.accountId lt:long hash:int256
+    /// = liteServer.TransactionList`, up to `count` transactions starting at `lt`/`hash`,
+    /// the newest first. Lite-servers return at most 16 at once, without a proof.
+    async fn transactions(
+        &self,
+        address: &TonAddress,
+        count: u32,
+        lt: u64,
+        hash: &[u8; 32],
+    ) -> Result<Vec<Cell>> {
+        let mut request = TlWriter::default();
+        request
+            .u32(GET_TRANSACTIONS)
+            .u32(count)
+            .i32(address.workchain)
+            .int256(&account_id(address)?)
+            .u64(lt)
+            .int256(hash);
+        let answer = self.query(request.into_inner()).await?;
+
+        // liteServer.transactionList ids:(vector tonNode.blockIdExt) transactions:bytes
+        let mut reader = TlReader::new(&answer);
+        reader.expect(TRANSACTION_LIST)?;
+        for _ in 0..reader.u32()? {
+            reader.block_id()?;
+        }
+        let transactions = reader.bytes()?;
+        if transactions.is_empty() {
+            return Ok(vec![]);
+        }
+        let boc = BagOfCells::parse(&transactions)?;
+        Ok(boc.roots.iter().map(|root| Cell::clone(root)).collect())
+    }
+}
+
+#[async_trait]
+impl TonBackend for LiteServer {
+    async fn last_seqno(&self) -> Result<u64> {
+        Ok(self.last_block().await?.seqno.into())
+    }
+
+    async fn block_root_hash(&self, seqno: u64) -> Result<[u8; 32]> {
+        Ok(self.masterchain_block(seqno).await?.root_hash)
+    }
+
+    /// `liteServer.runSmcMethod mode:# id:tonNode.blockIdExt account:liteServer.accountId
+    /// method_id:long params:bytes = liteServer.RunMethodResult`
+    async fn run_get_method(
+        &self,
+        address: &TonAddress,
+        method: &str,
+        stack: Vec<StackItem>,
+        block: Option<MasterchainBlock>,
+    ) -> Result<Vec<StackItem>> {
+        let block = match block {
+            Some(MasterchainBlock { seqno, root_hash }) => {
+                let block = self.masterchain_block(seqno).await?;
+                if let Some(root_hash) = root_hash {
+                    ensure!(
+                        block.root_hash == root_hash,
+                        "Lite-server has another masterchain block {seqno} than the agreed one"
+                    );
+                }
+                block
+            }
+            None => self.last_block().await?,
+        };
+        let account_id = account_id(address)?;
+        let mut request = TlWriter::default();
+        request
+            .u32(RUN_SMC_METHOD)
+            .u32(RUN_METHOD_MODE)
+            .block_id(&block)
+            .i32(address.workchain)
+            .int256(&account_id)
+            .u64(method_id(method))
+            .bytes(&vm_stack::serialize(&stack)?)?;
+        let answer = self.query(request.into_inner()).await?;
+
+        // liteServer.runMethodResult mode:# id:tonNode.blockIdExt shardblk:tonNode.blockIdExt
+        // shard_proof:mode.0?bytes proof:mode.0?bytes state_proof:mode.1?bytes
+        // init_c7:mode.3?bytes lib_extras:mode.4?bytes exit_code:int result:mode.2?bytes
+        let mut reader = TlReader::new(&answer);
+        reader.expect(RUN_METHOD_RESULT)?;
+        let mode = reader.u32()?;
+        ensure!(
+            mode & RUN_METHOD_MODE == RUN_METHOD_MODE,
+            "Lite-server answered without proofs: mode={mode:#b}"
+        );
+        let id = reader.block_id()?;
+        ensure!(
+            id == block,
+            "Lite-server ran {method} at {id:?} instead of {block:?}"
+        );
+        let shard_block = reader.block_id()?;
+        let shard_proof = reader.bytes()?;
+        let proof = reader.bytes()?;
+        let state_proof = reader.bytes()?;
+        if mode & INIT_C7_MODE != 0 {
+            reader.bytes()?;
+        }
+        if mode & LIB_EXTRAS_MODE != 0 {
+            reader.bytes()?;
+        }
+        let exit_code = reader.i32()?;
+        let result = reader.bytes()?;
+
+        let Some(account) = proof::check_account_state(
+            &block,
+            &shard_block,
+            &shard_proof,
+            &proof,
+            address.workchain,
+            &account_id,
+        )?
+        else {
+            return Err(AccountNotDeployed(address.to_base64_url()).into());
+        };
+        proof::check_state_proof(&state_proof, &account.account_hash)?;
+        ensure_exit_code(address, method, exit_code)?;
+        vm_stack::deserialize(&result)
+    }
+
+    async fn transaction(
+        &self,
+        address: &TonAddress,
+        lt: u64,
+        hash: &[u8; 32],
+    ) -> Result<Option<Cell>> {
+        let transactions = self.transactions(address, 1, lt, hash).await?;
+        Ok(transactions.into_iter().next())
+    }
+
+    /// The last transaction is taken from the proven account state, the ones before it
+    /// have to follow the `prev_trans_hash` chain.
+    async fn last_transactions(&self, address: &TonAddress, count: u32) -> Result<Vec<Cell>> {
+        let block = self.last_block().await?;
+        let account_id = account_id(address)?;
+        let mut request = TlWriter::default();
+        request
+            .u32(GET_ACCOUNT_STATE)
+            .block_id(&block)
+            .i32(address.workchain)
+            .int256(&account_id);
+        let answer = self.query(request.into_inner()).await?;
+
+        // liteServer.accountState id:tonNode.blockIdExt shardblk:tonNode.blockIdExt
+        // shard_proof:bytes proof:bytes state:bytes
+        let mut reader = TlReader::new(&answer);
+        reader.expect(ACCOUNT_STATE)?;
+        let id = reader.block_id()?;
+        ensure!(
+            id == block,
+            "Lite-server returned the account state at {id:?} instead of {block:?}"
+        );
+        let shard_block = reader.block_id()?;
+        let shard_proof = reader.bytes()?;
+        let proof = reader.bytes()?;
+        let Some(account) = proof::check_account_state(
+            &block,
+            &shard_block,
+            &shard_proof,
+            &proof,
+            address.workchain,
+            &account_id,
+        )?
+        else {
+            return Ok(vec![]);
+        };
+        if account.last_trans_lt == 0 {
+            return Ok(vec![]);
+        }
+
+        let transactions = self
+            .transactions(
+                address,
+                count,
+                account.last_trans_lt,
+                &account.last_trans_hash,
+            )
+            .await?;
+        let mut expected = account.last_trans_hash;
+        for transaction in &transactions {
+            ensure!(
+                transaction.cell_hash() == TonHash::from(expected),
+                "Lite-server returned a transaction out of the account chain"
+            );
+            expected = types::prev_transaction_hash(transaction)?;
+        }
+        Ok(transactions)
+    }
+}
+
+fn account_id(address: &TonAddress) -> Result<[u8; 32]> {
+    let raw = address.to_hex();
+    let (_, account_id) = raw
+        .split_once(':')
+        .with_context(|| format!("Invalid raw address {raw}"))?;
+    hex::decode(account_id)?
+        .try_into()
+        .map_err(|_| anyhow!("Invalid raw address {raw}"))
+}
+
+/// `crc16(name) | 0x10000`, the id of a get-method in FunC and Tact.
+fn method_id(name: &str) -> u64 {
+    // CRC-16/XMODEM
+    let mut crc: u16 = 0;
+    for byte in name.bytes() {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x1021
+            };
+        }
+    }
+    u64::from(crc) | 0x10000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::adnl::AdnlConnection;
+    use super::proof::{MASTERCHAIN, MASTERCHAIN_SHARD};
+    use super::tl::{
+        BlockIdExt, TlReader, TlWriter, ADNL_MESSAGE_ANSWER, ADNL_MESSAGE_QUERY, BLOCK_HEADER,
+        GET_MASTERCHAIN_INFO, LITE_SERVER_QUERY, LOOKUP_BLOCK, MASTERCHAIN_INFO, RUN_METHOD_RESULT,
+        RUN_SMC_METHOD,
+    };
+    use super::{account_id, method_id, proof, vm_stack, LiteServer};
+    use crate::verifiers::ton::backend::{MasterchainBlock, TonBackend};
+    use anyhow::Result;
+    use base64::prelude::BASE64_STANDARD;
+    use base64::Engine;
+    use curve25519_dalek::edwards::EdwardsPoint;
+    use hot_validation_primitives::bridge::ton::StackItem;
+    use std::str::FromStr;
+    use tokio::net::TcpListener;
+    use tonlib_core::cell::{ArcCell, BagOfCells, Cell, CellBuilder};
+    use tonlib_core::{TonAddress, TonHash};
+
+    const ACCOUNT: &str = "-1:3333333333333333333333333333333333333333333333333333333333333333";
+
+    #[test]
+    fn method_ids() {
+        assert_eq!(method_id("seqno"), 85143);
+        assert_eq!(method_id("get_wallet_data"), 97026);
+    }
+
+    fn hash(cell: &Cell) -> [u8; 32] {
+        cell.cell_hash().as_slice()[..]
+            .try_into()
+            .expect("32 bytes")
+    }
+
+    fn depth(cell: &Cell) -> u16 {
+        cell.references()
+            .iter()
+            .map(|child| depth(child) + 1)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// A Merkle proof of `cell`, `proven` is `cell` with the parts the proof doesn't need pruned.
+    fn merkle_proof(cell: &ArcCell, proven: ArcCell) -> Result<ArcCell> {
+        let mut data = vec![3];
+        data.extend_from_slice(&hash(cell));
+        data.extend_from_slice(&depth(cell).to_be_bytes());
+        Ok(ArcCell::new(Cell::new(
+            data,
+            8 + 256 + 16,
+            vec![proven],
+            true,
+        )?))
+    }
+
+    /// The pruned branch a proof keeps in place of `cell`: its level 0 hash and depth.
+    fn pruned(cell: &ArcCell) -> Result<ArcCell> {
+        let mut data = vec![1, 1];
+        data.extend_from_slice(&hash(cell));
+        data.extend_from_slice(&depth(cell).to_be_bytes());
+        Ok(ArcCell::new(Cell::new(
+            data,
+            8 + 8 + 256 + 16,
+            vec![],
+            true,
+        )?))
+    }
+
+    fn empty() -> Result<ArcCell> {
+        Ok(ArcCell::new(CellBuilder::new().build()?))
+    }
+
+    /// A shard state of a single account, `other` in place of the rest.
+    fn build_state(account_id: &[u8; 32], account: &ArcCell, other: &ArcCell) -> Result<ArcCell> {
+        // HashmapAugE 256 ShardAccount DepthBalanceInfo with a single hml_long$10 leaf.
+        let leaf = CellBuilder::new()
+            .store_u8(2, 0b10)?
+            .store_u32(9, 256)?
+            .store_slice(account_id)?
+            .store_u8(5, 0)?
+            .store_u8(4, 0)?
+            .store_bit(false)?
+            .store_reference(account)?
+            .store_slice(&[0; 32])?
+            .store_u64(64, 1)?
+            .build()?;
+        let accounts = CellBuilder::new()
+            .store_bit(true)?
+            .store_reference(&ArcCell::new(leaf))?
+            .store_u8(5, 0)?
+            .store_u8(4, 0)?
+            .store_bit(false)?
+            .build()?;
+        Ok(ArcCell::new(
+            CellBuilder::new()
+                .store_u32(32, 0x9023_afe2)?
+                .store_reference(other)?
+                .store_reference(&ArcCell::new(accounts))?
+                .store_reference(other)?
+                .build()?,
+        ))
+    }
+
+    /// The update of `state` to itself, `proven` in place of both states.
+    fn build_update(state: &ArcCell, proven: &ArcCell) -> Result<ArcCell> {
+        let mut update = vec![4];
+        for _ in 0..2 {
+            update.extend_from_slice(&hash(state));
+        }
+        for _ in 0..2 {
+            update.extend_from_slice(&depth(state).to_be_bytes());
+        }
+        Ok(ArcCell::new(Cell::new(
+            update,
+            8 + 2 * 256 + 2 * 16,
+            vec![proven.clone(), proven.clone()],
+            true,
+        )?))
+    }
+
+    fn build_block(info: &ArcCell, other: &ArcCell, update: &ArcCell) -> Result<ArcCell> {
+        Ok(ArcCell::new(
+            CellBuilder::new()
+                .store_u32(32, 0x11ef_55aa)?
+                .store_reference(info)?
+                .store_reference(other)?
+                .store_reference(update)?
+                .store_reference(other)?
+                .build()?,
+        ))
+    }
+
+    /// A masterchain block with a state of a single account, and the proofs a lite-server
+    /// returns for it. Like real proofs, they prune the cells that aren't read.
+    struct Masterchain {
+        id: BlockIdExt,
+        header_proof: Vec<u8>,
+        proof: Vec<u8>,
+        state_proof: Vec<u8>,
+    }
+
+    fn masterchain(account_id: &[u8; 32]) -> Result<Masterchain> {
+        let account = ArcCell::new(CellBuilder::new().store_u32(32, 42)?.build()?);
+        let state = build_state(account_id, &account, &empty()?)?;
+        let update = build_update(&state, &state)?;
+        let info = ArcCell::new(
+            CellBuilder::new()
+                .store_u32(32, 0x9bc7_a987)?
+                .store_u32(32, 0)?
+                .store_u8(8, 0)?
+                .store_u8(8, 0)?
+                .store_u32(32, 7)?
+                .build()?,
+        );
+        let block = build_block(&info, &empty()?, &update)?;
+        let id = BlockIdExt {
+            workchain: MASTERCHAIN,
+            shard: MASTERCHAIN_SHARD,
+            seqno: 7,
+            root_hash: hash(&block),
+            file_hash: [0; 32],
+        };
+
+        let header = build_block(&info, &pruned(&empty()?)?, &pruned(&update)?)?;
+        let header_proof =
+            BagOfCells::from_root(Cell::clone(&merkle_proof(&block, header)?)).serialize(false)?;
+        let proven_block = build_block(
+            &pruned(&info)?,
+            &pruned(&empty()?)?,
+            &build_update(&state, &pruned(&state)?)?,
+        )?;
+        let proven_state = build_state(account_id, &pruned(&account)?, &pruned(&empty()?)?)?;
+        let proof = BagOfCells {
+            roots: vec![
+                merkle_proof(&block, proven_block)?,
+                merkle_proof(&state, proven_state)?,
+            ],
+        }
+        .serialize(false)?;
+        let state_proof =
+            BagOfCells::from_root(Cell::clone(&merkle_proof(&account, account.clone())?))
+                .serialize(false)?;
+        Ok(Masterchain {
+            id,
+            header_proof,
+            proof,
+            state_proof,
+        })
+    }
+
+    #[test]
+    fn pruned_proofs() -> Result<()> {
+        let account_id = account_id(&TonAddress::from_str(ACCOUNT)?)?;
+        let Masterchain {
+            id,
+            header_proof,
+            proof,
+            state_proof,
+        } = masterchain(&account_id)?;
+
+        // Pruned branches raise the level of the cells above them,
+        // the proofs are of their level 0 hashes.
+        let roots = BagOfCells::parse(&proof)?.roots;
+        let proven_block = roots[0].reference(0)?;
+        assert_ne!(proven_block.cell_hash(), proven_block.get_hash(0));
+        assert_eq!(proven_block.get_hash(0), TonHash::from(id.root_hash));
+
+        proof::check_block_header(&header_proof, &id)?;
+        let account = proof::check_account_state(&id, &id, &[], &proof, MASTERCHAIN, &account_id)?
+            .expect("The account is in the state");
+        assert_eq!(account.last_trans_lt, 1);
+        proof::check_state_proof(&state_proof, &account.account_hash)?;
+
+        let other = BlockIdExt {
+            root_hash: [1; 32],
+            ..id
+        };
+        assert!(proof::check_block_header(&header_proof, &other).is_err());
+        assert!(
+            proof::check_account_state(&other, &other, &[], &proof, MASTERCHAIN, &account_id)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    /// Answers `getMasterchainInfo`, `lookupBlock` and `runSmcMethod`
+    /// with the proofs of [`masterchain`].
+    async fn serve(listener: TcpListener, secret: [u8; 32], account_id: [u8; 32]) -> Result<()> {
+        let (stream, _) = listener.accept().await?;
+        let mut connection = AdnlConnection::accept(stream, secret).await?;
+        let Masterchain {
+            id: block,
+            header_proof,
+            proof,
+            state_proof,
+        } = masterchain(&account_id)?;
+        loop {
+            let packet = connection.receive().await?;
+            let mut reader = TlReader::new(&packet);
+            reader.expect(ADNL_MESSAGE_QUERY)?;
+            let query_id = reader.int256()?;
+            let query = reader.bytes()?;
+            let mut reader = TlReader::new(&query);
+            reader.expect(LITE_SERVER_QUERY)?;
+            let query = reader.bytes()?;
+            let mut reader = TlReader::new(&query);
+
+            let mut answer = TlWriter::default();
+            match reader.u32()? {
+                GET_MASTERCHAIN_INFO => {
+                    answer
+                        .u32(MASTERCHAIN_INFO)
+                        .block_id(&block)
+                        .int256(&[0; 32])
+                        .i32(MASTERCHAIN)
+                        .int256(&[0; 32])
+                        .int256(&[0; 32]);
+                }
+                LOOKUP_BLOCK => {
+                    let mode = reader.u32()?;
+                    assert_eq!(reader.i32()?, MASTERCHAIN);
+                    assert_eq!(reader.u64()?, MASTERCHAIN_SHARD);
+                    assert_eq!(reader.u32()?, block.seqno);
+                    answer
+                        .u32(BLOCK_HEADER)
+                        .block_id(&block)
+                        .u32(mode)
+                        .bytes(&header_proof)?;
+                }
+                RUN_SMC_METHOD => {
+                    let mode = reader.u32()?;
+                    assert_eq!(reader.block_id()?, block);
+                    assert_eq!(reader.i32()?, MASTERCHAIN);
+                    assert_eq!(reader.int256()?, account_id);
+                    assert_eq!(reader.u64()?, method_id("get_answer"));
+                    let params = vm_stack::deserialize(&reader.bytes()?)?;
+                    let result = vm_stack::serialize(&[
+                        params[0].clone(),
+                        StackItem::Num("-0x1".to_string()),
+                    ])?;
+                    answer
+                        .u32(RUN_METHOD_RESULT)
+                        .u32(mode)
+                        .block_id(&block)
+                        .block_id(&block)
+                        .bytes(&[])?
+                        .bytes(&proof)?
+                        .bytes(&state_proof)?
+                        .i32(0)
+                        .bytes(&result)?;
+                }
+                other => anyhow::bail!("Unexpected query {other:#x}"),
+            }
+            let mut message = TlWriter::default();
+            message
+                .u32(ADNL_MESSAGE_ANSWER)
+                .int256(&query_id)
+                .bytes(&answer.into_inner())?;
+            connection.send(&message.into_inner()).await?;
+        }
+    }
+
+    #[tokio::test]
+    async fn local_lite_server() -> Result<()> {
+        let secret = [7; 32];
+        let key = EdwardsPoint::mul_base_clamped(secret).compress().to_bytes();
+        let address = TonAddress::from_str(ACCOUNT)?;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!(
+            "{}{}?key={}",
+            LiteServer::SCHEME,
+            listener.local_addr()?,
+            BASE64_STANDARD.encode(key)
+        );
+        tokio::spawn(serve(listener, secret, account_id(&address)?));
+
+        let server = LiteServer::from_url(&url)?;
+        assert_eq!(server.last_seqno().await?, 7);
+        let root_hash = masterchain(&account_id(&address)?)?.id.root_hash;
+        assert_eq!(server.block_root_hash(7).await?, root_hash);
+        let expected = vec![
+            StackItem::Num("0x2a".to_string()),
+            StackItem::Num("-0x1".to_string()),
+        ];
+        for block in [
+            None,
+            Some(MasterchainBlock {
+                seqno: 7,
+                root_hash: Some(root_hash),
+            }),
+        ] {
+            let stack = server
+                .run_get_method(
+                    &address,
+                    "get_answer",
+                    vec![StackItem::Num("0x2a".to_string())],
+                    block,
+                )
+                .await?;
+            assert_eq!(stack, expected);
+        }
+
+        let forged = MasterchainBlock {
+            seqno: 7,
+            root_hash: Some([1; 32]),
+        };
+        assert!(server
+            .run_get_method(&address, "get_answer", vec![], Some(forged))
+            .await
+            .is_err());
+        Ok(())
+    }
+}
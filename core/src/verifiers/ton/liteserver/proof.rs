@@ -0,0 +1,324 @@
+//! Checks of the Merkle proofs returned by lite-servers, following `block.tlb`:
+//! <https://github.com/ton-blockchain/ton/blob/master/crypto/block/block.tlb>.
+//!
+//! A proof is a `MERKLE_PROOF` exotic cell, whose data is the hash of the proven cell,
+//! and whose child is that cell with the parts that aren't needed pruned.
+use super::tl::BlockIdExt;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use tonlib_core::cell::{ArcCell, BagOfCells, Cell, CellParser};
+use tonlib_core::TonHash;
+
+pub(super) const MASTERCHAIN: i32 = -1;
+pub(super) const MASTERCHAIN_SHARD: u64 = 0x8000_0000_0000_0000;
+
+const MERKLE_PROOF_TYPE: u8 = 3;
+const MERKLE_UPDATE_TYPE: u8 = 4;
+const BLOCK_TAG: u32 = 0x11ef_55aa;
+const BLOCK_INFO_TAG: u32 = 0x9bc7_a987;
+const SHARD_STATE_TAG: u32 = 0x9023_afe2;
+const MC_STATE_EXTRA_TAG: u16 = 0xcc26;
+
+/// `account_descr$_ account:^Account last_trans_hash:bits256 last_trans_lt:uint64`
+pub(super) struct ShardAccount {
+    pub(super) account_hash: TonHash,
+    pub(super) last_trans_hash: [u8; 32],
+    pub(super) last_trans_lt: u64,
+}
+
+/// Checks that the account state is of the shard block, which is in turn registered
+/// in the masterchain `block`, and returns the account description in that state,
+/// `None` if the state proves the account isn't there.
+pub(super) fn check_account_state(
+    block: &BlockIdExt,
+    shard_block: &BlockIdExt,
+    shard_proof: &[u8],
+    proof: &[u8],
+    workchain: i32,
+    account_id: &[u8; 32],
+) -> Result<Option<ShardAccount>> {
+    ensure!(
+        shard_block.workchain == workchain && shard_contains(shard_block.shard, account_id),
+        "Shard block {shard_block:?} doesn't contain the account"
+    );
+    if workchain == MASTERCHAIN {
+        ensure!(
+            shard_block == block,
+            "Masterchain account state is from another block: {shard_block:?}"
+        );
+    } else {
+        let mc_state = prove_state(shard_proof, &block.root_hash)?;
+        let (seqno, root_hash) = shard_block_hash(&mc_state, workchain, shard_block.shard)?;
+        ensure!(
+            seqno == shard_block.seqno && root_hash == TonHash::from(shard_block.root_hash),
+            "Shard block {shard_block:?} isn't registered in the masterchain block {}",
+            block.seqno
+        );
+    }
+
+    let state = prove_state(proof, &shard_block.root_hash)?;
+    shard_account(&state, account_id)
+}
+
+/// Checks that the state a get-method ran on is the `Account` with `account_hash`.
+pub(super) fn check_state_proof(state_proof: &[u8], account_hash: &TonHash) -> Result<()> {
+    let account = BagOfCells::parse(state_proof)?.single_root()?;
+    merkle_proof_root(&account, account_hash).context("Invalid account state proof")?;
+    Ok(())
+}
+
+/// Checks the header proof of a block, `seq_no` of
+/// `block_info#9bc7a987 version:uint32 not_master:(## 1) after_merge:(## 1) before_split:(## 1)
+/// after_split:(## 1) want_split:Bool want_merge:Bool key_block:Bool vert_seqno_incr:(## 1)
+/// flags:(## 8) seq_no:# ...` has to match.
+pub(super) fn check_block_header(header_proof: &[u8], id: &BlockIdExt) -> Result<()> {
+    let proof = BagOfCells::parse(header_proof)?.single_root()?;
+    let block = merkle_proof_root(&proof, &TonHash::from(id.root_hash))?;
+    let info = block_field(&block, 0)?;
+    let mut parser = info.parser();
+    ensure!(parser.load_u32(32)? == BLOCK_INFO_TAG, "Not a block info");
+    parser.skip_bits(32 + 8 + 8)?;
+    let seqno = parser.load_u32(32)?;
+    ensure!(
+        seqno == id.seqno,
+        "Header of block {seqno} instead of {}",
+        id.seqno
+    );
+    Ok(())
+}
+
+/// The child of a `MERKLE_PROOF` cell, if the proof is of a cell with `hash`.
+fn merkle_proof_root(proof: &ArcCell, hash: &TonHash) -> Result<ArcCell> {
+    let mut parser = proof.parser();
+    ensure!(
+        proof.is_exotic() && parser.load_u8(8)? == MERKLE_PROOF_TYPE,
+        "Not a Merkle proof"
+    );
+    let proven = load_hash(&mut parser)?;
+    ensure!(&proven == hash, "Merkle proof of another cell");
+    let root = proof.reference(0)?;
+    ensure!(
+        &root.get_hash(0) == hash,
+        "Merkle proof doesn't match its hash"
+    );
+    Ok(root.clone())
+}
+
+/// `block#11ef55aa global_id:int32 info:^BlockInfo value_flow:^ValueFlow
+/// state_update:^(MERKLE_UPDATE ShardState) extra:^BlockExtra`
+fn block_field(block: &Cell, index: usize) -> Result<&ArcCell> {
+    ensure!(block.parser().load_u32(32)? == BLOCK_TAG, "Not a block");
+    Ok(block.reference(index)?)
+}
+
+/// The state proven by a BoC of a block proof and a proof of the state after that block.
+fn prove_state(proof: &[u8], block_hash: &[u8; 32]) -> Result<ArcCell> {
+    let boc = BagOfCells::parse(proof)?;
+    let [block_proof, state_proof] = boc.roots.as_slice() else {
+        bail!(
+            "Expected block and state proofs, got {} roots",
+            boc.roots.len()
+        );
+    };
+    let block = merkle_proof_root(block_proof, &TonHash::from(*block_hash))?;
+    // `!merkle_update#04 old_hash:bits256 new_hash:bits256 old_depth:uint16 new_depth:uint16 old:^X new:^X`
+    let update = block_field(&block, 2)?;
+    let mut parser = update.parser();
+    ensure!(
+        update.is_exotic() && parser.load_u8(8)? == MERKLE_UPDATE_TYPE,
+        "Not a Merkle update"
+    );
+    parser.skip_bits(256)?;
+    let state_hash = load_hash(&mut parser)?;
+    merkle_proof_root(state_proof, &state_hash)
+}
+
+/// `shard_state#9023afe2 ... out_msg_queue_info:^OutMsgQueueInfo before_split:(## 1)
+/// accounts:^ShardAccounts ^[...] custom:(Maybe ^McStateExtra)`
+fn state_field(state: &Cell, index: usize) -> Result<&ArcCell> {
+    ensure!(
+        !state.is_exotic(),
+        "The proof doesn't cover the shard state"
+    );
+    ensure!(
+        state.parser().load_u32(32)? == SHARD_STATE_TAG,
+        "Not a shard state"
+    );
+    Ok(state.reference(index)?)
+}
+
+/// The `ShardAccount` of `account_id` in `ShardAccounts`, a
+/// `HashmapAugE 256 ShardAccount DepthBalanceInfo`. `None` if it isn't there.
+fn shard_account(state: &Cell, account_id: &[u8; 32]) -> Result<Option<ShardAccount>> {
+    let accounts = state_field(state, 1)?;
+    if !accounts.parser().load_bit()? {
+        return Ok(None);
+    }
+    let Some((leaf, offset)) = dict_lookup(accounts.reference(0)?, &bits(account_id))? else {
+        return Ok(None);
+    };
+    let mut parser = leaf.parser();
+    parser.skip_bits(offset)?;
+    // depth_balance$_ split_depth:(#<= 30) balance:CurrencyCollection
+    parser.load_u8(5)?;
+    parser.load_coins()?;
+    if parser.load_bit()? {
+        parser.next_reference()?;
+    }
+    let account = parser.next_reference()?;
+    let last_trans_hash = load_bits256(&mut parser)?;
+    let last_trans_lt = parser.load_u64(64)?;
+    Ok(Some(ShardAccount {
+        account_hash: account.get_hash(0),
+        last_trans_hash,
+        last_trans_lt,
+    }))
+}
+
+/// Seqno and root hash of the last block of `shard` in the masterchain state:
+/// `masterchain_state_extra#cc26 shard_hashes:(HashmapE 32 ^(BinTree ShardDescr)) ...`
+fn shard_block_hash(mc_state: &Cell, workchain: i32, shard: u64) -> Result<(u32, TonHash)> {
+    let extra = state_field(mc_state, 3).context("Not a masterchain state")?;
+    let mut parser = extra.parser();
+    ensure!(
+        parser.load_u16(16)? == MC_STATE_EXTRA_TAG,
+        "Not a masterchain state extra"
+    );
+    ensure!(parser.load_bit()?, "No shards in the masterchain state");
+    let (leaf, _) = dict_lookup(extra.reference(0)?, &bits(&workchain.to_be_bytes()))?
+        .with_context(|| format!("No shards of workchain {workchain}"))?;
+
+    // bt_leaf$0 leaf:X = BinTree X; bt_fork$1 left:^(BinTree X) right:^(BinTree X) = BinTree X;
+    let mut node = leaf.reference(0)?.clone();
+    for bit in shard_prefix(shard) {
+        ensure!(
+            !node.is_exotic(),
+            "The proof doesn't cover shard {shard:#018x}"
+        );
+        ensure!(
+            node.parser().load_bit()?,
+            "Shard {shard:#018x} is merged in the masterchain state"
+        );
+        let next = node.reference(usize::from(bit))?.clone();
+        node = next;
+    }
+    ensure!(
+        !node.is_exotic(),
+        "The proof doesn't cover shard {shard:#018x}"
+    );
+    let mut parser = node.parser();
+    ensure!(
+        !parser.load_bit()?,
+        "Shard {shard:#018x} is split in the masterchain state"
+    );
+    // shard_descr#b seq_no:uint32 reg_mc_seqno:uint32 start_lt:uint64 end_lt:uint64
+    // root_hash:bits256 file_hash:bits256 ...
+    let tag = parser.load_u8(4)?;
+    ensure!(tag == 0xa || tag == 0xb, "Not a shard description");
+    let seqno = parser.load_u32(32)?;
+    parser.skip_bits(32 + 64 + 64)?;
+    let root_hash = load_hash(&mut parser)?;
+    Ok((seqno, root_hash))
+}
+
+/// Descends a `Hashmap` or `HashmapAug` to the leaf of `key`, returns the leaf and
+/// the offset of its value. `None` if there's no such key.
+///
+/// `hm_edge#_ label:(HmLabel ~l n) {n = (~m) + l} node:(HashmapNode m X)`, forks keep
+/// the `0` and `1` subtrees in their first two references.
+fn dict_lookup(root: &ArcCell, key: &[bool]) -> Result<Option<(ArcCell, usize)>> {
+    let mut cell = root.clone();
+    let mut key = key;
+    loop {
+        ensure!(
+            !cell.is_exotic(),
+            "The proof doesn't cover the dictionary key"
+        );
+        let (label, offset) = {
+            let mut parser = cell.parser();
+            let label = read_label(&mut parser, key.len())?;
+            (label, cell.bit_len() - parser.remaining_bits())
+        };
+        let Some(rest) = key.strip_prefix(label.as_slice()) else {
+            return Ok(None);
+        };
+        let Some((&bit, rest)) = rest.split_first() else {
+            return Ok(Some((cell, offset)));
+        };
+        let next = cell.reference(usize::from(bit))?.clone();
+        cell = next;
+        key = rest;
+    }
+}
+
+/// `hml_short$0 len:(Unary ~n) s:(n * Bit)`, `hml_long$10 n:(#<= m) s:(n * Bit)`,
+/// `hml_same$11 v:Bit n:(#<= m)`.
+fn read_label(parser: &mut CellParser, max_len: usize) -> Result<Vec<bool>> {
+    let len_bits = usize::try_from(usize::BITS - max_len.leading_zeros())?;
+    let label = if !parser.load_bit()? {
+        let mut len = 0;
+        while parser.load_bit()? {
+            len += 1;
+        }
+        (0..len)
+            .map(|_| parser.load_bit())
+            .collect::<Result<_, _>>()?
+    } else if !parser.load_bit()? {
+        let len = usize::try_from(parser.load_u32(len_bits)?)?;
+        (0..len)
+            .map(|_| parser.load_bit())
+            .collect::<Result<_, _>>()?
+    } else {
+        let bit = parser.load_bit()?;
+        let len = usize::try_from(parser.load_u32(len_bits)?)?;
+        vec![bit; len]
+    };
+    ensure!(label.len() <= max_len, "Dictionary label is too long");
+    Ok(label)
+}
+
+fn load_hash(parser: &mut CellParser) -> Result<TonHash> {
+    Ok(TonHash::from(load_bits256(parser)?))
+}
+
+fn load_bits256(parser: &mut CellParser) -> Result<[u8; 32]> {
+    parser
+        .load_bits(256)?
+        .try_into()
+        .map_err(|_| anyhow!("Expected 256 bits"))
+}
+
+fn bits(bytes: &[u8]) -> Vec<bool> {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+        .collect()
+}
+
+/// Bits of the shard prefix, the shard id is the prefix followed by `1` and zeros.
+fn shard_prefix(shard: u64) -> Vec<bool> {
+    let len = 63 - shard.trailing_zeros();
+    (0..len).map(|i| (shard >> (63 - i)) & 1 == 1).collect()
+}
+
+fn shard_contains(shard: u64, account_id: &[u8; 32]) -> bool {
+    let lowest_bit = shard & shard.wrapping_neg();
+    let mask = !(lowest_bit | lowest_bit.wrapping_sub(1));
+    let prefix = u64::from_be_bytes(account_id[..8].try_into().expect("8 bytes"));
+    (prefix ^ shard) & mask == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shard_contains, shard_prefix};
+
+    #[test]
+    fn shards() {
+        let mut account = [0; 32];
+        account[0] = 0b1010_0000;
+        assert!(shard_contains(0x8000_0000_0000_0000, &account));
+        assert!(shard_contains(0xa000_0000_0000_0000, &account));
+        assert!(!shard_contains(0x6000_0000_0000_0000, &account));
+        assert_eq!(shard_prefix(0x8000_0000_0000_0000), Vec::<bool>::new());
+        assert_eq!(shard_prefix(0xa000_0000_0000_0000), vec![true, false]);
+    }
+}
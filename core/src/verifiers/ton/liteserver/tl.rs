@@ -0,0 +1,185 @@
+//! TL serialization of the lite-server schema:
+//! <https://github.com/ton-blockchain/ton/blob/master/tl/generate/scheme/lite_api.tl>.
+//! Constructor ids are CRC32 of the schema lines, integers are little-endian.
+use anyhow::{bail, ensure, Context, Result};
+
+pub(super) const ADNL_MESSAGE_QUERY: u32 = 0xb48b_f97a;
+pub(super) const ADNL_MESSAGE_ANSWER: u32 = 0x0fac_8416;
+pub(super) const PUB_ED25519: u32 = 0x4813_b4c6;
+pub(super) const LITE_SERVER_QUERY: u32 = 0x798c_06df;
+pub(super) const LITE_SERVER_ERROR: u32 = 0xbba9_e148;
+pub(super) const GET_MASTERCHAIN_INFO: u32 = 0x89b5_e62e;
+pub(super) const MASTERCHAIN_INFO: u32 = 0x8583_2881;
+pub(super) const LOOKUP_BLOCK: u32 = 0xfac8_f71e;
+pub(super) const BLOCK_HEADER: u32 = 0x752d_8219;
+pub(super) const RUN_SMC_METHOD: u32 = 0x5cc6_5dd2;
+pub(super) const RUN_METHOD_RESULT: u32 = 0xa39a_616b;
+pub(super) const GET_ACCOUNT_STATE: u32 = 0x6b89_0e25;
+pub(super) const ACCOUNT_STATE: u32 = 0x7079_c751;
+pub(super) const GET_TRANSACTIONS: u32 = 0x1c40_e7a1;
+pub(super) const TRANSACTION_LIST: u32 = 0x6f26_c60b;
+
+#[derive(Default)]
+pub(super) struct TlWriter(Vec<u8>);
+
+impl TlWriter {
+    pub(super) fn u32(&mut self, value: u32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(super) fn i32(&mut self, value: i32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(super) fn u64(&mut self, value: u64) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(super) fn int256(&mut self, value: &[u8; 32]) -> &mut Self {
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    /// Short strings are prefixed with a length byte, long ones with `0xfe` and 3 length bytes,
+    /// both are padded to 4 bytes.
+    pub(super) fn bytes(&mut self, value: &[u8]) -> Result<&mut Self> {
+        let header = if value.len() < 254 {
+            self.0.push(u8::try_from(value.len())?);
+            1
+        } else {
+            ensure!(
+                value.len() < 1 << 24,
+                "{} bytes don't fit in TL bytes",
+                value.len()
+            );
+            self.0.push(0xfe);
+            self.0
+                .extend_from_slice(&u32::try_from(value.len())?.to_le_bytes()[..3]);
+            4
+        };
+        self.0.extend_from_slice(value);
+        let padding = (4 - (header + value.len()) % 4) % 4;
+        self.0.extend(std::iter::repeat_n(0, padding));
+        Ok(self)
+    }
+
+    pub(super) fn block_id(&mut self, id: &BlockIdExt) -> &mut Self {
+        self.i32(id.workchain)
+            .u64(id.shard)
+            .u32(id.seqno)
+            .int256(&id.root_hash)
+            .int256(&id.file_hash)
+    }
+
+    pub(super) fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+pub(super) struct TlReader<'a>(&'a [u8]);
+
+impl<'a> TlReader<'a> {
+    pub(super) const fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        ensure!(self.0.len() >= N, "Unexpected end of TL data");
+        let (head, tail) = self.0.split_at(N);
+        self.0 = tail;
+        Ok(head.try_into()?)
+    }
+
+    pub(super) fn u32(&mut self) -> Result<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    pub(super) fn i32(&mut self) -> Result<i32> {
+        self.take().map(i32::from_le_bytes)
+    }
+
+    pub(super) fn u64(&mut self) -> Result<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    pub(super) fn int256(&mut self) -> Result<[u8; 32]> {
+        self.take()
+    }
+
+    pub(super) fn bytes(&mut self) -> Result<Vec<u8>> {
+        let [first] = self.take()?;
+        let (header, len) = if first < 254 {
+            (1, usize::from(first))
+        } else {
+            ensure!(first == 0xfe, "Invalid TL bytes prefix {first:#x}");
+            let [a, b, c] = self.take()?;
+            (
+                4,
+                usize::from(a) | usize::from(b) << 8 | usize::from(c) << 16,
+            )
+        };
+        ensure!(self.0.len() >= len, "Unexpected end of TL bytes");
+        let (value, tail) = self.0.split_at(len);
+        self.0 = tail;
+        let padding = (4 - (header + len) % 4) % 4;
+        self.0 = self.0.get(padding..).context("Missing TL bytes padding")?;
+        Ok(value.to_vec())
+    }
+
+    pub(super) fn block_id(&mut self) -> Result<BlockIdExt> {
+        Ok(BlockIdExt {
+            workchain: self.i32()?,
+            shard: self.u64()?,
+            seqno: self.u32()?,
+            root_hash: self.int256()?,
+            file_hash: self.int256()?,
+        })
+    }
+
+    /// Reads the constructor of an answer, `liteServer.error` is turned into an error.
+    pub(super) fn expect(&mut self, constructor: u32) -> Result<()> {
+        match self.u32()? {
+            id if id == constructor => Ok(()),
+            LITE_SERVER_ERROR => {
+                let code = self.i32()?;
+                let message = String::from_utf8_lossy(&self.bytes()?).into_owned();
+                bail!("Lite-server error {code}: {message}")
+            }
+            id => bail!("Expected TL constructor {constructor:#010x}, got {id:#010x}"),
+        }
+    }
+}
+
+/// `tonNode.blockIdExt workchain:int shard:long seqno:int root_hash:int256 file_hash:int256`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct BlockIdExt {
+    pub(super) workchain: i32,
+    pub(super) shard: u64,
+    pub(super) seqno: u32,
+    pub(super) root_hash: [u8; 32],
+    pub(super) file_hash: [u8; 32],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TlReader, TlWriter};
+
+    #[test]
+    fn bytes_padding() -> anyhow::Result<()> {
+        for len in [0, 3, 4, 253, 254, 1000] {
+            let value = vec![7; len];
+            let mut writer = TlWriter::default();
+            writer.bytes(&value)?.u32(42);
+            let data = writer.into_inner();
+            assert_eq!(data.len() % 4, 0);
+
+            let mut reader = TlReader::new(&data);
+            assert_eq!(reader.bytes()?, value);
+            assert_eq!(reader.u32()?, 42);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! `VmStack` cells of get-method arguments and results:
+//! `vm_stack#_ depth:(## 24) stack:(VmStackList depth)`,
+//! `vm_stk_cons#_ {n:#} rest:^(VmStackList n) tos:VmStackValue = VmStackList (n + 1)`.
+//! Stack items are listed from the bottom, like in toncenter.
+use crate::verifiers::ton::backend::format_num;
+use alloy_primitives::U256;
+use anyhow::{bail, ensure, Result};
+use hot_validation_primitives::bridge::expectation::ReturnValue;
+use hot_validation_primitives::bridge::ton::{SerializableCell, StackItem};
+use tonlib_core::cell::{ArcCell, BagOfCells, Cell, CellBuilder, CellParser};
+
+const NULL: u8 = 0x00;
+const TINY_INT: u8 = 0x01;
+const INT: u8 = 0x02;
+const CELL: u8 = 0x03;
+const SLICE: u8 = 0x04;
+const TUPLE: u8 = 0x07;
+
+pub(super) fn serialize(stack: &[StackItem]) -> Result<Vec<u8>> {
+    let mut root = CellBuilder::new();
+    root.store_u32(24, u32::try_from(stack.len())?)?;
+    if let Some((top, rest)) = stack.split_last() {
+        let mut list = CellBuilder::new().build()?;
+        for item in rest {
+            let mut builder = CellBuilder::new();
+            builder.store_reference(&ArcCell::new(list))?;
+            write_value(&mut builder, item)?;
+            list = builder.build()?;
+        }
+        root.store_reference(&ArcCell::new(list))?;
+        write_value(&mut root, top)?;
+    }
+    Ok(BagOfCells::from_root(root.build()?).serialize(false)?)
+}
+
+pub(super) fn deserialize(boc: &[u8]) -> Result<Vec<StackItem>> {
+    let root = BagOfCells::parse(boc)?.single_root()?;
+    let (depth, mut rest, mut top) = {
+        let mut parser = root.parser();
+        let depth = parser.load_u32(24)?;
+        if depth == 0 {
+            return Ok(vec![]);
+        }
+        let rest = parser.next_reference()?;
+        (depth, rest, read_value(&mut parser)?)
+    };
+    let mut stack = Vec::with_capacity(usize::try_from(depth)?);
+    for _ in 1..depth {
+        let (next, item) = {
+            let mut parser = rest.parser();
+            let next = parser.next_reference()?;
+            (next, read_value(&mut parser)?)
+        };
+        stack.push(top);
+        top = item;
+        rest = next;
+    }
+    stack.push(top);
+    stack.reverse();
+    Ok(stack)
+}
+
+/// Integers are always written as `vm_stk_int#0201_ value:int257`,
+/// in two's complement: the sign bit followed by 256 bits.
+fn write_value(builder: &mut CellBuilder, item: &StackItem) -> Result<()> {
+    match item {
+        StackItem::Num(num) => {
+            let (negative, magnitude) = match num.parse::<ReturnValue>()? {
+                ReturnValue::Uint(magnitude) => (false, magnitude),
+                ReturnValue::Negative(magnitude) => (true, magnitude),
+                ReturnValue::Bool(_) => bail!("Expected a number, got {num}"),
+            };
+            let raw = if negative {
+                magnitude.wrapping_neg()
+            } else {
+                magnitude
+            };
+            builder
+                .store_u8(8, INT)?
+                .store_u8(7, 0)?
+                .store_bit(negative)?
+                .store_slice(&raw.to_be_bytes::<32>())?;
+        }
+        StackItem::Cell(cell) => {
+            builder.store_u8(8, CELL)?.store_reference(cell)?;
+        }
+        // vm_stk_slice#04 cell:^Cell st_bits:(## 10) end_bits:(## 10) st_ref:(#<= 4) end_ref:(#<= 4)
+        StackItem::Slice(cell) => {
+            builder
+                .store_u8(8, SLICE)?
+                .store_reference(cell)?
+                .store_u32(10, 0)?
+                .store_u32(10, u32::try_from(cell.bit_len())?)?
+                .store_u8(3, 0)?
+                .store_u8(3, u8::try_from(cell.references().len())?)?;
+        }
+        StackItem::Tuple(_) | StackItem::List(_) => {
+            bail!("Tuples and lists can't be passed to get-methods")
+        }
+    }
+    Ok(())
+}
+
+fn read_value(parser: &mut CellParser) -> Result<StackItem> {
+    let item = match parser.load_u8(8)? {
+        TINY_INT => {
+            let value = parser.load_i64(64)?;
+            StackItem::Num(format_num(value < 0, U256::from(value.unsigned_abs())))
+        }
+        INT => {
+            ensure!(parser.load_u8(7)? == 0, "NaN on the stack");
+            let negative = parser.load_bit()?;
+            let raw = U256::from_be_slice(&parser.load_bits(256)?);
+            let magnitude = if negative {
+                ensure!(raw != U256::ZERO, "-2^256 on the stack");
+                raw.wrapping_neg()
+            } else {
+                raw
+            };
+            StackItem::Num(format_num(negative, magnitude))
+        }
+        CELL => StackItem::Cell(SerializableCell::from(parser.next_reference()?)),
+        SLICE => StackItem::Slice(SerializableCell::from(ArcCell::new(read_slice(parser)?))),
+        // vm_stk_tuple#07 len:(## 16) data:(VmTuple len)
+        TUPLE => {
+            let len = parser.load_u32(16)?;
+            StackItem::Tuple(read_tuple(parser, len)?)
+        }
+        NULL => bail!("Null on the stack isn't supported"),
+        tag => bail!("Unsupported stack value {tag:#04x}"),
+    };
+    Ok(item)
+}
+
+/// The part of the cell between `st_bits`/`st_ref` and `end_bits`/`end_ref`.
+fn read_slice(parser: &mut CellParser) -> Result<Cell> {
+    let cell = parser.next_reference()?;
+    let start_bits = usize::try_from(parser.load_u32(10)?)?;
+    let end_bits = usize::try_from(parser.load_u32(10)?)?;
+    let start_ref = usize::from(parser.load_u8(3)?);
+    let end_ref = usize::from(parser.load_u8(3)?);
+    ensure!(
+        start_bits <= end_bits && end_bits <= cell.bit_len(),
+        "Invalid slice bits {start_bits}..{end_bits}"
+    );
+    let refs = cell
+        .references()
+        .get(start_ref..end_ref)
+        .ok_or_else(|| anyhow::anyhow!("Invalid slice refs {start_ref}..{end_ref}"))?
+        .to_vec();
+    let mut slice = cell.parser();
+    slice.skip_bits(start_bits)?;
+    let data = slice.load_bits(end_bits - start_bits)?;
+    Ok(Cell::new(data, end_bits - start_bits, refs, false)?)
+}
+
+/// `vm_tuple_tcons$_ head:(VmTupleRef n) tail:^VmStackValue = VmTuple (n + 1)`,
+/// `vm_tupref_single$_ entry:^VmStackValue = VmTupleRef 1`,
+/// `vm_tupref_any$_ ref:^(VmTuple (n + 2)) = VmTupleRef (n + 2)`.
+fn read_tuple(parser: &mut CellParser, len: u32) -> Result<Vec<StackItem>> {
+    let Some(head_len) = len.checked_sub(1) else {
+        return Ok(vec![]);
+    };
+    let mut items = match head_len {
+        0 => vec![],
+        1 => vec![read_entry(&parser.next_reference()?)?],
+        _ => {
+            let head = parser.next_reference()?;
+            let mut head = head.parser();
+            read_tuple(&mut head, head_len)?
+        }
+    };
+    items.push(read_entry(&parser.next_reference()?)?);
+    Ok(items)
+}
+
+fn read_entry(cell: &Cell) -> Result<StackItem> {
+    read_value(&mut cell.parser())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deserialize, serialize};
+    use hot_validation_primitives::bridge::ton::StackItem;
+
+    #[test]
+    fn round_trip() -> anyhow::Result<()> {
+        let stack = vec![
+            StackItem::Num("0x2a".to_string()),
+            StackItem::Num("-1".to_string()),
+            StackItem::from_address("EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ")?,
+        ];
+        let expected = vec![
+            StackItem::Num("0x2a".to_string()),
+            StackItem::Num("-0x1".to_string()),
+            stack[2].clone(),
+        ];
+        assert_eq!(deserialize(&serialize(&stack)?)?, expected);
+        assert_eq!(deserialize(&serialize(&[])?)?, vec![]);
+        Ok(())
+    }
+}
@@ -1,29 +1,85 @@
+mod backend;
+mod jetton;
+mod liteserver;
+mod toncenter_v3;
 mod types;
 
-use crate::http_client::post_json_receive_json;
-use crate::threshold_verifier::{Identifiable, ThresholdVerifier};
-use crate::verifiers::ton::types::{RpcRequest, RpcResponse};
+use crate::head_tracker::{HeadSource, HeadTracker, Heads};
+use crate::threshold_verifier::{Identifiable, ThresholdVerifier, VerificationError};
+use crate::verifiers::ton::backend::{
+    single_item, AccountNotDeployed, MasterchainBlock, TonBackend,
+};
+use crate::verifiers::ton::jetton::TransferNotification;
 use crate::verifiers::Verifier;
-use anyhow::ensure;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+use anyhow::{ensure, Context};
 use async_trait::async_trait;
-use hot_validation_primitives::bridge::ton::{Action, StackItem, TonInputData};
-use hot_validation_primitives::bridge::InputData;
-use hot_validation_primitives::{ChainId, ChainValidationConfig, ExtendedChainId};
-use primitive_types::U128;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use futures_util::future::join_all;
+use hot_validation_primitives::bridge::expectation::{Expectation, ReturnValue};
+use hot_validation_primitives::bridge::ton::{
+    Action, JettonTransfer, StackItem, TonInputData, TransactionRef,
+};
+use hot_validation_primitives::bridge::transaction::{TransactionId, TransactionInputData};
+use hot_validation_primitives::bridge::{CompletedWithdrawal, HotVerifyAuthCall, InputData};
+use hot_validation_primitives::{
+    ChainFamily, ChainId, ChainValidationConfig, ExtendedChainId, Finality,
+};
 use std::str::FromStr;
 use std::sync::Arc;
+use tonlib_core::cell::Cell;
 use tonlib_core::TonAddress;
+use tonlib_core::TonHash;
 
+/// Treasury get-method returning the child contract of a user.
+const USER_JETTON_ADDRESS_METHOD: &str = "get_user_jetton_address";
+/// Child get-method returning the last nonce withdrawn to the user.
+const LAST_WITHDRAWN_NONCE_METHOD: &str = "get_last_withdrawn_nonce";
+/// Child get-method returning `-1` if the deposit proof is valid.
+const VERIFY_WITHDRAW_METHOD: &str = "verify_withdraw";
+/// Child get-methods whose result the bridge verdict is derived from.
+pub(crate) const VERDICT_METHODS: [&str; 2] = [VERIFY_WITHDRAW_METHOD, LAST_WITHDRAWN_NONCE_METHOD];
+/// Jetton master get-method returning the jetton wallet of an owner.
+const JETTON_WALLET_ADDRESS_METHOD: &str = "get_wallet_address";
+/// Jetton wallet get-method returning `(balance, owner, master, wallet_code)`.
+const JETTON_WALLET_DATA_METHOD: &str = "get_wallet_data";
+/// Transactions of the treasury searched for a jetton transfer, lite-servers return
+/// at most 16 at once.
+const JETTON_TRANSFER_SCAN_LIMIT: u32 = 16;
+
+/// TON masterchain blocks are final once produced, so only `min_confirmations`
+/// of the [`Finality`] matters: get-methods run at `last seqno - min_confirmations`.
+/// The API is selected by the `server` URL, see [`backend`].
 pub struct TonVerifier {
-    client: Arc<reqwest::Client>,
     server: String,
+    backend: Box<dyn TonBackend>,
+    finality: Finality,
+    head_tracker: Option<Arc<HeadTracker>>,
 }
 
 impl Identifiable for TonVerifier {
     fn id(&self) -> String {
         self.server.clone()
     }
+
+    fn is_lagging(&self) -> bool {
+        self.head_tracker
+            .as_ref()
+            .is_some_and(|tracker| tracker.is_lagging(&self.server))
+    }
+}
+
+#[async_trait]
+impl HeadSource for TonVerifier {
+    async fn fetch_heads(&self) -> Result<Heads> {
+        let seqno = self.fetch_last_seqno().await?;
+        Ok(Heads {
+            latest: seqno,
+            safe: Some(seqno),
+            finalized: Some(seqno),
+        })
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -34,11 +90,65 @@ pub enum TonError {
     ChildCall(anyhow::Error),
     #[error("TON Verification failed during Verification stage")]
     VerificationStage(anyhow::Error),
+    #[error("TON Verification failed during Jetton transfer lookup")]
+    JettonTransfer(anyhow::Error),
 }
 
 impl TonVerifier {
-    fn new(client: Arc<reqwest::Client>, server: String) -> Self {
-        Self { client, server }
+    fn new(client: Arc<reqwest::Client>, server: String, finality: Finality) -> Result<Self> {
+        Ok(Self {
+            backend: backend::connect(client, &server)?,
+            server,
+            finality,
+            head_tracker: None,
+        })
+    }
+
+    /// Reads the last seqno from `head_tracker` instead of fetching it, while it's fresh.
+    #[must_use]
+    fn with_head_tracker(mut self, head_tracker: Arc<HeadTracker>) -> Self {
+        self.head_tracker = Some(head_tracker);
+        self
+    }
+
+    async fn fetch_last_seqno(&self) -> Result<u64> {
+        self.backend.last_seqno().await
+    }
+
+    /// Masterchain seqno to run get-methods at, `None` for the last one.
+    async fn seqno(&self, at: Option<u64>) -> Result<Option<u64>> {
+        if at.is_some() {
+            return Ok(at);
+        }
+        if self.finality.min_confirmations() == 0 {
+            return Ok(None);
+        }
+        self.confirmed_seqno().await.map(Some)
+    }
+
+    /// Block to run get-methods at outside threshold calls, its root hash isn't agreed on.
+    async fn block(&self, at: Option<u64>) -> Result<Option<MasterchainBlock>> {
+        let seqno = self.seqno(at).await?;
+        Ok(seqno.map(|seqno| MasterchainBlock {
+            seqno,
+            root_hash: None,
+        }))
+    }
+
+    /// The last seqno less `min_confirmations`, the tracked one while it's fresh.
+    async fn confirmed_seqno(&self) -> Result<u64> {
+        let tracked = self
+            .head_tracker
+            .as_ref()
+            .and_then(|tracker| tracker.heads(&self.server))
+            .map(|heads| heads.latest);
+        let last_seqno = match tracked {
+            Some(seqno) => seqno,
+            None => self.fetch_last_seqno().await?,
+        };
+        last_seqno
+            .checked_sub(self.finality.min_confirmations())
+            .context("Not enough blocks for the required confirmations")
     }
 
     async fn treasury_call(
@@ -46,43 +156,284 @@ impl TonVerifier {
         treasury_address: TonAddress,
         method_name: String,
         input: TonInputData,
+        block: Option<MasterchainBlock>,
     ) -> Result<TonAddress> {
-        let request = RpcRequest::build(&treasury_address, &method_name, input.treasury_call_args);
-        let item: RpcResponse =
-            post_json_receive_json(&self.client, &self.server, &request, ChainId::TON_V2).await?;
-        let address = item.unpack()?.as_cell()?.parser().load_address()?;
+        let stack = self
+            .backend
+            .run_get_method(
+                &treasury_address,
+                &method_name,
+                input.treasury_call_args,
+                block,
+            )
+            .await?;
+        let address = single_item(stack)?.as_cell()?.parser().load_address()?;
         Ok(address)
     }
 
-    async fn child_call(&self, child_address: TonAddress, input: TonInputData) -> Result<String> {
-        let request = RpcRequest::build(
-            &child_address,
-            &input.child_call_method,
-            input.child_call_args,
-        );
-        let item: RpcResponse =
-            post_json_receive_json(&self.client, &self.server, &request, ChainId::TON_V2).await?;
-        let item = item.unpack()?.as_num()?;
+    async fn child_call(
+        &self,
+        child_address: TonAddress,
+        input: TonInputData,
+        block: Option<MasterchainBlock>,
+    ) -> Result<Vec<StackItem>> {
+        self.backend
+            .run_get_method(
+                &child_address,
+                &input.child_call_method,
+                input.child_call_args,
+                block,
+            )
+            .await
+    }
+
+    /// Runs both steps, returns the number the child contract returned.
+    async fn run(
+        &self,
+        auth_contract_id: &str,
+        method_name: String,
+        input: TonInputData,
+        block: Option<MasterchainBlock>,
+    ) -> Result<String> {
+        let stack = self
+            .run_stack(auth_contract_id, method_name, input, block)
+            .await?;
+        Self::single_num(stack).map_err(|err| TonError::ChildCall(err).into())
+    }
+
+    /// Runs both steps, returns the whole stack the child contract returned.
+    async fn run_stack(
+        &self,
+        auth_contract_id: &str,
+        method_name: String,
+        input: TonInputData,
+        block: Option<MasterchainBlock>,
+    ) -> Result<Vec<StackItem>> {
+        let treasury_address = TonAddress::from_base64_url(auth_contract_id)?;
+        // Both calls have to see the same state.
+        let child_address = self
+            .treasury_call(treasury_address, method_name, input.clone(), block)
+            .await
+            .map_err(TonError::TreasuryCall)?;
+        let stack = self
+            .child_call(child_address, input, block)
+            .await
+            .map_err(TonError::ChildCall)?;
+        Ok(stack)
+    }
+
+    fn single_num(stack: Vec<StackItem>) -> Result<String> {
+        single_item(stack)?.as_num()
+    }
+
+    /// Selects a stack item by a dot-separated path of indexes,
+    /// the first one into the stack, the next ones into tuples and lists.
+    fn select_item<'a>(stack: &'a [StackItem], path: &str) -> Result<&'a StackItem> {
+        let mut segments = path.split('.');
+        let first = segments.next().unwrap_or_default();
+        let index: usize = first
+            .parse()
+            .with_context(|| format!("Invalid stack index {first:?} in {path}"))?;
+        let mut item = stack
+            .get(index)
+            .with_context(|| format!("No item {index} in stack {stack:?}"))?;
+        for segment in segments {
+            let index: usize = segment
+                .parse()
+                .with_context(|| format!("Invalid index {segment:?} in {path}"))?;
+            let (StackItem::Tuple(elements) | StackItem::List(elements)) = item else {
+                bail!("Can't select {segment} of {item:?}");
+            };
+            item = elements
+                .get(index)
+                .with_context(|| format!("No element {index} in {item:?}"))?;
+        }
         Ok(item)
     }
 
+    /// Jetton wallet of `owner`.
+    async fn jetton_wallet(
+        &self,
+        master_address: &TonAddress,
+        owner: &str,
+        block: Option<MasterchainBlock>,
+    ) -> Result<TonAddress> {
+        let stack = self
+            .backend
+            .run_get_method(
+                master_address,
+                JETTON_WALLET_ADDRESS_METHOD,
+                vec![StackItem::from_address(owner)?],
+                block,
+            )
+            .await?;
+        let wallet_address = match single_item(stack)? {
+            StackItem::Cell(cell) | StackItem::Slice(cell) => cell.parser().load_address()?,
+            other => anyhow::bail!("Expected jetton wallet address, got {other:?}"),
+        };
+        Ok(wallet_address)
+    }
+
+    /// Balance of the jetton wallet of `owner`, fails if the wallet isn't deployed yet.
+    async fn jetton_balance(
+        &self,
+        master_address: TonAddress,
+        owner: &str,
+        block: Option<MasterchainBlock>,
+    ) -> Result<String> {
+        let wallet_address = self.jetton_wallet(&master_address, owner, block).await?;
+        let stack = self
+            .backend
+            .run_get_method(&wallet_address, JETTON_WALLET_DATA_METHOD, vec![], block)
+            .await?;
+        let balance = stack
+            .first()
+            .context("Empty stack returned by get_wallet_data")?
+            .as_num()?;
+        Ok(balance)
+    }
+
+    /// Transaction of `address` with `lt` and base64 `hash`, `None` if there's no such one.
+    async fn lookup_transaction(
+        &self,
+        address: &TonAddress,
+        lt: u64,
+        hash: &str,
+    ) -> Result<Option<Cell>> {
+        let hash: [u8; 32] = BASE64_STANDARD
+            .decode(hash)?
+            .try_into()
+            .map_err(|_| anyhow!("Transaction hash must be 32 bytes: {hash}"))?;
+        let Some(transaction) = self.backend.transaction(address, lt, &hash).await? else {
+            return Ok(None);
+        };
+        ensure!(
+            transaction.cell_hash() == TonHash::from(hash),
+            "RPC returned another transaction: lt={lt}, hash={}",
+            transaction.cell_hash().to_hex()
+        );
+        Ok(Some(transaction))
+    }
+
+    /// Finds the notification of the jetton transfer with the deposit nonce among
+    /// the treasury transactions and checks it against the deposit data.
+    /// The jetton wallet is resolved at `block`, transactions are final once found.
+    async fn verify_jetton_transfer(
+        &self,
+        auth_contract_id: &str,
+        transfer: JettonTransfer,
+        block: Option<MasterchainBlock>,
+    ) -> Result<()> {
+        let treasury = TonAddress::from_base64_url(auth_contract_id)?;
+        let deposit = transfer.deposit_data;
+        let master_address = jetton::master_address(&deposit)?;
+        let wallet = self
+            .jetton_wallet(&master_address, auth_contract_id, block)
+            .await?;
+        let transactions = match transfer.transaction {
+            Some(TransactionRef { lt, hash }) => {
+                let transaction = self
+                    .lookup_transaction(&treasury, lt, &hash)
+                    .await?
+                    .with_context(|| format!("No treasury transaction lt={lt}, hash={hash}"))?;
+                vec![transaction]
+            }
+            None => {
+                self.backend
+                    .last_transactions(&treasury, JETTON_TRANSFER_SCAN_LIMIT)
+                    .await?
+            }
+        };
+        for transaction in &transactions {
+            let Some(notification) =
+                TransferNotification::from_transaction(transaction, &treasury, &wallet)?
+            else {
+                continue;
+            };
+            if notification.nonce != deposit.nonce {
+                continue;
+            }
+            ensure!(
+                types::is_successful(transaction)?,
+                "Treasury failed to process the transfer with nonce {}",
+                deposit.nonce
+            );
+            return notification.check(&deposit);
+        }
+        bail!(
+            "No jetton transfer with nonce {} among {} treasury transactions",
+            deposit.nonce,
+            transactions.len()
+        )
+    }
+
+    /// Fails if the child contract of the receiver isn't deployed yet.
+    async fn is_nonce_used_at(
+        &self,
+        contract_id: &str,
+        withdrawal: CompletedWithdrawal,
+        block: Option<MasterchainBlock>,
+    ) -> Result<bool> {
+        let receiver_address = withdrawal
+            .receiver_address
+            .context("receiver address missing")?;
+        let input = TonInputData {
+            treasury_call_args: vec![StackItem::from_address(&receiver_address)?],
+            child_call_method: LAST_WITHDRAWN_NONCE_METHOD.to_string(),
+            child_call_args: vec![],
+            action: Action::CheckCompletedWithdrawal {
+                nonce: withdrawal.nonce,
+            },
+        };
+        let treasury_address = TonAddress::from_base64_url(contract_id)?;
+        let child_address = self
+            .treasury_call(
+                treasury_address,
+                USER_JETTON_ADDRESS_METHOD.to_string(),
+                input.clone(),
+                block,
+            )
+            .await
+            .map_err(TonError::TreasuryCall)?;
+        let stack = match self.child_call(child_address, input, block).await {
+            // The child contract is deployed by the first withdrawal to the receiver.
+            Err(err) if err.is::<AccountNotDeployed>() => return Ok(false),
+            stack => stack,
+        };
+        let num = stack
+            .and_then(Self::single_num)
+            .map_err(TonError::ChildCall)?;
+        Ok(withdrawal.nonce <= Self::parse_nonce(&num)?)
+    }
+
+    /// Top-level numbers are `0x`-prefixed hex, the ones nested in tuples are decimal.
+    fn parse_nonce(num: &str) -> Result<u128> {
+        let ReturnValue::Uint(nonce) = num.parse::<ReturnValue>()? else {
+            bail!("Expected a non-negative nonce, got {num}");
+        };
+        u128::try_from(nonce).map_err(|e| anyhow!("Can't parse nonce ({num}) into u128: {e}"))
+    }
+
     fn verification_stage(num: String, action: Action) -> Result<()> {
         match action {
             Action::Deposit => {
-                ensure!(num == StackItem::SUCCESS_NUM, "Expected success, got {num}");
+                let success = ReturnValue::from(-1i128);
+                ensure!(
+                    num.parse::<ReturnValue>()? == success,
+                    "Expected success, got {num}"
+                );
             }
             Action::CheckCompletedWithdrawal { nonce } => {
-                let last_used_nonce = {
-                    U128::from_str(&num)
-                        .map_err(|e| anyhow!("Can't parse nonce ({num}) into u128: {e}"))?
-                        .as_u128()
-                };
+                let last_used_nonce = Self::parse_nonce(&num)?;
 
                 ensure!(
                     nonce <= last_used_nonce,
                     "Expected {nonce} <= {last_used_nonce}, last used: {last_used_nonce}"
                 );
             }
+            Action::JettonTransfer(_) => {
+                bail!("Jetton transfers are verified without get-methods")
+            }
         }
         Ok(())
     }
@@ -94,39 +445,305 @@ impl Verifier for TonVerifier {
         ExtendedChainId::Ton
     }
 
-    async fn verify(
+    async fn verify_at(
         &self,
         auth_contract_id: String,
         method_name: String,
         input_data: InputData,
+        at: Option<u64>,
     ) -> Result<bool> {
         let input: TonInputData = input_data.try_into()?;
-        let treasury_address = TonAddress::from_base64_url(&auth_contract_id)?;
-        let child_address = self
-            .treasury_call(treasury_address, method_name, input.clone())
-            .await
-            .map_err(TonError::TreasuryCall)?;
+        let block = self.block(at).await?;
+        if let Action::JettonTransfer(transfer) = &input.action {
+            self.verify_jetton_transfer(&auth_contract_id, transfer.clone(), block)
+                .await
+                .map_err(TonError::JettonTransfer)?;
+            return Ok(true);
+        }
         let num = self
-            .child_call(child_address, input.clone())
-            .await
-            .map_err(TonError::ChildCall)?;
+            .run(&auth_contract_id, method_name, input.clone(), block)
+            .await?;
         Self::verification_stage(num, input.action).map_err(TonError::VerificationStage)?;
         Ok(true)
     }
+
+    async fn call(
+        &self,
+        auth_contract_id: String,
+        method_name: String,
+        input_data: InputData,
+        at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        let input: TonInputData = input_data.try_into()?;
+        ensure!(
+            !matches!(input.action, Action::JettonTransfer(_)),
+            "Jetton transfers have no return value to check an expectation against"
+        );
+        let block = self.block(at).await?;
+        let num = self
+            .run(&auth_contract_id, method_name, input, block)
+            .await?;
+        num.parse()
+    }
+
+    /// Balance of the jetton wallet of `holder`, `token_id` is the jetton master.
+    async fn token_balance(
+        &self,
+        token_id: String,
+        holder: String,
+        at: Option<u64>,
+    ) -> Result<ReturnValue> {
+        let master_address = TonAddress::from_base64_url(&token_id)?;
+        let block = self.block(at).await?;
+        let balance = self.jetton_balance(master_address, &holder, block).await?;
+        balance.parse()
+    }
+
+    /// The transaction is looked up in the account history, TON has deterministic finality,
+    /// so a transaction that is found is final.
+    async fn is_transaction_successful(&self, input: TransactionInputData) -> Result<bool> {
+        let TransactionId::Ton { account, lt, hash } = input.transaction else {
+            bail!("Expected a TON transaction, got {:?}", input.transaction);
+        };
+        let address = TonAddress::from_str(&account)?;
+        let Some(transaction) = self.lookup_transaction(&address, lt, &hash).await? else {
+            return Ok(false);
+        };
+        types::is_successful(&transaction)
+    }
+
+    /// Fails if the child contract of the receiver isn't deployed yet.
+    async fn is_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
+    ) -> Result<bool> {
+        let block = self.block(None).await?;
+        self.is_nonce_used_at(&contract_id, withdrawal, block).await
+    }
 }
 
 impl ThresholdVerifier<TonVerifier> {
-    pub fn new_ton(config: ChainValidationConfig, client: &Arc<reqwest::Client>) -> Self {
+    /// Masterchain block the verifiers run get-methods at: the pinned seqno, or the highest
+    /// confirmed one `threshold` servers reached, along with the root hash they agree on.
+    /// Lite-servers check their proofs against that root hash instead of their own.
+    async fn agreed_block(&self, at: Option<u64>) -> Result<MasterchainBlock> {
+        let seqno = match at {
+            Some(seqno) => seqno,
+            None => {
+                let mut seqnos: Vec<u64> = join_all(
+                    self.verifiers
+                        .iter()
+                        .map(|verifier| verifier.confirmed_seqno()),
+                )
+                .await
+                .into_iter()
+                .filter_map(Result::ok)
+                .collect();
+                seqnos.sort_unstable_by(|a, b| b.cmp(a));
+                seqnos
+                    .get(self.threshold.saturating_sub(1))
+                    .copied()
+                    .with_context(|| {
+                        format!(
+                            "Less than {} TON servers reported their seqno",
+                            self.threshold
+                        )
+                    })?
+            }
+        };
+        let root_hash = self
+            .threshold_call(
+                move |verifier| async move { verifier.backend.block_root_hash(seqno).await },
+            )
+            .await?;
+        Ok(MasterchainBlock {
+            seqno,
+            root_hash: Some(root_hash),
+        })
+    }
+
+    /// The verifiers whose backends serve transactions, with the threshold counted over them.
+    fn transaction_verifiers(&self) -> Result<Self> {
+        let verifiers: Vec<_> = self
+            .verifiers
+            .iter()
+            .filter(|verifier| verifier.backend.serves_transactions())
+            .cloned()
+            .collect();
+        ensure!(
+            verifiers.len() >= self.threshold,
+            "Only {} TON servers serve transactions, the threshold is {}",
+            verifiers.len(),
+            self.threshold
+        );
+        Ok(Self {
+            threshold: self.threshold,
+            verifiers,
+        })
+    }
+
+    /// Same as [`ThresholdVerifier::verify_auth_call`], but get-methods run at the
+    /// [agreed block](Self::agreed_block). A `return_field` selects a number from the stack
+    /// returned by the child contract, which is checked against the expectation
+    /// or the implicit rule of the action.
+    pub async fn verify_ton_auth_call(
+        &self,
+        auth_call: HotVerifyAuthCall,
+        at: Option<u64>,
+    ) -> Result<bool> {
+        let HotVerifyAuthCall {
+            contract_id,
+            method,
+            input,
+            expectation,
+            return_field,
+            ..
+        } = auth_call;
+        match input {
+            InputData::Transaction(input) => {
+                self.transaction_verifiers()?
+                    .is_transaction_successful(input)
+                    .await
+            }
+            InputData::TokenBalance(input) => {
+                let master_address = TonAddress::from_base64_url(&contract_id)?;
+                let block = self.agreed_block(at).await?;
+                let holder = input.holder.clone();
+                let balance = self
+                    .threshold_call(move |verifier| {
+                        let master_address = master_address.clone();
+                        let holder = holder.clone();
+                        async move {
+                            verifier
+                                .jetton_balance(master_address, &holder, Some(block))
+                                .await
+                        }
+                    })
+                    .await?;
+                input.is_satisfied_by(&balance.parse()?)
+            }
+            input_data => {
+                let input: TonInputData = input_data.clone().try_into()?;
+                self.verify_ton_input(
+                    contract_id.clone(),
+                    method.clone(),
+                    input,
+                    expectation,
+                    return_field,
+                    at,
+                )
+                .await
+                .map_err(|kind| {
+                    VerificationError::new(
+                        ExtendedChainId::Ton,
+                        contract_id,
+                        method,
+                        input_data,
+                        kind,
+                    )
+                    .into()
+                })
+            }
+        }
+    }
+
+    /// Runs both get-methods of `input`, or looks up the jetton transfer it describes
+    /// on the [servers that serve transactions](Self::transaction_verifiers).
+    async fn verify_ton_input(
+        &self,
+        contract_id: String,
+        method: String,
+        input: TonInputData,
+        expectation: Option<Expectation>,
+        return_field: Option<String>,
+        at: Option<u64>,
+    ) -> Result<bool> {
+        let block = self.agreed_block(at).await?;
+        if let Action::JettonTransfer(transfer) = &input.action {
+            ensure!(
+                expectation.is_none() && return_field.is_none(),
+                "Jetton transfers have no return value to check an expectation against"
+            );
+            let transfer = transfer.clone();
+            self.transaction_verifiers()?
+                .threshold_call(move |verifier| {
+                    let contract_id = contract_id.clone();
+                    let transfer = transfer.clone();
+                    async move {
+                        verifier
+                            .verify_jetton_transfer(&contract_id, transfer, Some(block))
+                            .await
+                            .map_err(|err| anyhow::Error::from(TonError::JettonTransfer(err)))
+                    }
+                })
+                .await?;
+            return Ok(true);
+        }
+        let action = input.action.clone();
+        let stack = self
+            .threshold_call(move |verifier| {
+                let contract_id = contract_id.clone();
+                let method = method.clone();
+                let input = input.clone();
+                async move {
+                    verifier
+                        .run_stack(&contract_id, method, input, Some(block))
+                        .await
+                }
+            })
+            .await?;
+        let num = match return_field {
+            Some(field) => TonVerifier::select_item(&stack, &field)?.as_num()?,
+            None => TonVerifier::single_num(stack).map_err(TonError::ChildCall)?,
+        };
+        match expectation {
+            Some(expectation) => expectation.evaluate(&num.parse()?),
+            None => {
+                TonVerifier::verification_stage(num, action)
+                    .map_err(TonError::VerificationStage)?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Same as [`ThresholdVerifier::is_nonce_used`], at the [agreed block](Self::agreed_block).
+    pub async fn is_ton_nonce_used(
+        &self,
+        contract_id: String,
+        withdrawal: CompletedWithdrawal,
+    ) -> Result<bool> {
+        let block = self.agreed_block(None).await?;
+        self.threshold_call(move |verifier| {
+            let contract_id = contract_id.clone();
+            let withdrawal = withdrawal.clone();
+            async move {
+                verifier
+                    .is_nonce_used_at(&contract_id, withdrawal, Some(block))
+                    .await
+            }
+        })
+        .await
+    }
+
+    pub fn new_ton(config: ChainValidationConfig, client: &Arc<reqwest::Client>) -> Result<Self> {
         let threshold = config.threshold;
+        let finality = config.finality_or_default(ChainFamily::Ton);
+        let head_tracker = HeadTracker::new(ChainId::TON_V2, config.head_poll_interval());
         let servers = config.servers;
-        let verifiers = servers
+        let verifiers: Vec<_> = servers
             .into_iter()
-            .map(|url| Arc::new(TonVerifier::new(client.clone(), url)))
-            .collect();
-        Self {
+            .map(|url| {
+                let verifier = TonVerifier::new(client.clone(), url, finality)?
+                    .with_head_tracker(head_tracker.clone());
+                Ok(Arc::new(verifier))
+            })
+            .collect::<Result<_>>()?;
+        head_tracker.spawn(&verifiers);
+        Ok(Self {
             threshold,
             verifiers,
-        }
+        })
     }
 }
 
@@ -138,12 +755,18 @@ pub(crate) mod tests {
 
     use std::sync::Arc;
 
-    use crate::http_client::post_json_receive_json;
     use crate::test_data::ton_rpc;
-    use crate::verifiers::ton::types::{RpcRequest, RpcResponse};
+    use crate::threshold_verifier::ThresholdVerifier;
+    use crate::verifiers::ton::backend::{
+        single_item, AccountNotDeployed, MasterchainBlock, TonBackend,
+    };
     use crate::verifiers::ton::TonVerifier;
     use crate::verifiers::Verifier;
-    use hot_validation_primitives::ChainId;
+    use async_trait::async_trait;
+    use hot_validation_primitives::bridge::ton::SerializableCell;
+    use hot_validation_primitives::bridge::CompletedWithdrawal;
+    use hot_validation_primitives::{ChainFamily, Finality};
+    use tonlib_core::cell::{ArcCell, Cell, CellBuilder};
     use tonlib_core::TonAddress;
 
     #[tokio::test]
@@ -153,20 +776,20 @@ pub(crate) mod tests {
 
         let item = StackItem::from_nonce("1753218716000000003679".to_string());
 
-        let verifier = TonVerifier::new(Arc::new(reqwest::Client::new()), ton_rpc());
+        let verifier = TonVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            ton_rpc(),
+            Finality::default_for(ChainFamily::Ton),
+        )?;
 
         let address =
             TonAddress::from_base64_url("EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ")?;
-        let request = RpcRequest::build(&address, "get_deposit_jetton_address", vec![item]);
-        let item: RpcResponse = post_json_receive_json(
-            &verifier.client,
-            &verifier.server,
-            &request,
-            ChainId::TON_V2,
-        )
-        .await?;
+        let stack = verifier
+            .backend
+            .run_get_method(&address, "get_deposit_jetton_address", vec![item], None)
+            .await?;
 
-        let actual_address = item.unpack()?.as_cell()?.parser().load_address()?;
+        let actual_address = single_item(stack)?.as_cell()?.parser().load_address()?;
         assert_eq!(actual_address, expected_addr);
 
         Ok(())
@@ -180,25 +803,29 @@ pub(crate) mod tests {
             "bcb143828f64d7e4bf0b6a8e66a2a2d03c916c16e9e9034419ae778b9f699d3c".to_string(),
         )?;
 
-        let verifier = TonVerifier::new(Arc::new(reqwest::Client::new()), ton_rpc());
+        let verifier = TonVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            ton_rpc(),
+            Finality::default_for(ChainFamily::Ton),
+        )?;
 
-        let request = RpcRequest::build(&addr, "verify_withdraw", vec![item]);
-        let item: RpcResponse = post_json_receive_json(
-            &verifier.client,
-            &verifier.server,
-            &request,
-            ChainId::TON_V2,
-        )
-        .await?;
+        let stack = verifier
+            .backend
+            .run_get_method(&addr, "verify_withdraw", vec![item], None)
+            .await?;
 
-        let actual = item.unpack()?.as_num()?;
+        let actual = single_item(stack)?.as_num()?;
         assert_eq!(actual, "-0x1");
         Ok(())
     }
 
     #[tokio::test]
     async fn deposit_fist_and_second_call_combined() -> Result<()> {
-        let verifier = TonVerifier::new(Arc::new(reqwest::Client::new()), ton_rpc());
+        let verifier = TonVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            ton_rpc(),
+            Finality::default_for(ChainFamily::Ton),
+        )?;
 
         let input = TonInputData {
             treasury_call_args: vec![StackItem::from_nonce("1753218716000000003679".to_string())],
@@ -229,21 +856,26 @@ pub(crate) mod tests {
 
         let item = StackItem::from_address("UQA3zc65LQyIR9SoDniLaZA0UDPudeiNs6P06skYcCuCtw8I")?;
 
-        let verifier = TonVerifier::new(Arc::new(reqwest::Client::new()), ton_rpc());
+        let verifier = TonVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            ton_rpc(),
+            Finality::default_for(ChainFamily::Ton),
+        )?;
 
         let treasury_address =
             TonAddress::from_base64_url("EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ")?;
 
-        let request = RpcRequest::build(&treasury_address, "get_user_jetton_address", vec![item]);
-        let item: RpcResponse = post_json_receive_json(
-            &verifier.client,
-            &verifier.server,
-            &request,
-            ChainId::TON_V2,
-        )
-        .await?;
+        let stack = verifier
+            .backend
+            .run_get_method(
+                &treasury_address,
+                "get_user_jetton_address",
+                vec![item],
+                None,
+            )
+            .await?;
 
-        let actual_address = item.unpack()?.as_cell()?.parser().load_address()?;
+        let actual_address = single_item(stack)?.as_cell()?.parser().load_address()?;
         assert_eq!(actual_address, expected_addr);
 
         Ok(())
@@ -256,23 +888,27 @@ pub(crate) mod tests {
             TonAddress::from_base64_url(raw)?
         };
 
-        let verifier = TonVerifier::new(Arc::new(reqwest::Client::new()), ton_rpc());
-        let request = RpcRequest::build(&addr, "get_last_withdrawn_nonce", vec![]);
-        let item: RpcResponse = post_json_receive_json(
-            &verifier.client,
-            &verifier.server,
-            &request,
-            ChainId::TON_V2,
-        )
-        .await?;
+        let verifier = TonVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            ton_rpc(),
+            Finality::default_for(ChainFamily::Ton),
+        )?;
+        let stack = verifier
+            .backend
+            .run_get_method(&addr, "get_last_withdrawn_nonce", vec![], None)
+            .await?;
 
-        let _actual = item.unpack()?.as_num()?;
+        let _actual = single_item(stack)?.as_num()?;
         Ok(())
     }
 
     #[tokio::test]
     async fn completed_withdrawal_fist_and_second_call_combined_low() -> Result<()> {
-        let verifier = TonVerifier::new(Arc::new(reqwest::Client::new()), ton_rpc());
+        let verifier = TonVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            ton_rpc(),
+            Finality::default_for(ChainFamily::Ton),
+        )?;
 
         let input = TonInputData {
             treasury_call_args: vec![StackItem::from_address(
@@ -298,7 +934,11 @@ pub(crate) mod tests {
 
     #[tokio::test]
     async fn completed_withdrawal_fist_and_second_call_combined_high() -> Result<()> {
-        let verifier = TonVerifier::new(Arc::new(reqwest::Client::new()), ton_rpc());
+        let verifier = TonVerifier::new(
+            Arc::new(reqwest::Client::new()),
+            ton_rpc(),
+            Finality::default_for(ChainFamily::Ton),
+        )?;
 
         let input = TonInputData {
             treasury_call_args: vec![StackItem::from_address(
@@ -322,4 +962,134 @@ pub(crate) mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn toncenter_v3_is_left_out_of_transaction_lookups() -> Result<()> {
+        let client = Arc::new(reqwest::Client::new());
+        let finality = Finality::default_for(ChainFamily::Ton);
+        let verifiers = vec![
+            Arc::new(TonVerifier::new(
+                client.clone(),
+                "https://toncenter.com/api/v3".to_string(),
+                finality,
+            )?),
+            Arc::new(TonVerifier::new(client, ton_rpc(), finality)?),
+        ];
+        let verifier = ThresholdVerifier {
+            threshold: 1,
+            verifiers: verifiers.clone(),
+        };
+        let transaction_verifiers = verifier.transaction_verifiers()?;
+        assert_eq!(transaction_verifiers.verifiers.len(), 1);
+        assert_eq!(transaction_verifiers.verifiers[0].server, ton_rpc());
+
+        let verifier = ThresholdVerifier {
+            threshold: 2,
+            verifiers,
+        };
+        assert!(verifier.transaction_verifiers().is_err());
+        Ok(())
+    }
+
+    /// Treasury answering with `child`, which isn't deployed.
+    struct UndeployedChild {
+        child: TonAddress,
+    }
+
+    #[async_trait]
+    impl TonBackend for UndeployedChild {
+        async fn last_seqno(&self) -> Result<u64> {
+            Ok(1)
+        }
+
+        async fn block_root_hash(&self, _seqno: u64) -> Result<[u8; 32]> {
+            Ok([0; 32])
+        }
+
+        async fn run_get_method(
+            &self,
+            address: &TonAddress,
+            _method: &str,
+            _stack: Vec<StackItem>,
+            _block: Option<MasterchainBlock>,
+        ) -> Result<Vec<StackItem>> {
+            if *address == self.child {
+                return Err(AccountNotDeployed(address.to_base64_url()).into());
+            }
+            let mut cell = CellBuilder::new();
+            cell.store_address(&self.child)?;
+            Ok(vec![StackItem::Cell(SerializableCell::from(ArcCell::new(
+                cell.build()?,
+            )))])
+        }
+
+        async fn transaction(
+            &self,
+            _address: &TonAddress,
+            _lt: u64,
+            _hash: &[u8; 32],
+        ) -> Result<Option<Cell>> {
+            Ok(None)
+        }
+
+        async fn last_transactions(&self, _address: &TonAddress, _count: u32) -> Result<Vec<Cell>> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn undeployed_child_contract_has_no_used_nonces() -> Result<()> {
+        let verifier = TonVerifier {
+            server: "mock".to_string(),
+            backend: Box::new(UndeployedChild {
+                child: TonAddress::from_base64_url(
+                    "EQAgwUhaRZwU77BXUVEbtnEN8tplzDWMqUr0TbXWfez58tTL",
+                )?,
+            }),
+            finality: Finality::default_for(ChainFamily::Ton),
+            head_tracker: None,
+        };
+        let withdrawal = CompletedWithdrawal {
+            nonce: 1,
+            receiver_address: Some("EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ".to_string()),
+        };
+        let used = verifier
+            .is_nonce_used_at(
+                "EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ",
+                withdrawal,
+                None,
+            )
+            .await?;
+        assert!(!used);
+        Ok(())
+    }
+
+    #[test]
+    fn select_stack_item() -> Result<()> {
+        let stack = vec![
+            StackItem::Num("0x1".to_string()),
+            StackItem::Tuple(vec![
+                StackItem::Num("7".to_string()),
+                StackItem::List(vec![StackItem::Num("42".to_string())]),
+            ]),
+        ];
+        assert_eq!(TonVerifier::select_item(&stack, "0")?.as_num()?, "0x1");
+        assert_eq!(TonVerifier::select_item(&stack, "1.1.0")?.as_num()?, "42");
+        assert!(TonVerifier::select_item(&stack, "0.0").is_err());
+        assert!(TonVerifier::select_item(&stack, "2").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn nonce_of_nested_number() -> Result<()> {
+        assert_eq!(TonVerifier::parse_nonce("0x42")?, 66);
+        assert_eq!(TonVerifier::parse_nonce("42")?, 42);
+        assert!(TonVerifier::parse_nonce("-0x1").is_err());
+
+        let stack = vec![StackItem::Tuple(vec![StackItem::Num("42".to_string())])];
+        let num = TonVerifier::select_item(&stack, "0.0")?.as_num()?;
+        let action = Action::CheckCompletedWithdrawal { nonce: 66 };
+        assert!(TonVerifier::verification_stage(num, action).is_err());
+        Ok(())
+    }
 }
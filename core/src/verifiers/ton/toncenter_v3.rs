@@ -0,0 +1,201 @@
+//! toncenter API v3: <https://toncenter.com/api/v3/index.html>.
+//! The indexer doesn't serve raw transactions, so only get-methods are supported,
+//! see [`TonBackend::serves_transactions`].
+use crate::http_client::{get_json, post_json_receive_json};
+use crate::verifiers::ton::backend::{
+    canonical, decode_hash, ensure_exit_code, MasterchainBlock, TonBackend,
+};
+use anyhow::{bail, ensure, Context, Result};
+use async_trait::async_trait;
+use hot_validation_primitives::bridge::ton::{SerializableCell, StackItem};
+use hot_validation_primitives::ChainId;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tonlib_core::cell::Cell;
+use tonlib_core::TonAddress;
+
+pub(super) struct ToncenterV3 {
+    client: Arc<reqwest::Client>,
+    /// Base URL, e.g. `https://toncenter.com/api/v3`.
+    server: String,
+}
+
+/// Cells and slices are base64 BoCs.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+enum StackEntry {
+    Num(String),
+    Cell(String),
+    Slice(String),
+    Tuple(Vec<StackEntry>),
+    List(Vec<StackEntry>),
+}
+
+#[derive(Serialize)]
+struct RunGetMethodRequest<'a> {
+    address: String,
+    method: &'a str,
+    stack: Vec<StackEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seqno: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RunGetMethodResponse {
+    exit_code: i32,
+    stack: Vec<StackEntry>,
+}
+
+#[derive(Deserialize)]
+struct MasterchainInfo {
+    last: BlockId,
+}
+
+#[derive(Deserialize)]
+struct BlockId {
+    seqno: u64,
+    /// Base64.
+    root_hash: String,
+}
+
+#[derive(Deserialize)]
+struct Blocks {
+    blocks: Vec<BlockId>,
+}
+
+impl ToncenterV3 {
+    pub(super) fn new(client: Arc<reqwest::Client>, server: &str) -> Self {
+        Self {
+            client,
+            server: server.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl TryFrom<StackItem> for StackEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(item: StackItem) -> Result<Self> {
+        let entry = match item {
+            StackItem::Num(num) => Self::Num(num),
+            StackItem::Cell(cell) => Self::Cell(cell.to_boc_b64()?),
+            StackItem::Slice(cell) => Self::Slice(cell.to_boc_b64()?),
+            StackItem::Tuple(_) | StackItem::List(_) => {
+                bail!("Tuples and lists can't be passed to get-methods")
+            }
+        };
+        Ok(entry)
+    }
+}
+
+impl TryFrom<StackEntry> for StackItem {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: StackEntry) -> Result<Self> {
+        let entries = |entries: Vec<StackEntry>| -> Result<Vec<Self>> {
+            entries.into_iter().map(Self::try_from).collect()
+        };
+        let item = match entry {
+            StackEntry::Num(num) => canonical(Self::Num(num))?,
+            StackEntry::Cell(boc) => Self::Cell(SerializableCell::from_boc_b64(&boc)?),
+            StackEntry::Slice(boc) => Self::Slice(SerializableCell::from_boc_b64(&boc)?),
+            StackEntry::Tuple(items) => Self::Tuple(entries(items)?),
+            StackEntry::List(items) => Self::List(entries(items)?),
+        };
+        Ok(item)
+    }
+}
+
+#[async_trait]
+impl TonBackend for ToncenterV3 {
+    async fn last_seqno(&self) -> Result<u64> {
+        let url = format!("{}/masterchainInfo", self.server);
+        let info: MasterchainInfo = get_json(&self.client, &url, ChainId::TON_V2).await?;
+        Ok(info.last.seqno)
+    }
+
+    async fn block_root_hash(&self, seqno: u64) -> Result<[u8; 32]> {
+        let url = format!(
+            "{}/blocks?workchain=-1&shard=8000000000000000&seqno={seqno}&limit=1",
+            self.server
+        );
+        let response: Blocks = get_json(&self.client, &url, ChainId::TON_V2).await?;
+        let block = response
+            .blocks
+            .into_iter()
+            .find(|block| block.seqno == seqno)
+            .with_context(|| format!("No masterchain block {seqno}"))?;
+        decode_hash(&block.root_hash)
+    }
+
+    async fn run_get_method(
+        &self,
+        address: &TonAddress,
+        method: &str,
+        stack: Vec<StackItem>,
+        block: Option<MasterchainBlock>,
+    ) -> Result<Vec<StackItem>> {
+        let request = RunGetMethodRequest {
+            address: address.to_base64_url(),
+            method,
+            stack: stack
+                .into_iter()
+                .map(StackEntry::try_from)
+                .collect::<Result<_>>()?,
+            seqno: block.map(|block| block.seqno),
+        };
+        let url = format!("{}/runGetMethod", self.server);
+        let response: RunGetMethodResponse =
+            post_json_receive_json(&self.client, &url, &request, ChainId::TON_V2).await?;
+        ensure_exit_code(address, method, response.exit_code)?;
+        response
+            .stack
+            .into_iter()
+            .map(StackItem::try_from)
+            .collect()
+    }
+
+    async fn transaction(
+        &self,
+        _address: &TonAddress,
+        _lt: u64,
+        _hash: &[u8; 32],
+    ) -> Result<Option<Cell>> {
+        bail!("toncenter v3 doesn't serve raw transactions")
+    }
+
+    async fn last_transactions(&self, _address: &TonAddress, _count: u32) -> Result<Vec<Cell>> {
+        bail!("toncenter v3 doesn't serve raw transactions")
+    }
+
+    fn serves_transactions(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StackEntry;
+    use hot_validation_primitives::bridge::ton::StackItem;
+    use serde_json::json;
+
+    #[test]
+    fn stack_entries() -> anyhow::Result<()> {
+        let stack: Vec<StackEntry> = serde_json::from_value(json!([
+            {"type": "num", "value": "0x2a"},
+            {"type": "tuple", "value": [{"type": "num", "value": "-1"}]},
+        ]))?;
+        let stack = stack
+            .into_iter()
+            .map(StackItem::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(
+            stack,
+            vec![
+                StackItem::Num("0x2a".to_string()),
+                StackItem::Tuple(vec![StackItem::Num("-0x1".to_string())]),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
-use anyhow::ensure;
+use anyhow::{anyhow, ensure};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
 use hot_validation_primitives::bridge::ton::{ResponseStackItem, StackItem};
 use serde::{Deserialize, Serialize};
+use tonlib_core::cell::Cell;
 use tonlib_core::TonAddress;
 
+const TRANSACTION_TAG: u8 = 0b0111;
+
 #[derive(Debug, Serialize)]
 pub(crate) struct RpcRequest {
     jsonrpc: &'static str,
@@ -16,6 +21,9 @@ struct Params {
     address: String,
     method: String,
     stack: Vec<StackItem>,
+    /// Masterchain block to run the method at, the last one if omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seqno: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -24,25 +32,19 @@ pub(crate) struct RpcResponse {
 }
 
 impl RpcResponse {
-    pub(crate) fn unpack(self) -> anyhow::Result<StackItem> {
-        let stack = self
-            .result
-            .stack
-            .into_iter()
-            .map(|item| item.0)
-            .collect::<Vec<StackItem>>();
-        ensure!(
-            stack.len() == 1,
-            "expected 1 item in stack, got {}: stack={:?}",
-            stack.len(),
-            stack
-        );
-        Ok(stack[0].clone())
+    pub(crate) const fn exit_code(&self) -> i32 {
+        self.result.exit_code
+    }
+
+    pub(crate) fn into_stack(self) -> Vec<StackItem> {
+        self.result.stack.into_iter().map(|item| item.0).collect()
     }
 }
 
 #[derive(Deserialize)]
 struct ResultStack {
+    #[serde(default)]
+    exit_code: i32,
     stack: Vec<ResponseStackItem>,
 }
 
@@ -56,7 +58,303 @@ impl RpcRequest {
                 address: address.to_base64_url(),
                 method: method.to_string(),
                 stack,
+                seqno: None,
+            },
+        }
+    }
+
+    pub(crate) fn at_seqno(mut self, seqno: Option<u64>) -> Self {
+        self.params.seqno = seqno;
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MasterchainInfoRequest {
+    jsonrpc: &'static str,
+    id: &'static str,
+    method: &'static str,
+    params: serde_json::Value,
+}
+
+impl MasterchainInfoRequest {
+    pub(crate) fn build() -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "getMasterchainInfo",
+            params: serde_json::json!({}),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MasterchainInfoResponse {
+    result: MasterchainInfo,
+}
+
+#[derive(Deserialize)]
+struct MasterchainInfo {
+    last: BlockIdExt,
+}
+
+#[derive(Deserialize)]
+struct BlockIdExt {
+    seqno: u64,
+    /// Base64.
+    root_hash: String,
+}
+
+impl MasterchainInfoResponse {
+    pub(crate) fn last_seqno(&self) -> u64 {
+        self.result.last.seqno
+    }
+}
+
+/// `lookupBlock` request for a masterchain block by seqno.
+#[derive(Debug, Serialize)]
+pub(crate) struct LookupBlockRequest {
+    jsonrpc: &'static str,
+    id: &'static str,
+    method: &'static str,
+    params: serde_json::Value,
+}
+
+impl LookupBlockRequest {
+    pub(crate) fn masterchain(seqno: u64) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "lookupBlock",
+            params: serde_json::json!({
+                "workchain": -1,
+                "shard": "-9223372036854775808",
+                "seqno": seqno,
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LookupBlockResponse {
+    result: BlockIdExt,
+}
+
+impl LookupBlockResponse {
+    pub(crate) fn root_hash(&self) -> &str {
+        &self.result.root_hash
+    }
+}
+
+/// `getTransactions` request for transactions of `address`, from the newest one
+/// or starting at `lt`/`hash`.
+#[derive(Debug, Serialize)]
+pub(crate) struct TransactionsRequest {
+    jsonrpc: &'static str,
+    id: &'static str,
+    method: &'static str,
+    params: TransactionsParams,
+}
+
+#[derive(Debug, Serialize)]
+struct TransactionsParams {
+    address: String,
+    limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+    archival: bool,
+}
+
+impl TransactionsRequest {
+    pub(crate) fn build(address: &TonAddress, lt: u64, hash: &[u8; 32]) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "getTransactions",
+            params: TransactionsParams {
+                address: address.to_base64_url(),
+                limit: 1,
+                lt: Some(lt.to_string()),
+                hash: Some(BASE64_STANDARD.encode(hash)),
+                archival: true,
             },
         }
     }
+
+    pub(crate) fn latest(address: &TonAddress, limit: u32) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: "dontcare",
+            method: "getTransactions",
+            params: TransactionsParams {
+                address: address.to_base64_url(),
+                limit,
+                lt: None,
+                hash: None,
+                archival: false,
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TransactionsResponse {
+    result: Vec<Transaction>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Transaction {
+    /// Base64 BoC of the transaction.
+    pub(crate) data: String,
+    pub(crate) transaction_id: InternalTransactionId,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InternalTransactionId {
+    pub(crate) lt: String,
+    pub(crate) hash: String,
+}
+
+impl TransactionsResponse {
+    pub(crate) fn into_first(self) -> Option<Transaction> {
+        self.result.into_iter().next()
+    }
+
+    pub(crate) fn into_cells(self) -> anyhow::Result<Vec<Cell>> {
+        self.result
+            .into_iter()
+            .map(|transaction| Ok(Cell::from_boc_b64(&transaction.data)?))
+            .collect()
+    }
+}
+
+/// Hash of the previous transaction of the account:
+/// `transaction$0111 account_addr:bits256 lt:uint64 prev_trans_hash:bits256 prev_trans_lt:uint64 ...`
+pub(crate) fn prev_transaction_hash(cell: &Cell) -> anyhow::Result<[u8; 32]> {
+    let mut parser = cell.parser();
+    ensure!(parser.load_u8(4)? == TRANSACTION_TAG, "Not a transaction");
+    parser.skip_bits(256 + 64)?;
+    parser
+        .load_bits(256)?
+        .try_into()
+        .map_err(|_| anyhow!("Expected 256 bits"))
+}
+
+/// Internal message that triggered a transaction.
+pub(crate) struct InternalMessage {
+    pub(crate) src: TonAddress,
+    pub(crate) dest: TonAddress,
+    pub(crate) bounced: bool,
+    pub(crate) body: Cell,
+}
+
+/// The in-message of a transaction, `None` if there's none or it's external:
+/// `^[in_msg:(Maybe ^(Message Any)) out_msgs:(HashmapE 15 ^(Message Any))]`,
+/// `message$_ info:CommonMsgInfo init:(Maybe (Either StateInit ^StateInit)) body:(Either X ^X)`.
+pub(crate) fn in_message(cell: &Cell) -> anyhow::Result<Option<InternalMessage>> {
+    let messages = cell.reference(0)?;
+    if !messages.parser().load_bit()? {
+        return Ok(None);
+    }
+    let message = messages.reference(0)?;
+    let mut parser = message.parser();
+    // int_msg_info$0 ihr_disabled:Bool bounce:Bool bounced:Bool src:MsgAddressInt
+    // dest:MsgAddressInt value:CurrencyCollection ihr_fee:Grams fwd_fee:Grams
+    // created_lt:uint64 created_at:uint32
+    if parser.load_bit()? {
+        return Ok(None);
+    }
+    parser.load_bit()?;
+    parser.load_bit()?;
+    let bounced = parser.load_bit()?;
+    let src = parser.load_address()?;
+    let dest = parser.load_address()?;
+    parser.load_coins()?;
+    // References of the message that come before the body.
+    let mut refs = 0;
+    if parser.load_bit()? {
+        refs += 1;
+    }
+    parser.load_coins()?;
+    parser.load_coins()?;
+    parser.skip_bits(64 + 32)?;
+    if parser.load_bit()? {
+        ensure!(parser.load_bit()?, "Inline state init isn't supported");
+        refs += 1;
+    }
+    let body = if parser.load_bit()? {
+        Cell::clone(message.reference(refs)?)
+    } else {
+        let bits = parser.remaining_bits();
+        let references = message
+            .references()
+            .get(refs..)
+            .unwrap_or_default()
+            .to_vec();
+        Cell::new(parser.load_bits(bits)?, bits, references, false)?
+    };
+    Ok(Some(InternalMessage {
+        src,
+        dest,
+        bounced,
+        body,
+    }))
+}
+
+/// Whether the compute phase succeeded and the transaction wasn't aborted. Only ordinary
+/// transactions are supported, the only kind a user can send:
+/// `trans_ord$0000 credit_first:Bool storage_ph:(Maybe TrStoragePhase)
+/// credit_ph:(Maybe TrCreditPhase) compute_ph:TrComputePhase action:(Maybe ^TrActionPhase)
+/// aborted:Bool ...`
+pub(crate) fn is_successful(cell: &Cell) -> anyhow::Result<bool> {
+    // Refs of a transaction: `^[in_msg out_msgs]`, `state_update`, `description`.
+    let description = cell.reference(2)?;
+    let mut parser = description.parser();
+    ensure!(
+        parser.load_u8(4)? == 0,
+        "Not an ordinary transaction, only those are supported"
+    );
+    // credit_first
+    parser.load_bit()?;
+    // storage_ph: storage_fees_collected:Grams storage_fees_due:(Maybe Grams) status_change:AccStatusChange
+    if parser.load_bit()? {
+        parser.load_coins()?;
+        if parser.load_bit()? {
+            parser.load_coins()?;
+        }
+        // acst_unchanged$0 | acst_frozen$10 | acst_deleted$11
+        if parser.load_bit()? {
+            parser.load_bit()?;
+        }
+    }
+    // credit_ph: due_fees_collected:(Maybe Grams) credit:CurrencyCollection
+    if parser.load_bit()? {
+        if parser.load_bit()? {
+            parser.load_coins()?;
+        }
+        parser.load_coins()?;
+        // Extra currencies live in a reference, only the `Maybe` bit is inline.
+        parser.load_bit()?;
+    }
+    // compute_ph: tr_phase_compute_vm$1 success:Bool msg_state_used:Bool account_activated:Bool
+    // gas_fees:Grams ^[...] | tr_phase_compute_skipped$0 reason:ComputeSkipReason
+    let compute_success = if parser.load_bit()? {
+        let success = parser.load_bit()?;
+        parser.load_bit()?;
+        parser.load_bit()?;
+        parser.load_coins()?;
+        success
+    } else {
+        // cskip_no_state$00 | cskip_bad_state$01 | cskip_no_gas$10 | cskip_suspended$110
+        if parser.load_u8(2)? == 0b11 {
+            parser.load_bit()?;
+        }
+        false
+    };
+    // action: the phase itself is in a reference.
+    parser.load_bit()?;
+    let aborted = parser.load_bit()?;
+    Ok(compute_success && !aborted)
 }
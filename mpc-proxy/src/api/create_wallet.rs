@@ -69,7 +69,11 @@ pub(crate) async fn create_wallet_endpoint(
     State(state): State<AppState>,
     Json(request): Json<CreateWalletRequest>,
 ) -> Result<Json<CreateWalletResponse>, AppError> {
-    let worker = near_workspaces::mainnet().await.unwrap();
+    let network = &state.validation.network;
+    let worker = near_workspaces::custom(&network.near_rpc)
+        .await
+        .map_err(anyhow::Error::from)
+        .map_err(AppError::NearSigner)?;
     let signer = InMemorySigner::from(state.secrets_config.near_registry_account.clone());
 
     // TODO: Validation
@@ -77,7 +81,9 @@ pub(crate) async fn create_wallet_endpoint(
     let tx = worker
         .call(
             &signer,
-            &AccountId::from_str("mpc.hot.tg").unwrap(),
+            &AccountId::from_str(&network.near_registry_contract)
+                .map_err(anyhow::Error::from)
+                .map_err(AppError::NearSigner)?,
             "create_wallet",
         )
         .args_json(OnChainArgs::from(request))
@@ -6,6 +6,7 @@ use crate::api::healthcheck::healthcheck_endpoint;
 use crate::api::public_key::public_key_endpoint;
 use crate::api::sign::{sign_endpoint, sign_raw_endpoint};
 use crate::api::telemetry::prometheus_metrics;
+use crate::api::verify::verify_at_endpoint;
 use crate::domain::mpc::cluster::ClusterManager;
 use crate::secrets::SecretsConfig;
 use axum::Router;
@@ -19,6 +20,7 @@ mod healthcheck;
 mod public_key;
 mod sign;
 mod telemetry;
+mod verify;
 
 #[derive(Clone)]
 pub(crate) struct AppState {
@@ -36,6 +38,7 @@ pub fn router() -> Router<AppState> {
         .route("/clear/sign", post(clear_completed_withdrawal_endpoint))
         .route("/sign_raw", post(sign_raw_endpoint))
         .route("/sign", post(sign_endpoint))
+        .route("/verify_at", post(verify_at_endpoint))
         .route("/public_key", post(public_key_endpoint))
         .route("/create_wallet", post(create_wallet_endpoint))
 }
@@ -22,12 +22,19 @@ struct ProofRaw {
     user_payloads: Vec<serde_json::Value>,
 }
 
-impl From<ProofRaw> for ProofModel {
-    fn from(value: ProofRaw) -> Self {
-        Self {
+impl TryFrom<ProofRaw> for ProofModel {
+    type Error = anyhow::Error;
+
+    fn try_from(value: ProofRaw) -> Result<Self, Self::Error> {
+        let user_payloads = value
+            .user_payloads
+            .iter()
+            .map(|p| p.to_string().parse())
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
             message_body: value.message_body,
-            user_payloads: value.user_payloads.iter().map(|p| p.to_string()).collect(),
-        }
+            user_payloads,
+        })
     }
 }
 
@@ -108,7 +115,7 @@ pub(crate) async fn sign_raw_endpoint(
         key_type,
     }): Json<SignRawRequest>,
 ) -> Result<Json<ProxySignatureResponse>, AppError> {
-    let proof_model = ProofModel::from(proof);
+    let proof_model = ProofModel::try_from(proof).map_err(AppError::DataConversionError)?;
     let signature = validate_and_sign(
         &state.cluster_manager,
         &state.validation,
@@ -0,0 +1,106 @@
+use crate::api::AppState;
+use axum::Json;
+use axum::extract::State;
+use hot_validation_core::AuthMethodReport;
+use hot_validation_primitives::uid::Uid;
+use hot_validation_primitives::{Base58, Base58Array, ChainId, ProofModel};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::collections::HashMap;
+use tracing::instrument;
+
+/// Same as the `/sign` request, plus the blocks to verify at. Nothing gets signed.
+#[serde_as]
+#[derive(Deserialize)]
+pub(crate) struct VerifyAtRequest {
+    #[serde_as(deserialize_as = "Base58Array<32>")]
+    #[serde(rename = "wallet_derive")]
+    uid: Uid,
+    /// Hashed message that we want to check
+    #[serde_as(as = "Base58")]
+    message: Vec<u8>,
+    #[serde(flatten)]
+    proof: ProofModel,
+    /// Chain id -> block height (NEAR, EVM, Cosmos) or masterchain seqno (TON).
+    /// Solana and Stellar can't be pinned.
+    /// Chains without a pin are verified against their usual head.
+    #[serde(default)]
+    pins: HashMap<ChainId, u64>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct VerifyAtResponse {
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// One per auth method of the wallet, in order, if it's valid.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    auth_methods: Vec<AuthMethodReport>,
+}
+
+#[instrument(skip(state, uid, message, proof))]
+pub(crate) async fn verify_at_endpoint(
+    State(state): State<AppState>,
+    Json(VerifyAtRequest {
+        uid,
+        message,
+        proof,
+        pins,
+    }): Json<VerifyAtRequest>,
+) -> Json<VerifyAtResponse> {
+    let wallet_id = uid.to_wallet_id();
+    let response = match state
+        .validation
+        .verify_at(wallet_id, message, proof, &pins)
+        .await
+    {
+        Ok(auth_methods) => VerifyAtResponse {
+            valid: true,
+            error: None,
+            auth_methods,
+        },
+        Err(err) => VerifyAtResponse {
+            valid: false,
+            error: Some(format!("{err:#}")),
+            auth_methods: vec![],
+        },
+    };
+    Json(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VerifyAtResponse;
+    use hot_validation_core::AuthMethodReport;
+    use serde_json::json;
+
+    #[test]
+    fn response_reports_the_read_ledger() {
+        let response = VerifyAtResponse {
+            valid: true,
+            error: None,
+            auth_methods: vec![
+                AuthMethodReport {
+                    last_modified_ledger: Some(52_000_000),
+                },
+                AuthMethodReport::default(),
+            ],
+        };
+        assert_eq!(
+            serde_json::to_value(response).unwrap(),
+            json!({
+                "valid": true,
+                "auth_methods": [{ "last_modified_ledger": 52_000_000 }, {}]
+            })
+        );
+        let response = VerifyAtResponse {
+            valid: false,
+            error: Some("failed".to_string()),
+            auth_methods: vec![],
+        };
+        assert_eq!(
+            serde_json::to_value(response).unwrap(),
+            json!({ "valid": false, "error": "failed" })
+        );
+    }
+}
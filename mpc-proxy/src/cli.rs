@@ -1,4 +1,5 @@
 use clap::Parser;
+use hot_validation_primitives::Network;
 use std::path::PathBuf;
 
 #[derive(Debug, Parser, Clone)]
@@ -16,6 +17,24 @@ pub struct Cli {
     #[arg(long, env)]
     pub cluster_config_path: PathBuf,
 
+    /// Built-in network profile (contracts, passphrases) to run against.
+    #[arg(long, env, default_value_t = Network::Mainnet)]
+    pub network: Network,
+
+    /// Overrides the built-in profile, e.g. for a local sandbox. Required on testnet,
+    /// whose built-in profile has no contracts.
+    #[arg(long, env)]
+    pub network_profile_path: Option<PathBuf>,
+
+    /// Extra chains (or overrides) on top of the built-in chain registry.
+    #[arg(long, env)]
+    pub chain_registry_path: Option<PathBuf>,
+
+    /// Allowlist of auth calls NEAR auth contracts may redirect verification to.
+    /// Auth calls are not restricted if omitted.
+    #[arg(long, env)]
+    pub auth_call_policy_path: Option<PathBuf>,
+
     #[arg(long, env)]
     pub alloy_endpoint: String,
 
@@ -20,7 +20,7 @@ pub(crate) async fn sign_clear_completed_withdrawal(
         .build_challenge_for_removal_owned()
         .to_vec();
     let proof_model =
-        ClearCompletedWithdrawalRequest::create_proof_model(completed_withdrawal_action)?;
+        ClearCompletedWithdrawalRequest::create_proof_model(completed_withdrawal_action);
     validate_and_sign(
         cluster_manager,
         validation,
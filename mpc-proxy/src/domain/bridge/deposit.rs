@@ -18,7 +18,7 @@ pub(crate) async fn sign_deposit(
         .build_challenge_for_deposit()
         .map_err(AppError::DataConversionError)?
         .to_vec();
-    let proof_model = DepositRequest::create_proof_model(deposit_action)?;
+    let proof_model = DepositRequest::create_proof_model(deposit_action);
     validate_and_sign(
         cluster_manager,
         validation,
@@ -1,30 +1,65 @@
 use crate::domain::WithdrawRequest;
 use crate::domain::errors::AppError;
 use crate::domain::mpc::cluster::ClusterManager;
-use anyhow::Result;
+use anyhow::{Result, ensure};
 use hot_validation_core::Validation;
 use hot_validation_primitives::bridge::DepositAction;
 use hot_validation_primitives::mpc::{KeyType, OffchainSignatureResponse};
 use hot_validation_primitives::uid::Uid;
 use serde_json::json;
 use std::sync::Arc;
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 #[instrument(skip(validation), err(Debug))]
 async fn get_withdrawal(
     validation: &Arc<Validation>,
     nonce: u128,
 ) -> Result<Option<DepositAction>> {
-    let contract = "v2_1.omni.hot.tg";
+    let contract = validation.network.omni_bridge_contract.clone();
     let method = "get_transfer";
     let args = json!({ "nonce": nonce.to_string() });
     let result: Option<DepositAction> = validation
         .near
-        .call_view_method(contract.to_string(), method.to_string(), args)
+        .call_view_method(contract, method.to_string(), args)
         .await?;
     Ok(result)
 }
 
+/// Refuses withdrawals that were already executed on the destination chain,
+/// so a signature for the same nonce is never produced twice.
+/// Chains without a bridge contract are skipped with a warning, unless they're strict.
+#[instrument(skip(validation), err(Debug))]
+async fn ensure_nonce_not_used(
+    validation: &Arc<Validation>,
+    withdrawal: &DepositAction,
+) -> Result<()> {
+    let Some(contract) = validation
+        .network
+        .nonce_check_contract(withdrawal.chain_id)?
+    else {
+        warn!(
+            "No bridge contract configured for {}, its withdrawal nonce is not checked",
+            withdrawal.chain_id
+        );
+        return Ok(());
+    };
+    let used = validation
+        .is_nonce_used(
+            withdrawal.chain_id,
+            contract,
+            withdrawal.data.nonce,
+            withdrawal.receiver_address()?,
+        )
+        .await?;
+    ensure!(
+        !used,
+        "withdrawal {} was already executed on {}",
+        withdrawal.data.nonce,
+        withdrawal.chain_id
+    );
+    Ok(())
+}
+
 /// We don't need to do `validation.verify()` here, because it will check the state of NEAR bridge,
 /// but we've formed our data by reading the state of NEAR bridge.
 pub(crate) async fn sign_withdraw(
@@ -44,6 +79,9 @@ pub(crate) async fn sign_withdraw(
             )
         })
         .map_err(AppError::ValidationError)?;
+    ensure_nonce_not_used(validation, &withdrawal)
+        .await
+        .map_err(AppError::ValidationError)?;
     let challenge = withdrawal
         .build_challenge_for_deposit()
         .map_err(AppError::ValidationError)?
@@ -6,12 +6,12 @@ use crate::api::bridge::{ClearCompletedWithdrawalRequest, DepositRequest, Withdr
 use crate::domain::errors::AppError;
 use crate::domain::mpc::cluster::ClusterManager;
 use hot_validation_core::Validation;
-use hot_validation_primitives::ProofModel;
 use hot_validation_primitives::bridge::{
     CompletedWithdrawalAction, DepositAction, HotVerifyBridge,
 };
 use hot_validation_primitives::mpc::{KeyType, OffchainSignatureResponse};
 use hot_validation_primitives::uid::Uid;
+use hot_validation_primitives::{ProofModel, UserPayload};
 use std::sync::Arc;
 
 pub(crate) async fn validate_and_sign(
@@ -37,16 +37,13 @@ pub(crate) async fn validate_and_sign(
 impl ClearCompletedWithdrawalRequest {
     pub fn create_proof_model(
         completed_withdrawal_action: CompletedWithdrawalAction,
-    ) -> Result<ProofModel, AppError> {
-        let payload = HotVerifyBridge::ClearCompletedWithdrawal(completed_withdrawal_action);
-        let json = serde_json::to_string(&payload)
-            .map_err(anyhow::Error::from)
-            .map_err(AppError::DataConversionError)?;
-        let proof_model = ProofModel {
+    ) -> ProofModel {
+        ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
-        };
-        Ok(proof_model)
+            user_payloads: vec![
+                HotVerifyBridge::ClearCompletedWithdrawal(completed_withdrawal_action).into(),
+            ],
+        }
     }
 }
 
@@ -60,25 +57,19 @@ impl From<ClearCompletedWithdrawalRequest> for CompletedWithdrawalAction {
 }
 
 impl DepositRequest {
-    pub fn create_proof_model(deposit_action: DepositAction) -> Result<ProofModel, AppError> {
-        let payload = HotVerifyBridge::Deposit(deposit_action);
-        let json = serde_json::to_value(&payload)
-            .map_err(anyhow::Error::from)
-            .map_err(AppError::DataConversionError)?;
-        let proof_model = ProofModel {
+    pub fn create_proof_model(deposit_action: DepositAction) -> ProofModel {
+        ProofModel {
             message_body: String::new(),
-            user_payloads: vec![json.to_string()],
-        };
-        Ok(proof_model)
+            user_payloads: vec![HotVerifyBridge::Deposit(deposit_action).into()],
+        }
     }
 }
 
 impl WithdrawRequest {
     pub fn create_proof_model(&self) -> ProofModel {
-        let payload = self.nonce.to_string();
         ProofModel {
             message_body: String::new(),
-            user_payloads: vec![payload],
+            user_payloads: vec![UserPayload::WithdrawNonce(self.nonce)],
         }
     }
 }
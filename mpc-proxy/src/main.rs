@@ -15,7 +15,8 @@ use axum::Router;
 use axum::extract::MatchedPath;
 use clap::Parser;
 use hot_validation_core::Validation;
-use hot_validation_primitives::ValidationConfig;
+use hot_validation_core::policy::AuthCallPolicy;
+use hot_validation_primitives::{ChainRegistry, NetworkProfile, ValidationConfig};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -44,13 +45,37 @@ async fn main() -> Result<()> {
 
         result
     };
+    if let Some(path) = &cli.chain_registry_path {
+        let file = std::fs::read_to_string(path).context("failed to read chain registry")?;
+        let mut registry = ChainRegistry::builtin();
+        registry.merge_yaml(&file)?;
+        ChainRegistry::install(registry)?;
+    }
+    let network_profile = match &cli.network_profile_path {
+        Some(path) => {
+            let file = std::fs::read_to_string(path).context("failed to read network profile")?;
+            serde_yaml::from_str(&file)?
+        }
+        None => NetworkProfile::from(cli.network),
+    };
+    info!(?network_profile, "selected network profile");
     let validation = {
         let validation_config: ValidationConfig = {
             let file = std::fs::read_to_string(&cli.validation_config_path)
                 .context("failed to read validation config")?;
             serde_yaml::from_str(&file)?
         };
-        Validation::new(&validation_config)?
+        let validation = Validation::new_with_network(&validation_config, network_profile)?;
+        match &cli.auth_call_policy_path {
+            Some(path) => {
+                let file =
+                    std::fs::read_to_string(path).context("failed to read auth call policy")?;
+                let policy: AuthCallPolicy = serde_yaml::from_str(&file)?;
+                info!(rules = policy.rules.len(), "auth call policy enabled");
+                validation.with_auth_call_policy(policy)
+            }
+            None => validation,
+        }
     };
     let cluster_manager = {
         let cluster_config: Vec<Vec<Server>> = {
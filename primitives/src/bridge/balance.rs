@@ -0,0 +1,64 @@
+//! Token-balance auth calls: signing is allowed while `holder` keeps at least `min_balance`
+//! of the token at [`HotVerifyAuthCall::contract_id`](crate::bridge::HotVerifyAuthCall::contract_id).
+//!
+//! The token is queried the standard way of each chain instead of a custom view method:
+//! `balanceOf` of an ERC-20 on EVM, the amount of the associated SPL token account on Solana,
+//! `ft_balance_of` of a NEP-141 on NEAR and the jetton wallet balance on TON.
+//! Stellar and Cosmos don't support token balances.
+use crate::bridge::expectation::{Expectation, ReturnValue};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct TokenBalanceInputData {
+    /// Account whose balance is checked. On Solana it's the wallet owning
+    /// the associated token account of the mint.
+    pub holder: String,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schemars(with = "String")]
+    pub min_balance: u128,
+}
+
+impl TokenBalanceInputData {
+    pub fn is_satisfied_by(&self, balance: &ReturnValue) -> Result<bool> {
+        Expectation::GreaterOrEqual(self.min_balance.into()).evaluate(balance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bridge::InputData;
+    use crate::bridge::balance::TokenBalanceInputData;
+    use crate::bridge::expectation::ReturnValue;
+
+    #[test]
+    fn token_balance_input() {
+        let input: InputData = serde_json::from_str(
+            r#"{"family":"token_balance","data":{"holder":"alice.near","min_balance":"100"}}"#,
+        )
+        .unwrap();
+        let InputData::TokenBalance(input) = input else {
+            panic!("expected token balance input, got {input:?}")
+        };
+        assert!(input.is_satisfied_by(&ReturnValue::from(100u128)).unwrap());
+        assert!(!input.is_satisfied_by(&ReturnValue::from(99u128)).unwrap());
+
+        // Only the tagged format is accepted for token balance checks.
+        assert!(
+            serde_json::from_str::<InputData>(r#"{"holder":"alice.near","min_balance":"100"}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn token_balance_rejects_bools() {
+        let input = TokenBalanceInputData {
+            holder: "alice.near".to_string(),
+            min_balance: 1,
+        };
+        assert!(input.is_satisfied_by(&ReturnValue::Bool(true)).is_err());
+    }
+}
@@ -21,3 +21,45 @@ pub enum CosmosInputData {
         nonce: u128,
     },
 }
+
+/// Auth call answered by reading a CosmWasm contract storage entry with an ICS-23 proof
+/// instead of running a smart query, for plain lookups such as executed nonces.
+/// The auth call method is unused.
+///
+/// Without an expectation, a JSON `bool` entry is its value and any other entry is `true`
+/// if it exists.
+#[serde_as]
+#[derive(Serialize, Deserialize, Clone, Debug, schemars::JsonSchema, Eq, PartialEq, Hash)]
+pub struct CosmosStorageQuery {
+    /// Raw key in the contract storage, e.g. a `cw-storage-plus` `Map` key:
+    /// `len(namespace):u16 || namespace || key`.
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "String")]
+    pub key: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ChainFamily;
+    use crate::bridge::InputData;
+    use crate::bridge::cosmos::CosmosStorageQuery;
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[test]
+    fn check_storage_query() -> Result<()> {
+        let input = json!({
+            "family": "cosmos_storage",
+            "data": { "key": "AAhleGVjdXRlZA==" }
+        });
+        let input: InputData = serde_json::from_value(input)?;
+        assert_eq!(input.family(), Some(ChainFamily::Cosmos));
+        assert_eq!(
+            input,
+            InputData::CosmosStorage(CosmosStorageQuery {
+                key: b"\x00\x08executed".to_vec()
+            })
+        );
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_hex::{SerHexSeq, StrictPfx};
 use serde_with::serde_as;
+use std::collections::BTreeMap;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Hash, Clone)]
@@ -35,32 +36,80 @@ impl From<EvmInputArg> for DynSolValue {
     }
 }
 
+/// Arguments of an EVM view call: a plain list for the functions of the bridge ABI
+/// (`hot_verify`, `usedNonces`, `balanceOf`), or a [`EvmFunctionCall`] for any other function.
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Hash, Clone)]
-#[serde(transparent)]
-pub struct EvmInputData(pub Vec<EvmInputArg>);
+#[serde(untagged)]
+pub enum EvmInputData {
+    Args(Vec<EvmInputArg>),
+    Call(EvmFunctionCall),
+}
+
+/// Call of a function that isn't in the bridge ABI, encoded and decoded by its signature.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct EvmFunctionCall {
+    /// Human-readable signature with the outputs, e.g.
+    /// `function lastNonce(bytes32 user) view returns (uint128)`.
+    /// The function name has to be the auth call method.
+    pub function: String,
+    #[serde(default)]
+    pub args: Vec<EvmInputArg>,
+}
 
 impl EvmInputData {
     pub fn from_parts(message_hex: String, user_payload: String) -> Result<Self> {
-        Ok(Self(vec![
+        Ok(Self::Args(vec![
             EvmInputArg::FixedBytes(hex::decode(message_hex)?),
             EvmInputArg::Bytes(Vec::new()),
             EvmInputArg::Bytes(hex::decode(user_payload)?),
             EvmInputArg::Bytes(Vec::new()),
         ]))
     }
+
+    /// Signature of the called function, `None` for the functions of the bridge ABI.
+    #[must_use]
+    pub fn function(&self) -> Option<&str> {
+        match self {
+            Self::Args(_) => None,
+            Self::Call(call) => Some(&call.function),
+        }
+    }
+}
+
+/// Auth call that is satisfied by an event log of the contract instead of a view method,
+/// for contracts that only emit e.g. `Deposit(...)`.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct EvmLogQuery {
+    /// Human-readable event signature with parameter names,
+    /// e.g. `Deposit(address indexed sender, uint256 amount, uint128 nonce)`.
+    pub event: String,
+    /// Filters for `topic1..topic3` passed to `eth_getLogs`: `0x`-prefixed 32-byte words,
+    /// `null` matches any value.
+    #[serde(default)]
+    pub topics: Vec<Option<String>>,
+    pub from_block: u64,
+    /// Capped by the head selected by the finality policy, which is also the default.
+    #[serde(default)]
+    pub to_block: Option<u64>,
+    /// Decoded fields of the log that have to equal the given values, by parameter name,
+    /// e.g. `{"nonce": "1753218716000000003679"}`.
+    #[serde(default)]
+    pub expected: BTreeMap<String, String>,
 }
 
 impl From<EvmInputData> for Vec<DynSolValue> {
     fn from(data: EvmInputData) -> Self {
-        data.0.into_iter().map(DynSolValue::from).collect()
+        let (EvmInputData::Args(args) | EvmInputData::Call(EvmFunctionCall { args, .. })) = data;
+        args.into_iter().map(DynSolValue::from).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use crate::ChainFamily;
     use crate::bridge::evm::{EvmInputArg, EvmInputData};
-    use crate::bridge::{HotVerifyAuthCall, HotVerifyResult};
+    use crate::bridge::{HotVerifyAuthCall, HotVerifyResult, InputData};
     use serde_json::json;
 
     #[test]
@@ -120,4 +169,44 @@ mod tests {
         serde_json::from_str::<HotVerifyAuthCall>(&x.to_string()).unwrap();
         serde_json::from_str::<HotVerifyResult>(&x.to_string()).unwrap();
     }
+
+    #[test]
+    fn evm_function_call_format() {
+        let input = json!({
+            "function": "function lastNonce(bytes32 user) view returns (uint128)",
+            "args": [{ "type": "bytes32", "value": "0x74657374" }]
+        });
+        let input: EvmInputData = serde_json::from_value(input).unwrap();
+        assert_eq!(
+            input.function(),
+            Some("function lastNonce(bytes32 user) view returns (uint128)")
+        );
+        let args: Vec<alloy_dyn_abi::DynSolValue> = input.into();
+        assert_eq!(args.len(), 1);
+
+        let input: EvmInputData = serde_json::from_str("[]").unwrap();
+        assert_eq!(input, EvmInputData::Args(vec![]));
+        assert_eq!(input.function(), None);
+    }
+
+    #[test]
+    fn evm_log_query_format() {
+        let input = json!({
+            "family": "evm_logs",
+            "data": {
+                "event": "Deposit(address indexed sender, uint256 amount, uint128 nonce)",
+                "topics": [null],
+                "from_block": 30_000_000,
+                "expected": { "nonce": "42" }
+            }
+        });
+        let input: InputData = serde_json::from_value(input).unwrap();
+        let InputData::EvmLogs(query) = input else {
+            panic!("expected evm logs input, got {input:?}")
+        };
+        assert_eq!(query.topics, vec![None]);
+        assert_eq!(query.to_block, None);
+        assert_eq!(query.expected["nonce"], "42");
+        assert_eq!(InputData::EvmLogs(query).family(), Some(ChainFamily::Evm));
+    }
 }
@@ -0,0 +1,294 @@
+//! Chain-agnostic success rules for auth calls.
+//!
+//! By default every verifier applies its own implicit rule to what the auth call returned
+//! (a `bool` on EVM/Stellar/Cosmos, nonce comparisons on TON and Solana). An auth call that
+//! carries an [`Expectation`] is instead evaluated by core on the decoded [`ReturnValue`],
+//! so new contracts can express checks like "stored nonce ≥ nonce" without new verifier code.
+use alloy_primitives::U256;
+use anyhow::{Result, anyhow, bail, ensure};
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaGenerator;
+use schemars::schema::{Schema, SchemaObject, SubschemaValidation};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Return value of an auth call, decoded from the chain-specific representation.
+///
+/// In JSON a bool is a bool, and an integer is a decimal (or `0x`-prefixed hex) string,
+/// so that it isn't limited by the precision of JSON numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReturnValue {
+    Bool(bool),
+    /// Non-negative integer.
+    Uint(U256),
+    /// Negative integer, holds the absolute value (e.g. TVM `true` is `Negative(1)`).
+    Negative(U256),
+}
+
+impl ReturnValue {
+    #[must_use]
+    pub fn from_signed(negative: bool, magnitude: U256) -> Self {
+        if negative && magnitude != U256::ZERO {
+            Self::Negative(magnitude)
+        } else {
+            Self::Uint(magnitude)
+        }
+    }
+
+    fn compare(&self, other: &Self) -> Result<Ordering> {
+        let ordering = match (self, other) {
+            (Self::Uint(a), Self::Uint(b)) => a.cmp(b),
+            (Self::Negative(a), Self::Negative(b)) => b.cmp(a),
+            (Self::Negative(_), Self::Uint(_)) => Ordering::Less,
+            (Self::Uint(_), Self::Negative(_)) => Ordering::Greater,
+            (Self::Bool(_), _) | (_, Self::Bool(_)) => {
+                bail!("Can't order {self} and {other}, only integers are ordered")
+            }
+        };
+        Ok(ordering)
+    }
+}
+
+impl From<bool> for ReturnValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<u128> for ReturnValue {
+    fn from(value: u128) -> Self {
+        Self::Uint(U256::from(value))
+    }
+}
+
+impl From<i128> for ReturnValue {
+    fn from(value: i128) -> Self {
+        Self::from_signed(value < 0, U256::from(value.unsigned_abs()))
+    }
+}
+
+/// Parses a decimal or `0x`-prefixed hex integer with an optional minus sign.
+impl FromStr for ReturnValue {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (negative, magnitude) = match s.strip_prefix('-') {
+            Some(magnitude) => (true, magnitude),
+            None => (false, s),
+        };
+        let magnitude = match magnitude.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16),
+            None => U256::from_str_radix(magnitude, 10),
+        }
+        .map_err(|e| anyhow!("Invalid integer {s}: {e}"))?;
+        Ok(Self::from_signed(negative, magnitude))
+    }
+}
+
+impl Display for ReturnValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Uint(value) => write!(f, "{value}"),
+            Self::Negative(value) => write!(f, "-{value}"),
+        }
+    }
+}
+
+impl Serialize for ReturnValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Bool(value) => serializer.serialize_bool(*value),
+            Self::Uint(_) | Self::Negative(_) => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ReturnValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bool(bool),
+            Number(u64),
+            String(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Bool(value) => Ok(Self::Bool(value)),
+            Raw::Number(value) => Ok(Self::Uint(U256::from(value))),
+            Raw::String(value) => value.parse().map_err(D::Error::custom),
+        }
+    }
+}
+
+impl JsonSchema for ReturnValue {
+    fn schema_name() -> String {
+        "ReturnValue".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    generator.subschema_for::<bool>(),
+                    generator.subschema_for::<u64>(),
+                    generator.subschema_for::<String>(),
+                ]),
+                ..SubschemaValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
+/// What the return value of an auth call has to satisfy: `returned <op> value`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "op", content = "value", rename_all = "snake_case")]
+pub enum Expectation {
+    Equals(ReturnValue),
+    NotEquals(ReturnValue),
+    LessOrEqual(ReturnValue),
+    GreaterOrEqual(ReturnValue),
+    NonZero,
+    True,
+}
+
+/// Operator of an [`Expectation`], without its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpectationOp {
+    Equals,
+    NotEquals,
+    LessOrEqual,
+    GreaterOrEqual,
+    NonZero,
+    True,
+}
+
+impl Expectation {
+    #[must_use]
+    pub const fn op(&self) -> ExpectationOp {
+        match self {
+            Self::Equals(_) => ExpectationOp::Equals,
+            Self::NotEquals(_) => ExpectationOp::NotEquals,
+            Self::LessOrEqual(_) => ExpectationOp::LessOrEqual,
+            Self::GreaterOrEqual(_) => ExpectationOp::GreaterOrEqual,
+            Self::NonZero => ExpectationOp::NonZero,
+            Self::True => ExpectationOp::True,
+        }
+    }
+
+    /// Fails if `returned` can't be checked against the expectation (e.g. ordering bools).
+    pub fn evaluate(&self, returned: &ReturnValue) -> Result<bool> {
+        let satisfied = match self {
+            Self::Equals(expected) => returned == expected,
+            Self::NotEquals(expected) => returned != expected,
+            Self::LessOrEqual(expected) => returned.compare(expected)?.is_le(),
+            Self::GreaterOrEqual(expected) => returned.compare(expected)?.is_ge(),
+            Self::NonZero => {
+                ensure!(
+                    !matches!(returned, ReturnValue::Bool(_)),
+                    "non_zero expects an integer, got {returned}"
+                );
+                *returned != ReturnValue::Uint(U256::ZERO)
+            }
+            Self::True => *returned == ReturnValue::Bool(true),
+        };
+        Ok(satisfied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn return_value_serde() {
+        let values: Vec<ReturnValue> =
+            serde_json::from_str(r#"[true, 7, "42", "-0x1", "0x10", "-0"]"#).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                ReturnValue::Bool(true),
+                ReturnValue::from(7u128),
+                ReturnValue::from(42u128),
+                ReturnValue::from(-1i128),
+                ReturnValue::from(16u128),
+                ReturnValue::from(0u128),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&values).unwrap(),
+            r#"[true,"7","42","-1","16","0"]"#
+        );
+        assert!(serde_json::from_str::<ReturnValue>(r#""ten""#).is_err());
+    }
+
+    #[test]
+    fn expectations() {
+        let nonce = ReturnValue::from(1_753_218_716_000_000_003_679u128);
+        let stored = ReturnValue::from(1_753_218_716_000_000_003_680u128);
+        assert!(
+            Expectation::GreaterOrEqual(nonce)
+                .evaluate(&stored)
+                .unwrap()
+        );
+        assert!(!Expectation::LessOrEqual(nonce).evaluate(&stored).unwrap());
+        assert!(Expectation::LessOrEqual(nonce).evaluate(&nonce).unwrap());
+        assert!(
+            Expectation::LessOrEqual(ReturnValue::from(0u128))
+                .evaluate(&ReturnValue::from(-1i128))
+                .unwrap()
+        );
+
+        assert!(Expectation::NonZero.evaluate(&stored).unwrap());
+        assert!(
+            !Expectation::NonZero
+                .evaluate(&ReturnValue::from(0u128))
+                .unwrap()
+        );
+        assert!(
+            Expectation::NonZero
+                .evaluate(&ReturnValue::Bool(true))
+                .is_err()
+        );
+
+        assert!(
+            Expectation::True
+                .evaluate(&ReturnValue::Bool(true))
+                .unwrap()
+        );
+        assert!(
+            !Expectation::True
+                .evaluate(&ReturnValue::from(1u128))
+                .unwrap()
+        );
+        assert!(
+            Expectation::Equals(ReturnValue::from(-1i128))
+                .evaluate(&"-0x1".parse().unwrap())
+                .unwrap()
+        );
+        assert!(
+            Expectation::GreaterOrEqual(ReturnValue::Bool(true))
+                .evaluate(&ReturnValue::Bool(true))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn expectation_serde() {
+        let expectation: Expectation =
+            serde_json::from_str(r#"{"op":"greater_or_equal","value":"42"}"#).unwrap();
+        assert_eq!(
+            expectation,
+            Expectation::GreaterOrEqual(ReturnValue::from(42u128))
+        );
+        let expectation: Expectation = serde_json::from_str(r#"{"op":"true"}"#).unwrap();
+        assert_eq!(expectation, Expectation::True);
+    }
+}
@@ -2,36 +2,72 @@
 #![allow(clippy::missing_errors_doc)]
 //! Types for bridge validation, which include flows for deposit and completed withdrawal verification.
 
+pub mod balance;
 pub mod cosmos;
 pub mod evm;
+pub mod expectation;
 pub mod solana;
 pub mod stellar;
 pub mod ton;
+pub mod transaction;
 
 use crate::Base58;
-use crate::ChainId;
-use crate::bridge::cosmos::CosmosInputData;
+use crate::bridge::balance::TokenBalanceInputData;
+use crate::bridge::cosmos::{CosmosInputData, CosmosStorageQuery};
 use crate::bridge::solana::SolanaInputData;
 use crate::hex_wrapper::PrefixedHex;
-use anyhow::{Result, bail};
+use crate::{ChainFamily, ChainId};
+use anyhow::{Context, Result, bail, ensure};
 use borsh::BorshSerialize;
 use derive_more::{From, TryInto};
-use evm::EvmInputData;
+use evm::{EvmInputData, EvmLogQuery};
+use expectation::Expectation;
 use rlp::RlpStream;
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::DisplayFromStr;
 use serde_with::PickFirst;
 use serde_with::serde_as;
 use sha2::Digest;
-use stellar::StellarInputData;
+use stellar::{StellarInputData, StellarStorageQuery};
 use ton::TonInputData;
+use transaction::TransactionInputData;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
 pub enum HotVerifyBridge {
     Deposit(DepositAction),
     ClearCompletedWithdrawal(CompletedWithdrawalAction),
 }
 
+impl HotVerifyBridge {
+    const TAGS: [&str; 2] = ["Deposit", "ClearCompletedWithdrawal"];
+
+    /// Recognizes a bridge action among the user payloads of a proof.
+    /// Returns `Ok(None)` for payloads that don't look like a bridge action,
+    /// and an error for the ones that do, but can't be parsed.
+    pub fn from_user_payload(payload: &str) -> Result<Option<Self>> {
+        let Ok(serde_json::Value::Object(object)) = serde_json::from_str(payload) else {
+            return Ok(None);
+        };
+        if !object.keys().any(|key| Self::TAGS.contains(&key.as_str())) {
+            return Ok(None);
+        }
+        let bridge = serde_json::from_value(serde_json::Value::Object(object))
+            .context("Malformed bridge payload")?;
+        Ok(Some(bridge))
+    }
+
+    /// The message that has to be signed for this action.
+    pub fn build_challenge(&self) -> Result<[u8; 32]> {
+        match self {
+            Self::Deposit(action) => action.build_challenge_for_deposit(),
+            Self::ClearCompletedWithdrawal(action) => {
+                Ok(action.data.build_challenge_for_removal_owned())
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
 pub struct DepositAction {
     pub chain_id: ChainId,
@@ -41,6 +77,17 @@ pub struct DepositAction {
 
 impl DepositAction {
     pub fn build_challenge_for_deposit(&self) -> Result<[u8; 32]> {
+        // `DepositData::build_challenge_for_deposit` panics on those, and the action may come from a user.
+        ensure!(
+            self.chain_id != ChainId::Near,
+            "Deposit challenge can't be built for Near"
+        );
+        if self.chain_id == ChainId::Solana {
+            ensure!(
+                u64::try_from(self.data.get_amount()?).is_ok(),
+                "Amount doesn't fit into u64 for Solana"
+            );
+        }
         let challenge = DepositData::build_challenge_for_deposit(
             self.data.get_receiver()?,
             self.chain_id,
@@ -50,6 +97,25 @@ impl DepositAction {
         );
         Ok(challenge)
     }
+
+    /// Receiver in the form the destination bridge contract tracks withdrawn nonces by,
+    /// `None` for chains that track nonces globally (EVM, Stellar, Cosmos).
+    pub fn receiver_address(&self) -> Result<Option<String>> {
+        let address = match self.chain_id {
+            ChainId::Solana => bs58::encode(self.data.get_receiver()?).into_string(),
+            ChainId::Ton | ChainId::TON_V2 => {
+                let receiver = self.data.get_receiver()?;
+                ensure!(
+                    receiver.len() == 32,
+                    "TON receiver should be a 32-byte basechain account id, got {} bytes",
+                    receiver.len()
+                );
+                format!("0:{}", hex::encode(receiver))
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(address))
+    }
 }
 
 /// Many of the fields are optional, because there are different use cases for this structure.
@@ -212,25 +278,234 @@ pub enum HotVerifyResult {
     Result(bool),
 }
 
-#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+/// Legacy untagged input is parsed as the input of the `chain_id` family,
+/// see [`InputData::from_legacy`].
+#[derive(Debug, Serialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
 pub struct HotVerifyAuthCall {
     pub contract_id: String,
     pub method: String,
     pub chain_id: ChainId,
     pub input: InputData,
+    /// If set, the decoded return value of the call is checked against it,
+    /// instead of the implicit rule of the chain verifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expectation: Option<Expectation>,
+    /// Dot-separated path to the checked value in a structured return value, e.g. `nonce`
+    /// of a returned struct or `0` of a tuple. Only Soroban and TON return values are structured,
+    /// on TON the first segment is the index in the stack returned by the child contract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_field: Option<String>,
 }
 
-#[derive(
-    Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone, TryInto, From,
-)]
+impl<'de> Deserialize<'de> for HotVerifyAuthCall {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            contract_id: String,
+            method: String,
+            chain_id: ChainId,
+            input: serde_json::Value,
+            #[serde(default)]
+            expectation: Option<Expectation>,
+            #[serde(default)]
+            return_field: Option<String>,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        let input = match raw.chain_id.family() {
+            Some(family) if raw.input.get(InputData::TAG).is_none() => {
+                InputData::from_legacy(family, raw.input)
+            }
+            _ => InputData::deserialize(raw.input),
+        }
+        .map_err(D::Error::custom)?;
+        Ok(Self {
+            contract_id: raw.contract_id,
+            method: raw.method,
+            chain_id: raw.chain_id,
+            input,
+            expectation: raw.expectation,
+            return_field: raw.return_field,
+        })
+    }
+}
+
+impl HotVerifyAuthCall {
+    /// Checks that the input data is meant for the family of `chain_id`, before calling any RPC.
+    pub fn input_family(&self) -> Result<ChainFamily> {
+        let expected = self
+            .chain_id
+            .family()
+            .with_context(|| format!("Unknown chain {} in auth call", self.chain_id))?;
+        if let Some(actual) = self.input.family() {
+            ensure!(
+                expected == actual,
+                "Auth call to chain {} expects {expected:?} input data, got {actual:?}",
+                self.chain_id
+            );
+        }
+        Ok(expected)
+    }
+}
+
+/// Arguments of an auth call on the target chain.
+///
+/// The canonical format is tagged with the chain family: `{"family": "ton", "data": {...}}`.
+/// Data without the tag is the way legacy auth contracts return it: in an auth call it's parsed
+/// as the input of the chain family, on its own it's parsed untagged, which may pick a variant
+/// of another family for ambiguous data, see [`HotVerifyAuthCall::input_family`].
+/// Built-in checks ([`EvmLogQuery`], [`StellarStorageQuery`], [`CosmosStorageQuery`],
+/// [`TokenBalanceInputData`], [`TransactionInputData`]) are accepted in the tagged format only.
+#[derive(Debug, Serialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone, TryInto, From)]
 #[try_into(owned, ref, ref_mut)]
-#[serde(untagged)]
+#[serde(tag = "family", content = "data", rename_all = "snake_case")]
 pub enum InputData {
     Evm(EvmInputData),
     Stellar(StellarInputData),
     Ton(TonInputData),
     Solana(SolanaInputData),
     Cosmos(CosmosInputData),
+    EvmLogs(EvmLogQuery),
+    StellarStorage(StellarStorageQuery),
+    CosmosStorage(CosmosStorageQuery),
+    TokenBalance(TokenBalanceInputData),
+    Transaction(TransactionInputData),
+}
+
+/// Variant of [`InputData`], named after its tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputKind {
+    Evm,
+    Stellar,
+    Ton,
+    Solana,
+    Cosmos,
+    EvmLogs,
+    StellarStorage,
+    CosmosStorage,
+    TokenBalance,
+    Transaction,
+}
+
+impl InputKind {
+    /// A call of a method of the auth contract, as opposed to a built-in check.
+    #[must_use]
+    pub const fn is_contract_call(self) -> bool {
+        matches!(
+            self,
+            Self::Evm | Self::Stellar | Self::Ton | Self::Solana | Self::Cosmos
+        )
+    }
+}
+
+impl InputData {
+    const TAG: &str = "family";
+
+    #[must_use]
+    pub const fn kind(&self) -> InputKind {
+        match self {
+            Self::Evm(_) => InputKind::Evm,
+            Self::Stellar(_) => InputKind::Stellar,
+            Self::Ton(_) => InputKind::Ton,
+            Self::Solana(_) => InputKind::Solana,
+            Self::Cosmos(_) => InputKind::Cosmos,
+            Self::EvmLogs(_) => InputKind::EvmLogs,
+            Self::StellarStorage(_) => InputKind::StellarStorage,
+            Self::CosmosStorage(_) => InputKind::CosmosStorage,
+            Self::TokenBalance(_) => InputKind::TokenBalance,
+            Self::Transaction(_) => InputKind::Transaction,
+        }
+    }
+
+    /// Legacy untagged contract call input of a chain of `family`.
+    pub fn from_legacy(
+        family: ChainFamily,
+        value: serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        let input = match family {
+            ChainFamily::Evm => Self::Evm(serde_json::from_value(value)?),
+            ChainFamily::Stellar => Self::Stellar(serde_json::from_value(value)?),
+            ChainFamily::Ton => Self::Ton(serde_json::from_value(value)?),
+            ChainFamily::Solana => Self::Solana(serde_json::from_value(value)?),
+            ChainFamily::Cosmos => Self::Cosmos(serde_json::from_value(value)?),
+            ChainFamily::Near => {
+                return Err(serde_json::Error::custom(
+                    "Auth calls to NEAR take tagged built-in checks only",
+                ));
+            }
+        };
+        Ok(input)
+    }
+
+    /// Family of chains the input is meant for, `None` for inputs that fit any chain.
+    #[must_use]
+    pub fn family(&self) -> Option<ChainFamily> {
+        let family = match self {
+            Self::Evm(_) | Self::EvmLogs(_) => ChainFamily::Evm,
+            Self::Stellar(_) | Self::StellarStorage(_) => ChainFamily::Stellar,
+            Self::Ton(_) => ChainFamily::Ton,
+            Self::Solana(_) => ChainFamily::Solana,
+            Self::Cosmos(_) | Self::CosmosStorage(_) => ChainFamily::Cosmos,
+            Self::Transaction(input) => input.transaction.family(),
+            Self::TokenBalance(_) => return None,
+        };
+        Some(family)
+    }
+}
+
+impl<'de> Deserialize<'de> for InputData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(tag = "family", content = "data", rename_all = "snake_case")]
+        enum Tagged {
+            Evm(EvmInputData),
+            Stellar(StellarInputData),
+            Ton(TonInputData),
+            Solana(SolanaInputData),
+            Cosmos(CosmosInputData),
+            EvmLogs(EvmLogQuery),
+            StellarStorage(StellarStorageQuery),
+            CosmosStorage(CosmosStorageQuery),
+            TokenBalance(TokenBalanceInputData),
+            Transaction(TransactionInputData),
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Legacy {
+            Evm(EvmInputData),
+            Stellar(StellarInputData),
+            Ton(TonInputData),
+            Solana(SolanaInputData),
+            Cosmos(CosmosInputData),
+        }
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let input = if value.get(Self::TAG).is_some() {
+            match Tagged::deserialize(value).map_err(D::Error::custom)? {
+                Tagged::Evm(data) => Self::Evm(data),
+                Tagged::Stellar(data) => Self::Stellar(data),
+                Tagged::Ton(data) => Self::Ton(data),
+                Tagged::Solana(data) => Self::Solana(data),
+                Tagged::Cosmos(data) => Self::Cosmos(data),
+                Tagged::EvmLogs(data) => Self::EvmLogs(data),
+                Tagged::StellarStorage(data) => Self::StellarStorage(data),
+                Tagged::CosmosStorage(data) => Self::CosmosStorage(data),
+                Tagged::TokenBalance(data) => Self::TokenBalance(data),
+                Tagged::Transaction(data) => Self::Transaction(data),
+            }
+        } else {
+            match Legacy::deserialize(value).map_err(D::Error::custom)? {
+                Legacy::Evm(data) => Self::Evm(data),
+                Legacy::Stellar(data) => Self::Stellar(data),
+                Legacy::Ton(data) => Self::Ton(data),
+                Legacy::Solana(data) => Self::Solana(data),
+                Legacy::Cosmos(data) => Self::Cosmos(data),
+            }
+        };
+        Ok(input)
+    }
 }
 
 impl HotVerifyResult {
@@ -246,10 +521,163 @@ impl HotVerifyResult {
 
 #[cfg(test)]
 mod tests {
-    use crate::bridge::CompletedWithdrawal;
+    use crate::bridge::balance::TokenBalanceInputData;
+    use crate::bridge::cosmos::CosmosInputData;
+    use crate::bridge::evm::EvmInputData;
+    use crate::bridge::stellar::{StellarInputArg, StellarInputData};
+    use crate::bridge::{
+        CompletedWithdrawal, DepositAction, DepositData, HotVerifyAuthCall, HotVerifyBridge,
+        InputData,
+    };
+    use crate::{ChainFamily, ChainId};
     use rlp::RlpStream;
     use sha2::Digest;
 
+    #[test]
+    fn input_data_tagged_and_legacy() {
+        let expected = InputData::Cosmos(CosmosInputData::IsExecuted { nonce: 1 });
+        let tagged: InputData =
+            serde_json::from_str(r#"{"family":"cosmos","data":{"is_executed":{"nonce":"1"}}}"#)
+                .unwrap();
+        assert_eq!(tagged, expected);
+        let legacy: InputData = serde_json::from_str(r#"{"is_executed":{"nonce":"1"}}"#).unwrap();
+        assert_eq!(legacy, expected);
+        assert_eq!(
+            serde_json::from_str::<InputData>(&serde_json::to_string(&expected).unwrap()).unwrap(),
+            expected
+        );
+
+        // The tag is authoritative, no fallback to another family.
+        assert!(
+            serde_json::from_str::<InputData>(
+                r#"{"family":"evm","data":{"is_executed":{"nonce":"1"}}}"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn legacy_input_follows_the_chain_family() {
+        let auth_call = |chain_id: u64, input: serde_json::Value| {
+            serde_json::from_value::<HotVerifyAuthCall>(serde_json::json!({
+                "contract_id": "contract",
+                "method": "hot_verify",
+                "chain_id": chain_id,
+                "input": input,
+            }))
+            .unwrap()
+            .input
+        };
+        // also a valid list of EVM arguments
+        assert_eq!(
+            auth_call(1100, serde_json::json!([])),
+            InputData::Stellar(StellarInputData(vec![]))
+        );
+        assert_eq!(
+            auth_call(
+                1100,
+                serde_json::json!([{"type": "bytes", "value": "0x00"}])
+            ),
+            InputData::Stellar(StellarInputData(vec![StellarInputArg::Bytes(vec![0])]))
+        );
+        assert_eq!(
+            auth_call(56, serde_json::json!([])),
+            InputData::Evm(EvmInputData::Args(vec![]))
+        );
+        // the tag still wins
+        assert_eq!(
+            auth_call(1100, serde_json::json!({"family": "evm", "data": []})),
+            InputData::Evm(EvmInputData::Args(vec![]))
+        );
+    }
+
+    #[test]
+    fn input_family_must_match_chain() {
+        let auth_call = |chain_id| HotVerifyAuthCall {
+            contract_id: "contract".to_string(),
+            method: "hot_verify".to_string(),
+            chain_id,
+            input: InputData::Cosmos(CosmosInputData::IsExecuted { nonce: 1 }),
+            expectation: None,
+            return_field: None,
+        };
+        assert_eq!(
+            auth_call(ChainId::Evm(4_444_118)).input_family().unwrap(),
+            ChainFamily::Cosmos
+        );
+        let balance_call = HotVerifyAuthCall {
+            chain_id: ChainId::Near,
+            input: InputData::TokenBalance(TokenBalanceInputData {
+                holder: "alice.near".to_string(),
+                min_balance: 1,
+            }),
+            ..auth_call(ChainId::Near)
+        };
+        assert_eq!(balance_call.input_family().unwrap(), ChainFamily::Near);
+        let err = auth_call(ChainId::Evm(56)).input_family().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expects Evm input data, got Cosmos")
+        );
+    }
+
+    #[test]
+    fn receiver_address_per_chain() {
+        let receiver = bs58::decode("5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho")
+            .into_vec()
+            .unwrap();
+        let action = |chain_id| DepositAction {
+            chain_id,
+            data: DepositData {
+                receiver: Some(receiver.clone()),
+                ..DepositData::from_nonce(1)
+            },
+        };
+        assert_eq!(
+            action(ChainId::Solana).receiver_address().unwrap().unwrap(),
+            "5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho"
+        );
+        assert_eq!(
+            action(ChainId::TON_V2).receiver_address().unwrap().unwrap(),
+            format!("0:{}", hex::encode(&receiver))
+        );
+        assert!(
+            action(ChainId::Evm(56))
+                .receiver_address()
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn bridge_payload_recognition() {
+        let payload = r#"{"ClearCompletedWithdrawal":{"chain_id":1100,"nonce":"42"}}"#;
+        let bridge = HotVerifyBridge::from_user_payload(payload)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            bridge.build_challenge().unwrap(),
+            CompletedWithdrawal::build_challenge_for_removal(42)
+        );
+
+        let payload = r#"{"auth_method":0,"signatures":["HZUhhJamfp8GJLL8gEa2F2qZ6TXPu4PYzzWk"]}"#;
+        assert!(
+            HotVerifyBridge::from_user_payload(payload)
+                .unwrap()
+                .is_none()
+        );
+        assert!(HotVerifyBridge::from_user_payload("42").unwrap().is_none());
+
+        let payload = r#"{"Deposit":{"chain_id":56}}"#;
+        assert!(HotVerifyBridge::from_user_payload(payload).is_err());
+
+        let payload = r#"{"Deposit":{"chain_id":0,"receiver":"11","token_id":"11","amount":"1","nonce":"1"}}"#;
+        let bridge = HotVerifyBridge::from_user_payload(payload)
+            .unwrap()
+            .unwrap();
+        assert!(bridge.build_challenge().is_err());
+    }
+
     #[test]
     fn completed_withdrawal_challenge_consistency() {
         let nonce = 42u128;
@@ -10,6 +10,92 @@ use solana_pubkey::Pubkey;
 pub enum SolanaInputData {
     Deposit(DepositWithProof),
     CheckCompletedWithdrawal(CompletedWithdrawal),
+    Instruction(AnchorInstruction),
+    AccountField(AnchorAccountField),
+}
+
+/// Simulation of an arbitrary Anchor instruction of the auth call program, which succeeds
+/// if the instruction doesn't fail. The instruction handler is the auth call method, so the
+/// data is `anchor::instruction_discriminator(method)` followed by `args`.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct AnchorInstruction {
+    /// Borsh-encoded instruction arguments.
+    #[serde_as(as = "Hex")]
+    #[schemars(with = "String")]
+    #[serde(default)]
+    pub args: Vec<u8>,
+    pub accounts: Vec<AccountSpec>,
+    /// Base58 fee payer of the simulated transaction, the first signer of `accounts` if not set.
+    /// It has to exist on chain, but doesn't sign: signatures aren't verified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+    #[serde(default)]
+    pub message_version: MessageVersion,
+    /// Base58 address lookup tables to compile a v0 message against.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub address_lookup_tables: Vec<String>,
+}
+
+/// A field of an Anchor account of the auth call program, decoded with the program's IDL
+/// (see `NetworkProfile::solana_idls`). The auth call returns the field, so its
+/// `expectation` is the predicate, e.g. `User.last_withdraw_nonce >= nonce`.
+/// Without an expectation the field has to be `true`.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct AnchorAccountField {
+    /// Account type as named in the IDL, e.g. `User`.
+    pub account_type: String,
+    pub address: AccountAddress,
+    /// Dot-separated path to the field in the account, e.g. `last_withdraw_nonce`.
+    pub field: String,
+}
+
+#[derive(
+    Debug, Default, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone, Copy,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageVersion {
+    #[default]
+    Legacy,
+    V0,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct AccountSpec {
+    pub address: AccountAddress,
+    #[serde(default)]
+    pub is_signer: bool,
+    #[serde(default)]
+    pub is_writable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountAddress {
+    /// Base58 public key.
+    Pubkey(String),
+    /// Program derived address of the auth call program, or of `program_id` if set.
+    Pda {
+        seeds: Vec<Seed>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        program_id: Option<String>,
+    },
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Seed {
+    /// UTF-8 bytes of the string, e.g. `"user"`.
+    Utf8(String),
+    /// Raw bytes in hex, e.g. big-endian integers.
+    Bytes(
+        #[serde_as(as = "Hex")]
+        #[schemars(with = "String")]
+        Vec<u8>,
+    ),
+    /// 32 bytes of a base58 public key.
+    Pubkey(String),
 }
 
 #[serde_as]
@@ -182,10 +268,106 @@ impl DepositWithProof {
     }
 }
 
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey> {
+    pubkey
+        .parse()
+        .map_err(|e| anyhow!("Invalid public key {pubkey}: {e}"))
+}
+
+impl Seed {
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        let bytes = match self {
+            Self::Utf8(s) => s.as_bytes().to_vec(),
+            Self::Bytes(bytes) => bytes.clone(),
+            Self::Pubkey(pubkey) => parse_pubkey(pubkey)?.to_bytes().to_vec(),
+        };
+        Ok(bytes)
+    }
+}
+
+impl AccountAddress {
+    pub fn resolve(&self, program_id: &Address) -> Result<Address> {
+        match self {
+            Self::Pubkey(pubkey) => parse_pubkey(pubkey),
+            Self::Pda {
+                seeds,
+                program_id: seeds_program_id,
+            } => {
+                let program_id = match seeds_program_id {
+                    Some(program_id) => parse_pubkey(program_id)?,
+                    None => *program_id,
+                };
+                let seeds = seeds
+                    .iter()
+                    .map(Seed::to_bytes)
+                    .collect::<Result<Vec<_>>>()?;
+                let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+                let (pda, _bump) = Pubkey::try_find_program_address(&seeds, &program_id)
+                    .ok_or_else(|| anyhow!("No PDA of {program_id} for seeds {seeds:?}"))?;
+                Ok(pda)
+            }
+        }
+    }
+}
+
+impl AnchorInstruction {
+    pub fn get_instruction(&self, program_id: &Address, method_name: &str) -> Result<Instruction> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                let pubkey = account.address.resolve(program_id)?;
+                Ok(if account.is_writable {
+                    AccountMeta::new(pubkey, account.is_signer)
+                } else {
+                    AccountMeta::new_readonly(pubkey, account.is_signer)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut data = Vec::with_capacity(8 + self.args.len());
+        data.extend_from_slice(&anchor::instruction_discriminator(method_name));
+        data.extend_from_slice(&self.args);
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data,
+        })
+    }
+
+    pub fn get_payer(&self, program_id: &Address) -> Result<Address> {
+        if let Some(payer) = &self.payer {
+            return parse_pubkey(payer);
+        }
+        self.accounts
+            .iter()
+            .find(|account| account.is_signer)
+            .ok_or_else(|| anyhow!("No payer and no signer accounts in the instruction"))?
+            .address
+            .resolve(program_id)
+    }
+
+    pub fn get_address_lookup_tables(&self) -> Result<Vec<Address>> {
+        if self.message_version == MessageVersion::Legacy && !self.address_lookup_tables.is_empty()
+        {
+            return Err(anyhow!("Address lookup tables require a v0 message"));
+        }
+        self.address_lookup_tables
+            .iter()
+            .map(|table| parse_pubkey(table))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::bridge::solana::{DepositData, DepositWithProof};
+    use crate::bridge::solana::{
+        AccountAddress, AccountSpec, AnchorInstruction, DepositData, DepositWithProof,
+        MessageVersion, Seed, SolanaInputData,
+    };
     use anyhow::Result;
+    use borsh::BorshSerialize;
     use serde_json::json;
     use solana_pubkey::Pubkey;
     use std::str::FromStr;
@@ -248,4 +430,79 @@ mod tests {
         assert_eq!(actual.to_string(), expected);
         Ok(())
     }
+
+    #[test]
+    fn anchor_instruction_matches_deposit() -> Result<()> {
+        let program_id = Pubkey::from_str("8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC")?;
+        let deposit_with_proof = get_deposit_with_proof();
+        let deposit_data = &deposit_with_proof.deposit_data;
+        let sender = "5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho".to_string();
+        let pda = |seeds| AccountAddress::Pda {
+            seeds,
+            program_id: None,
+        };
+        let account = |address, is_writable| AccountSpec {
+            address,
+            is_signer: false,
+            is_writable,
+        };
+        let mut args = Vec::new();
+        BorshSerialize::serialize(&deposit_with_proof, &mut args)?;
+
+        let input = json!({
+            "Instruction": {
+                "args": hex::encode(&args),
+                "accounts": [
+                    { "address": { "pubkey": sender }, "is_signer": true, "is_writable": true },
+                ],
+            }
+        });
+        let SolanaInputData::Instruction(mut instruction) = serde_json::from_value(input)? else {
+            panic!("expected an instruction");
+        };
+        assert_eq!(instruction.message_version, MessageVersion::Legacy);
+        instruction.accounts.extend([
+            account(
+                pda(vec![
+                    Seed::Utf8("deposit".to_string()),
+                    Seed::Bytes(deposit_data.nonce.to_be_bytes().to_vec()),
+                    Seed::Pubkey(sender.clone()),
+                    Seed::Pubkey("BJu6S7gT4gnx7AXPnghM7aYiS5dPfSUixqAZJq1Uqf4V".to_string()),
+                    Seed::Pubkey("BYPsjxa3YuZESQz1dKuBw1QSFCSpecsm8nCQhY5xbU1Z".to_string()),
+                    Seed::Bytes(10_000_000u64.to_be_bytes().to_vec()),
+                ]),
+                false,
+            ),
+            account(
+                pda(vec![Seed::Utf8("user".to_string()), Seed::Pubkey(sender)]),
+                false,
+            ),
+            account(pda(vec![Seed::Utf8("state".to_string())]), true),
+        ]);
+
+        let method_name = "hot_verify_deposit";
+        assert_eq!(
+            instruction.get_instruction(&program_id, method_name)?,
+            deposit_with_proof.get_instruction(&program_id, method_name)?
+        );
+        assert_eq!(
+            instruction.get_payer(&program_id)?.to_string(),
+            "5eMysQ7ywu4D8pmN5RtDoPxbu5YbiEThQy8gaBcmMoho"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lookup_tables_require_v0() {
+        let mut instruction = AnchorInstruction {
+            args: vec![],
+            accounts: vec![],
+            payer: None,
+            message_version: MessageVersion::Legacy,
+            address_lookup_tables: vec!["AddressLookupTab1e1111111111111111111111111".to_string()],
+        };
+        assert!(instruction.get_address_lookup_tables().is_err());
+        instruction.message_version = MessageVersion::V0;
+        assert_eq!(instruction.get_address_lookup_tables().unwrap().len(), 1);
+    }
 }
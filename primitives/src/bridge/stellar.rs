@@ -1,9 +1,14 @@
 use crate::integer::U128String;
+use anyhow::{anyhow, ensure};
 use serde::{Deserialize, Serialize};
 use serde_hex::SerHexSeq;
 use serde_hex::StrictPfx;
-use serde_with::serde_as;
-use stellar_xdr::curr::{Limited, Limits, ReadXdr, ScBytes, ScString, ScVal, UInt128Parts};
+use serde_with::{DisplayFromStr, serde_as};
+use std::str::FromStr;
+use stellar_xdr::curr::{
+    Int128Parts, Limited, Limits, ReadXdr, ScAddress, ScBytes, ScMap, ScMapEntry, ScString,
+    ScSymbol, ScVal, ScVec, UInt128Parts,
+};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
@@ -20,6 +25,37 @@ pub enum StellarInputArg {
     #[serde(rename = "u128")]
     #[schemars(with = "String")]
     U128(#[serde_as(as = "U128String")] u128),
+    /// `BytesN<32>`, e.g. a hash.
+    #[serde(rename = "bytes32")]
+    #[serde(with = "SerHexSeq::<StrictPfx>")]
+    #[schemars(with = "String")]
+    Bytes32(Vec<u8>),
+    #[serde(rename = "u64")]
+    #[schemars(with = "String")]
+    U64(#[serde_as(as = "DisplayFromStr")] u64),
+    #[serde(rename = "i128")]
+    #[schemars(with = "String")]
+    I128(#[serde_as(as = "DisplayFromStr")] i128),
+    /// Up to 32 characters of `[a-zA-Z0-9_]`, e.g. a variant of a `DataKey` enum.
+    #[serde(rename = "symbol")]
+    Symbol(String),
+    /// Account (`G...`) or contract (`C...`) strkey.
+    #[serde(rename = "address")]
+    Address(String),
+    #[serde(rename = "vec")]
+    Vec(Vec<StellarInputArg>),
+    /// Entries may come in any order, they are sorted by key as Soroban requires.
+    #[serde(rename = "map")]
+    Map(Vec<StellarMapEntry>),
+    /// Any `ScVal` as base64 XDR, e.g. a `DataKey` enum used as a storage key.
+    #[serde(rename = "xdr")]
+    Xdr(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct StellarMapEntry {
+    pub key: StellarInputArg,
+    pub value: StellarInputArg,
 }
 
 impl TryFrom<StellarInputArg> for ScVal {
@@ -34,6 +70,57 @@ impl TryFrom<StellarInputArg> for ScVal {
                 let mut limited = Limited::new(bytes.as_slice(), Limits::none());
                 Ok(ScVal::U128(UInt128Parts::read_xdr(&mut limited)?))
             }
+            StellarInputArg::Bytes32(data) => {
+                ensure!(data.len() == 32, "Expected 32 bytes, got {}", data.len());
+                Ok(ScVal::Bytes(ScBytes(data.try_into()?)))
+            }
+            StellarInputArg::U64(data) => Ok(ScVal::U64(data)),
+            StellarInputArg::I128(data) => {
+                let bytes = data.to_be_bytes();
+                Ok(ScVal::I128(Int128Parts {
+                    hi: i64::from_be_bytes(bytes[..8].try_into()?),
+                    lo: u64::from_be_bytes(bytes[8..].try_into()?),
+                }))
+            }
+            StellarInputArg::Symbol(symbol) => {
+                ensure!(
+                    symbol
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                    "Invalid symbol {symbol:?}, only [a-zA-Z0-9_] is allowed"
+                );
+                let symbol = symbol
+                    .as_str()
+                    .try_into()
+                    .map_err(|_| anyhow!("Symbol {symbol:?} is longer than 32 characters"))?;
+                Ok(ScVal::Symbol(ScSymbol(symbol)))
+            }
+            StellarInputArg::Address(address) => Ok(ScVal::Address(ScAddress::from_str(&address)?)),
+            StellarInputArg::Vec(items) => {
+                let items = items
+                    .into_iter()
+                    .map(ScVal::try_from)
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                Ok(ScVal::Vec(Some(ScVec(items.try_into()?))))
+            }
+            StellarInputArg::Map(entries) => {
+                let mut entries = entries
+                    .into_iter()
+                    .map(|entry| {
+                        Ok(ScMapEntry {
+                            key: entry.key.try_into()?,
+                            val: entry.value.try_into()?,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                entries.sort_by(|a, b| a.key.cmp(&b.key));
+                ensure!(
+                    entries.windows(2).all(|pair| pair[0].key != pair[1].key),
+                    "Duplicate map keys"
+                );
+                Ok(ScVal::Map(Some(ScMap(entries.try_into()?))))
+            }
+            StellarInputArg::Xdr(xdr) => Ok(ScVal::from_xdr_base64(xdr, Limits::none())?),
         }
     }
 }
@@ -41,6 +128,25 @@ impl TryFrom<StellarInputArg> for ScVal {
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
 pub struct StellarInputData(pub Vec<StellarInputArg>);
 
+/// Auth call answered by reading a contract storage entry with `getLedgerEntries` instead of
+/// simulating a call, for plain lookups such as executed nonces. The auth call method is unused.
+///
+/// Without an expectation, a `bool` entry is its value and any other entry is `true` if it exists.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct StellarStorageQuery {
+    pub durability: StellarStorageDurability,
+    pub key: StellarInputArg,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum StellarStorageDurability {
+    Persistent,
+    Temporary,
+    /// Instance storage, a key of the map stored in the contract instance entry.
+    Instance,
+}
+
 impl StellarInputData {
     pub fn from_parts(msg_hash: String, user_payload: String) -> anyhow::Result<Self> {
         Ok(Self(vec![
@@ -60,9 +166,15 @@ impl TryFrom<StellarInputData> for Vec<ScVal> {
 
 #[cfg(test)]
 mod tests {
-    use crate::bridge::stellar::{StellarInputArg, StellarInputData};
+    use crate::ChainFamily;
+    use crate::bridge::InputData;
+    use crate::bridge::stellar::{
+        StellarInputArg, StellarInputData, StellarMapEntry, StellarStorageDurability,
+        StellarStorageQuery,
+    };
     use anyhow::Result;
-    use stellar_xdr::curr::ScVal;
+    use serde_json::json;
+    use stellar_xdr::curr::{Int128Parts, Limits, ScSymbol, ScVal, ScVec, WriteXdr};
 
     #[test]
     fn check_u128() -> Result<()> {
@@ -73,6 +185,91 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn check_address() -> Result<()> {
+        let x: ScVal = StellarInputArg::Address(
+            "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG".to_string(),
+        )
+        .try_into()?;
+        assert!(matches!(x, ScVal::Address(_)));
+        assert!(ScVal::try_from(StellarInputArg::Address("alice".to_string())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn check_composite_args() -> Result<()> {
+        let arg: StellarInputArg = serde_json::from_value(json!({
+            "type": "map",
+            "value": [
+                { "key": { "type": "symbol", "value": "nonce" }, "value": { "type": "u64", "value": "7" } },
+                { "key": { "type": "symbol", "value": "amount" }, "value": { "type": "i128", "value": "-3" } },
+                {
+                    "key": { "type": "symbol", "value": "hashes" },
+                    "value": { "type": "vec", "value": [{ "type": "bytes32", "value": format!("0x{}", "ab".repeat(32)) }] }
+                }
+            ]
+        }))?;
+        let ScVal::Map(Some(map)) = ScVal::try_from(arg)? else {
+            panic!("expected a map")
+        };
+        let symbol = |s: &str| ScVal::Symbol(ScSymbol(s.try_into().unwrap()));
+        let keys: Vec<_> = map.iter().map(|entry| entry.key.clone()).collect();
+        assert_eq!(
+            keys,
+            vec![symbol("amount"), symbol("hashes"), symbol("nonce")]
+        );
+        assert_eq!(
+            map[0].val,
+            ScVal::I128(Int128Parts {
+                hi: -1,
+                lo: u64::MAX - 2
+            })
+        );
+        assert_eq!(map[2].val, ScVal::U64(7));
+
+        assert!(ScVal::try_from(StellarInputArg::Bytes32(vec![0; 31])).is_err());
+        assert!(ScVal::try_from(StellarInputArg::Symbol("not a symbol".to_string())).is_err());
+        assert!(ScVal::try_from(StellarInputArg::Symbol("a".repeat(33))).is_err());
+        let duplicate = StellarMapEntry {
+            key: StellarInputArg::Symbol("a".to_string()),
+            value: StellarInputArg::U64(1),
+        };
+        assert!(ScVal::try_from(StellarInputArg::Map(vec![duplicate.clone(), duplicate])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn check_storage_query() -> Result<()> {
+        let key = ScVal::Vec(Some(ScVec(
+            vec![
+                ScVal::Symbol(ScSymbol("Executed".try_into()?)),
+                StellarInputArg::U128(42).try_into()?,
+            ]
+            .try_into()?,
+        )));
+        let input = json!({
+            "family": "stellar_storage",
+            "data": {
+                "durability": "persistent",
+                "key": { "type": "xdr", "value": key.to_xdr_base64(Limits::none())? }
+            }
+        });
+        let input: InputData = serde_json::from_value(input)?;
+        assert_eq!(input.family(), Some(ChainFamily::Stellar));
+        let InputData::StellarStorage(query) = input else {
+            panic!("expected a storage query, got {input:?}")
+        };
+        assert_eq!(query.durability, StellarStorageDurability::Persistent);
+        assert_eq!(ScVal::try_from(query.key)?, key);
+
+        let query = StellarStorageQuery {
+            durability: StellarStorageDurability::Instance,
+            key: StellarInputArg::Xdr("not xdr".to_string()),
+        };
+        assert!(ScVal::try_from(query.key).is_err());
+        Ok(())
+    }
+
     #[test]
     fn check_input_data() -> Result<()> {
         let x = r#"
@@ -6,7 +6,10 @@
 //! This is because there's no developer-friendly hash-map support on TON at the moment.
 //!
 //! This logic implements TOP API V2 data format for `runGetMethod`: <https://toncenter.com/api/v2>/
+//! Cells are exchanged either as JSON objects (`{"data": {"b64", "len"}, "refs": [...], "special"}`)
+//! or as base64 bags of cells (BoC), both keep the referenced cells.
 
+use crate::bridge::DepositData;
 use crate::integer::U128String;
 use anyhow::Context;
 use base64::Engine;
@@ -15,16 +18,19 @@ use derive_more::{Deref, From};
 use serde::ser::{SerializeMap, SerializeTuple};
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use serde_json::json;
-use serde_with::serde_as;
+use serde_with::{DisplayFromStr, serde_as};
 use std::str::FromStr;
 use tonlib_core::TonAddress;
-use tonlib_core::cell::{ArcCell, Cell, CellBuilder};
+use tonlib_core::cell::{ArcCell, BagOfCells, Cell, CellBuilder};
 use tonlib_core::tlb_types::tlb::TLB;
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
 pub struct TonInputData {
+    #[serde(default)]
     pub treasury_call_args: Vec<StackItem>,
+    #[serde(default)]
     pub child_call_method: String,
+    #[serde(default)]
     pub child_call_args: Vec<StackItem>,
     pub action: Action,
 }
@@ -38,6 +44,35 @@ pub enum Action {
         #[serde_as(as = "U128String")]
         nonce: u128,
     },
+    /// Deposit confirmed by the jetton transfer to the treasury instead of the get-methods,
+    /// so it works before the child contract is deployed. The call fields are ignored.
+    JettonTransfer(JettonTransfer),
+}
+
+/// The treasury transaction that got the `transfer_notification` of the deposit
+/// from the treasury's jetton wallet:
+/// `transfer_notification#7362d09c query_id:uint64 amount:(VarUInteger 16) sender:MsgAddress
+/// forward_payload:(Either Cell ^Cell)`, where the forward payload is
+/// `nonce:uint128 receiver:^Cell` with the receiver bytes in a snake cell.
+///
+/// `sender` and `token_id` of the deposit data are 32-byte basechain account ids
+/// of the user and the jetton master.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct JettonTransfer {
+    pub deposit_data: DepositData,
+    /// The last transactions of the treasury are searched for the nonce if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionRef>,
+}
+
+/// Transaction of the treasury with logical time `lt` and base64 `hash`.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct TransactionRef {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schemars(with = "String")]
+    pub lt: u64,
+    pub hash: String,
 }
 
 #[derive(Debug, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
@@ -45,6 +80,10 @@ pub enum StackItem {
     Cell(SerializableCell),
     Slice(SerializableCell),
     Num(String),
+    /// Only returned by get-methods, e.g. for `(int, cell)` results.
+    Tuple(Vec<StackItem>),
+    /// Only returned by get-methods.
+    List(Vec<StackItem>),
 }
 
 #[derive(Debug, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
@@ -68,6 +107,8 @@ impl<'a> Deserialize<'a> for SerializableCell {
         #[derive(Deserialize)]
         struct Helper {
             data: DataField,
+            #[serde(default)]
+            refs: Vec<SerializableCell>,
             special: bool,
         }
 
@@ -76,9 +117,10 @@ impl<'a> Deserialize<'a> for SerializableCell {
         let bytes = BASE64_STANDARD
             .decode(&helper.data.b64)
             .map_err(de::Error::custom)?;
+        let refs = helper.refs.into_iter().map(|cell| cell.0).collect();
 
         Ok(SerializableCell(ArcCell::new(
-            Cell::new(bytes, helper.data.len, vec![], helper.special).map_err(de::Error::custom)?,
+            Cell::new(bytes, helper.data.len, refs, helper.special).map_err(de::Error::custom)?,
         )))
     }
 }
@@ -95,12 +137,28 @@ impl Serialize for SerializableCell {
                 "len": self.bit_len()
             }),
         )?;
-        map.serialize_entry("refs", &json!([]))?;
+        let refs: Vec<SerializableCell> = self
+            .references()
+            .iter()
+            .map(|cell| SerializableCell(cell.clone()))
+            .collect();
+        map.serialize_entry("refs", &refs)?;
         map.serialize_entry("special", &self.0.is_exotic())?;
         map.end()
     }
 }
 
+impl SerializableCell {
+    pub fn from_boc_b64(boc: &str) -> anyhow::Result<Self> {
+        Ok(Self(ArcCell::new(Cell::from_boc_b64(boc)?)))
+    }
+
+    pub fn to_boc_b64(&self) -> anyhow::Result<String> {
+        let boc = BagOfCells::from_root(Cell::clone(&self.0)).serialize(true)?;
+        Ok(BASE64_STANDARD.encode(boc))
+    }
+}
+
 impl StackItem {
     #[must_use]
     pub fn from_nonce(nonce: String) -> Self {
@@ -140,6 +198,11 @@ impl Serialize for StackItem {
                 tup.serialize_element("num")?;
                 tup.serialize_element(num)?;
             }
+            StackItem::Tuple(_) | StackItem::List(_) => {
+                return Err(serde::ser::Error::custom(
+                    "tuples and lists can't be passed to get-methods",
+                ));
+            }
         }
         tup.end()
     }
@@ -158,7 +221,7 @@ impl<'de> Deserialize<'de> for ResponseStackItem {
                     let bytes = val["bytes"]
                         .as_str()
                         .ok_or(de::Error::custom("missing bytes field"))?;
-                    Cell::from_boc_b64(bytes).map_err(de::Error::custom)?
+                    SerializableCell::from_boc_b64(bytes).map_err(de::Error::custom)?
                 };
                 let actual = {
                     SerializableCell::deserialize(val["object"].clone())
@@ -166,7 +229,8 @@ impl<'de> Deserialize<'de> for ResponseStackItem {
                         .map_err(de::Error::custom)?
                 };
 
-                if expected.data() != actual.data() {
+                // The hash covers the referenced cells too.
+                if expected.cell_hash() != actual.cell_hash() {
                     Err(de::Error::custom("cell data mismatch"))?;
                 }
                 match tag.as_str() {
@@ -179,11 +243,55 @@ impl<'de> Deserialize<'de> for ResponseStackItem {
                 let num: String = Deserialize::deserialize(val).map_err(de::Error::custom)?;
                 Ok(ResponseStackItem(StackItem::Num(num)))
             }
+            "tuple" | "list" => {
+                let elements = Self::tvm_elements(&val).map_err(de::Error::custom)?;
+                match tag.as_str() {
+                    "tuple" => Ok(ResponseStackItem(StackItem::Tuple(elements))),
+                    "list" => Ok(ResponseStackItem(StackItem::List(elements))),
+                    &_ => unreachable!(),
+                }
+            }
             other => Err(de::Error::custom(format!("unexpected tag: {other}"))),
         }
     }
 }
 
+impl ResponseStackItem {
+    /// Elements of a `tvm.tuple` or `tvm.list`, which are in the tonlib format:
+    /// `{"@type": "tvm.stackEntryNumber", "number": {"number": "42"}}`, ...
+    fn tvm_elements(value: &serde_json::Value) -> anyhow::Result<Vec<StackItem>> {
+        value["elements"]
+            .as_array()
+            .context("missing elements field")?
+            .iter()
+            .map(Self::tvm_entry)
+            .collect()
+    }
+
+    fn tvm_entry(entry: &serde_json::Value) -> anyhow::Result<StackItem> {
+        let bytes = |field: &str| {
+            entry[field]["bytes"]
+                .as_str()
+                .with_context(|| format!("missing {field}.bytes field"))
+                .and_then(SerializableCell::from_boc_b64)
+        };
+        let item = match entry["@type"].as_str() {
+            Some("tvm.stackEntryNumber") => StackItem::Num(
+                entry["number"]["number"]
+                    .as_str()
+                    .context("missing number.number field")?
+                    .to_string(),
+            ),
+            Some("tvm.stackEntryCell") => StackItem::Cell(bytes("cell")?),
+            Some("tvm.stackEntrySlice") => StackItem::Slice(bytes("slice")?),
+            Some("tvm.stackEntryTuple") => StackItem::Tuple(Self::tvm_elements(&entry["tuple"])?),
+            Some("tvm.stackEntryList") => StackItem::List(Self::tvm_elements(&entry["list"])?),
+            other => anyhow::bail!("unexpected stack entry type: {other:?}"),
+        };
+        Ok(item)
+    }
+}
+
 impl<'de> Deserialize<'de> for StackItem {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -202,6 +310,12 @@ impl<'de> Deserialize<'de> for StackItem {
                     serde_json::from_str(&val).map_err(de::Error::custom)?;
                 Ok(StackItem::Slice(cell))
             }
+            "tvm.Cell" => Ok(StackItem::Cell(
+                SerializableCell::from_boc_b64(&val).map_err(de::Error::custom)?,
+            )),
+            "tvm.Slice" => Ok(StackItem::Slice(
+                SerializableCell::from_boc_b64(&val).map_err(de::Error::custom)?,
+            )),
             "num" => Ok(StackItem::Num(val)),
             other => Err(de::Error::custom(format!("unexpected tag: {other}"))),
         }
@@ -235,8 +349,11 @@ impl StackItem {
 
 #[cfg(test)]
 mod tests {
-    use crate::bridge::ton::{ResponseStackItem, SerializableCell, StackItem, TonInputData};
+    use crate::bridge::ton::{
+        Action, ResponseStackItem, SerializableCell, StackItem, TonInputData, TransactionRef,
+    };
     use serde_json::json;
+    use tonlib_core::cell::{ArcCell, CellBuilder};
 
     #[test]
     fn foo() -> anyhow::Result<()> {
@@ -245,6 +362,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn jetton_transfer_action() -> anyhow::Result<()> {
+        let json = json!({"action": {"JettonTransfer": {
+            "deposit_data": {"amount": "1000", "nonce": "1753218716000000003679"},
+            "transaction": {"lt": "59234106000003", "hash": "k0D5Gg4lmrpn0Wmq7mqT3SBf9UkZ3w0r3pE4cQ2X0yA="},
+        }}});
+        let input = serde_json::from_value::<TonInputData>(json)?;
+        assert!(input.treasury_call_args.is_empty());
+        let Action::JettonTransfer(transfer) = input.action else {
+            panic!("Expected a jetton transfer, got {:?}", input.action);
+        };
+        assert_eq!(transfer.deposit_data.amount, Some(1000));
+        assert_eq!(
+            transfer.transaction,
+            Some(TransactionRef {
+                lt: 59_234_106_000_003,
+                hash: "k0D5Gg4lmrpn0Wmq7mqT3SBf9UkZ3w0r3pE4cQ2X0yA=".to_string(),
+            })
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_serializable_cell() -> anyhow::Result<()> {
         let expected = json!({
@@ -304,6 +443,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cell_with_refs() -> anyhow::Result<()> {
+        let child = CellBuilder::new().store_u32(32, 7)?.build()?;
+        let cell = CellBuilder::new()
+            .store_u8(8, 1)?
+            .store_reference(&ArcCell::new(child))?
+            .build()?;
+        let cell = SerializableCell(ArcCell::new(cell));
+
+        let object = serde_json::to_value(&cell)?;
+        assert_eq!(object["refs"][0]["data"]["len"], 32);
+        let decoded: SerializableCell = serde_json::from_value(object)?;
+        assert_eq!(decoded.cell_hash(), cell.cell_hash());
+
+        let item: StackItem = serde_json::from_value(json!(["tvm.Slice", cell.to_boc_b64()?]))?;
+        assert_eq!(item.as_slice()?.references().len(), 1);
+        assert_eq!(item.as_slice()?.cell_hash(), cell.cell_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn response_tuple() -> anyhow::Result<()> {
+        let json = json!([
+            "tuple",
+            {
+                "@type": "tvm.tuple",
+                "elements": [
+                    {"@type": "tvm.stackEntryNumber", "number": {"@type": "tvm.numberDecimal", "number": "42"}},
+                    {
+                        "@type": "tvm.stackEntryCell",
+                        "cell": {"@type": "tvm.cell", "bytes": "te6cckEBAQEAJAAAQ4AEGCkLSLOCnfYK6iojds4hvltMuYaxlSleiba6z72fPlDajt4V"}
+                    },
+                    {"@type": "tvm.stackEntryList", "list": {"@type": "tvm.list", "elements": []}}
+                ]
+            }
+        ]);
+        let ResponseStackItem(StackItem::Tuple(elements)) = serde_json::from_value(json)? else {
+            anyhow::bail!("expected a tuple");
+        };
+        assert_eq!(elements.len(), 3);
+        assert_eq!(elements[0].as_num()?, "42");
+        assert_eq!(elements[1].as_cell()?.bit_len(), 267);
+        assert_eq!(elements[2], StackItem::List(vec![]));
+        assert!(serde_json::to_value(StackItem::Tuple(elements)).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_stack_item_num() -> anyhow::Result<()> {
         let expected = json!(["num", "-0x1"]);
@@ -0,0 +1,101 @@
+//! Transaction-inclusion auth calls: instead of calling a view method, core checks that
+//! the transaction was executed successfully on the target chain.
+//!
+//! Only the outcome of the transaction is checked, binding it to the signed message
+//! (e.g. by returning its hash for a specific deposit) is up to the auth contract.
+use crate::ChainFamily;
+use serde::{Deserialize, Serialize};
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+pub struct TransactionInputData {
+    pub transaction: TransactionId,
+    /// Blocks that have to be produced on top of the one with the transaction, including it.
+    /// Used on EVM only: Solana transactions are checked at `finalized`, NEAR ones at `FINAL`,
+    /// and Stellar and TON have deterministic finality.
+    #[serde(default)]
+    pub min_confirmations: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Eq, PartialEq, Hash, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionId {
+    /// `0x`-prefixed transaction hash, checked by the receipt status.
+    Evm { hash: String },
+    /// Base58 transaction signature.
+    Solana { signature: String },
+    /// Hex transaction hash.
+    Stellar { hash: String },
+    /// Transaction of `account` with logical time `lt` and base64 `hash`.
+    Ton {
+        account: String,
+        #[serde_as(as = "DisplayFromStr")]
+        #[schemars(with = "String")]
+        lt: u64,
+        hash: String,
+    },
+    /// Base58 transaction hash, NEAR RPC requires the signer to look it up.
+    Near { hash: String, sender_id: String },
+}
+
+impl TransactionId {
+    #[must_use]
+    pub fn family(&self) -> ChainFamily {
+        match self {
+            Self::Evm { .. } => ChainFamily::Evm,
+            Self::Solana { .. } => ChainFamily::Solana,
+            Self::Stellar { .. } => ChainFamily::Stellar,
+            Self::Ton { .. } => ChainFamily::Ton,
+            Self::Near { .. } => ChainFamily::Near,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bridge::transaction::{TransactionId, TransactionInputData};
+    use crate::bridge::{HotVerifyAuthCall, InputData};
+    use crate::{ChainFamily, ChainId};
+
+    #[test]
+    fn transaction_input() {
+        let input: InputData = serde_json::from_str(
+            r#"{"family":"transaction","data":{"transaction":{"ton":{"account":"EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ","lt":"59234106000003","hash":"k0D5Gg4lmrpn0Wmq7mqT3SBf9UkZ3w0r3pE4cQ2X0yA="}}}}"#,
+        )
+        .unwrap();
+        let expected = InputData::Transaction(TransactionInputData {
+            transaction: TransactionId::Ton {
+                account: "EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ".to_string(),
+                lt: 59_234_106_000_003,
+                hash: "k0D5Gg4lmrpn0Wmq7mqT3SBf9UkZ3w0r3pE4cQ2X0yA=".to_string(),
+            },
+            min_confirmations: 0,
+        });
+        assert_eq!(input, expected);
+        assert_eq!(input.family(), Some(ChainFamily::Ton));
+    }
+
+    #[test]
+    fn transaction_family_must_match_chain() {
+        let auth_call = |chain_id| HotVerifyAuthCall {
+            contract_id: String::new(),
+            method: String::new(),
+            chain_id,
+            input: InputData::Transaction(TransactionInputData {
+                transaction: TransactionId::Evm {
+                    hash: "0x00".to_string(),
+                },
+                min_confirmations: 12,
+            }),
+            expectation: None,
+            return_field: None,
+        };
+        assert_eq!(
+            auth_call(ChainId::Evm(56)).input_family().unwrap(),
+            ChainFamily::Evm
+        );
+        assert!(auth_call(ChainId::Solana).input_family().is_err());
+    }
+}
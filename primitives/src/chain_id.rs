@@ -1,6 +1,8 @@
 #![allow(clippy::unreadable_literal)]
+use crate::chain_registry::{ChainFamily, ChainRegistry};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::str::FromStr;
 use strum_macros::EnumIter;
 
 #[derive(
@@ -35,8 +37,22 @@ impl ChainId {
 
     #[must_use]
     pub fn is_cosmos(&self) -> bool {
-        let chain_id: u64 = (*self).into();
-        chain_id.to_string().starts_with("4444")
+        self.family() == Some(ChainFamily::Cosmos)
+    }
+}
+
+/// Accepts either a numeric id or a name/alias from the chain registry (e.g. "base", "bsc").
+impl FromStr for ChainId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(id) = s.parse::<u64>() {
+            return Ok(Self::from(id));
+        }
+        ChainRegistry::global()
+            .by_name(s)
+            .map(crate::chain_registry::ChainInfo::chain_id)
+            .ok_or_else(|| format!("unknown chain: {s}"))
     }
 }
 
@@ -176,48 +192,22 @@ impl TryFrom<ChainId> for ExtendedChainId {
     }
 }
 
+impl FromStr for ExtendedChainId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ChainId::from_str(s).and_then(Self::try_from)
+    }
+}
+
 impl ExtendedChainId {
-    /// A list of chains that can roll-back blocks after they've been transmitted (mem pool -> latest),
-    /// but they are not yet "safe" nor "finalized".
+    /// Whether the chain can roll-back blocks after they've been transmitted (mem pool -> latest),
+    /// but they are not yet "safe" nor "finalized". Described by the chain registry,
+    /// chains missing from it are assumed to reorg.
     #[must_use]
-    pub const fn can_reorg(self) -> bool {
-        match self {
-            // ✅ Probabilistic-finality or L2 rollups
-            Self::Eth
-            | Self::Optimism
-            | Self::Base
-            | Self::Polygon
-            | Self::Arbitrum
-            | Self::ZkSync
-            | Self::Linea
-            | Self::Mantle
-            | Self::MonadMainnet
-            | Self::MonadTestnet
-            | Self::Plasma
-            | Self::MegaEthTestnet
-            | Self::MegaEthMainnet
-            | Self::Flare => true,
-
-            // 🧱 BFT / PoA / deterministic
-            Self::Near
-            | Self::ADI
-            | Self::Bsc
-            | Self::Avax
-            | Self::Kaia
-            | Self::Kava
-            | Self::Aurora
-            | Self::XLayer
-            | Self::BeraChain
-            | Self::Abstract
-            | Self::HyperEVM
-            | Self::Stellar
-            | Self::Ton
-            | Self::Solana
-            | Self::Scroll
-            | Self::Juno
-            | Self::Gonka
-            | Self::Ink => false,
-        }
+    pub fn can_reorg(self) -> bool {
+        self.info()
+            .is_none_or(crate::chain_registry::ChainInfo::can_reorg)
     }
 }
 
@@ -0,0 +1,317 @@
+//! Data-driven description of the supported chains.
+//!
+//! The built-in registry is shipped in `chains.yaml`. It can be extended (or overridden) with
+//! a YAML file of the same format, see [`ChainRegistry::merge_yaml`] and [`ChainRegistry::install`].
+use crate::{ChainId, ExtendedChainId};
+use anyhow::{Context, Result, anyhow, ensure};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const BUILTIN_CHAINS: &str = include_str!("chains.yaml");
+
+static GLOBAL_REGISTRY: OnceLock<ChainRegistry> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainFamily {
+    Near,
+    Evm,
+    Cosmos,
+    Ton,
+    Solana,
+    Stellar,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalityModel {
+    /// BFT / PoA / deterministic: a block can't be rolled back once it's transmitted.
+    Instant,
+    /// Probabilistic-finality or L2 rollups: blocks can be rolled back until they are "safe" or "finalized".
+    Probabilistic,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcDialect {
+    NearJsonRpc,
+    EvmJsonRpc,
+    CosmosLcd,
+    SolanaJsonRpc,
+    StellarRpc,
+    ToncenterV2,
+    /// CometBFT (Tendermint) RPC of a Cosmos chain, for servers with the
+    /// [`RpcDialect::COMETBFT_PREFIX`], see [`RpcDialect::of_server`].
+    CometBftRpc,
+}
+
+impl RpcDialect {
+    /// Prefix of Cosmos servers that speak CometBFT RPC instead of the LCD REST API,
+    /// e.g. `cometbft+https://juno-rpc.polkachu.com`.
+    pub const COMETBFT_PREFIX: &str = "cometbft+";
+
+    /// Dialect of a `server` of a chain with this dialect, and the server URL without
+    /// the dialect prefix. Only Cosmos chains mix dialects.
+    #[must_use]
+    pub fn of_server(self, server: &str) -> (Self, &str) {
+        match server.strip_prefix(Self::COMETBFT_PREFIX) {
+            Some(url) if self == Self::CosmosLcd => (Self::CometBftRpc, url),
+            _ => (self, server),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainInfo {
+    pub id: u64,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    pub family: ChainFamily,
+    pub finality: FinalityModel,
+    pub rpc_dialect: RpcDialect,
+    /// Provider name (e.g. `quicknode`) -> chain slug used in the provider's URLs.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub provider_slugs: HashMap<String, String>,
+}
+
+impl ChainInfo {
+    #[must_use]
+    pub fn chain_id(&self) -> ChainId {
+        ChainId::from(self.id)
+    }
+
+    #[must_use]
+    pub fn can_reorg(&self) -> bool {
+        self.finality == FinalityModel::Probabilistic
+    }
+
+    #[must_use]
+    pub fn provider_slug(&self, provider: &str) -> Option<&str> {
+        self.provider_slugs.get(provider).map(String::as_str)
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ChainRegistry {
+    chains: Vec<ChainInfo>,
+    by_id: HashMap<u64, usize>,
+    by_name: HashMap<String, usize>,
+}
+
+impl ChainRegistry {
+    pub fn new(chains: Vec<ChainInfo>) -> Result<Self> {
+        let mut registry = Self::default();
+        registry.merge(chains)?;
+        Ok(registry)
+    }
+
+    /// The registry shipped with the crate.
+    #[must_use]
+    pub fn builtin() -> Self {
+        Self::from_yaml(BUILTIN_CHAINS).expect("built-in chain registry is valid")
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let chains: Vec<ChainInfo> =
+            serde_yaml::from_str(yaml).context("failed to parse chain registry")?;
+        Self::new(chains)
+    }
+
+    /// Adds chains from a YAML list on top of the current ones.
+    /// A chain with an already known id replaces the existing description.
+    pub fn merge_yaml(&mut self, yaml: &str) -> Result<()> {
+        let chains: Vec<ChainInfo> =
+            serde_yaml::from_str(yaml).context("failed to parse chain registry")?;
+        self.merge(chains)
+    }
+
+    pub fn merge(&mut self, chains: Vec<ChainInfo>) -> Result<()> {
+        for chain in chains {
+            match self.by_id.get(&chain.id) {
+                Some(&index) => self.chains[index] = chain,
+                None => {
+                    self.by_id.insert(chain.id, self.chains.len());
+                    self.chains.push(chain);
+                }
+            }
+        }
+        self.reindex_names()
+    }
+
+    fn reindex_names(&mut self) -> Result<()> {
+        let mut by_name = HashMap::new();
+        for (index, chain) in self.chains.iter().enumerate() {
+            for name in chain.names() {
+                let previous = by_name.insert(name.to_lowercase(), index);
+                ensure!(
+                    previous.is_none_or(|previous| previous == index),
+                    "chain name `{name}` is used by more than one chain"
+                );
+            }
+        }
+        self.by_name = by_name;
+        Ok(())
+    }
+
+    /// Makes `registry` the one returned by [`ChainRegistry::global`].
+    /// Must be called before the first access to the global registry.
+    pub fn install(registry: Self) -> Result<()> {
+        GLOBAL_REGISTRY
+            .set(registry)
+            .map_err(|_| anyhow!("chain registry is already initialized"))
+    }
+
+    /// Installed registry, or the built-in one if nothing was installed.
+    pub fn global() -> &'static Self {
+        GLOBAL_REGISTRY.get_or_init(Self::builtin)
+    }
+
+    pub fn get(&self, chain_id: impl Into<u64>) -> Option<&ChainInfo> {
+        let index = self.by_id.get(&chain_id.into())?;
+        self.chains.get(*index)
+    }
+
+    /// Case-insensitive lookup by name or alias.
+    #[must_use]
+    pub fn by_name(&self, name: &str) -> Option<&ChainInfo> {
+        let index = self.by_name.get(&name.to_lowercase())?;
+        self.chains.get(*index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ChainInfo> {
+        self.chains.iter()
+    }
+}
+
+impl ChainId {
+    #[must_use]
+    pub fn info(&self) -> Option<&'static ChainInfo> {
+        ChainRegistry::global().get(*self)
+    }
+
+    #[must_use]
+    pub fn family(&self) -> Option<ChainFamily> {
+        self.info().map(|info| info.family)
+    }
+}
+
+impl ExtendedChainId {
+    #[must_use]
+    pub fn info(self) -> Option<&'static ChainInfo> {
+        ChainRegistry::global().get(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn builtin_registry_covers_extended_chain_ids() {
+        let registry = ChainRegistry::builtin();
+        for chain_id in ExtendedChainId::iter() {
+            assert!(registry.get(chain_id).is_some(), "{chain_id} is missing");
+        }
+    }
+
+    #[test]
+    fn server_dialect() {
+        let juno = ChainRegistry::builtin()
+            .get(ChainId::Evm(4444_118))
+            .map(|chain| chain.rpc_dialect);
+        assert_eq!(juno, Some(RpcDialect::CosmosLcd));
+        assert_eq!(
+            RpcDialect::CosmosLcd.of_server("cometbft+https://juno-rpc.polkachu.com"),
+            (RpcDialect::CometBftRpc, "https://juno-rpc.polkachu.com")
+        );
+        assert_eq!(
+            RpcDialect::CosmosLcd.of_server("https://juno-api.polkachu.com"),
+            (RpcDialect::CosmosLcd, "https://juno-api.polkachu.com")
+        );
+        assert_eq!(
+            RpcDialect::EvmJsonRpc.of_server("cometbft+https://eth.example"),
+            (RpcDialect::EvmJsonRpc, "cometbft+https://eth.example")
+        );
+    }
+
+    #[test]
+    fn reorg_chains() {
+        let reorg: Vec<_> = ExtendedChainId::iter()
+            .filter(|chain_id| chain_id.can_reorg())
+            .collect();
+        assert!(reorg.contains(&ExtendedChainId::Eth));
+        assert!(reorg.contains(&ExtendedChainId::Base));
+        assert!(!reorg.contains(&ExtendedChainId::Bsc));
+        assert!(!reorg.contains(&ExtendedChainId::Near));
+    }
+
+    #[test]
+    fn chain_id_from_name() {
+        assert_eq!(ChainId::from_str("base").unwrap(), ChainId::Evm(8453));
+        assert_eq!(ChainId::from_str("BSC").unwrap(), ChainId::Evm(56));
+        assert_eq!(ChainId::from_str("bnb").unwrap(), ChainId::Evm(56));
+        assert_eq!(ChainId::from_str("near").unwrap(), ChainId::Near);
+        assert_eq!(ChainId::from_str("ton").unwrap(), ChainId::TON_V2);
+        assert_eq!(ChainId::from_str("1100").unwrap(), ChainId::Stellar);
+        assert!(ChainId::from_str("unknown-chain").is_err());
+
+        assert_eq!(
+            ExtendedChainId::from_str("arbitrum").unwrap(),
+            ExtendedChainId::Arbitrum
+        );
+    }
+
+    #[test]
+    fn merge_yaml_overrides_and_extends() {
+        let mut registry = ChainRegistry::builtin();
+        registry
+            .merge_yaml(
+                r"
+- id: 4444120
+  name: osmosis
+  aliases: [osmo]
+  family: cosmos
+  finality: instant
+  rpc_dialect: cosmos_lcd
+- id: 56
+  name: bsc
+  family: evm
+  finality: probabilistic
+  rpc_dialect: evm_json_rpc
+",
+            )
+            .unwrap();
+
+        let osmosis = registry.by_name("osmo").unwrap();
+        assert_eq!(osmosis.family, ChainFamily::Cosmos);
+        assert!(registry.get(ChainId::Evm(56)).unwrap().can_reorg());
+        // aliases of the replaced description are gone
+        assert!(registry.by_name("bnb").is_none());
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let yaml = r"
+- id: 1
+  name: foo
+  family: evm
+  finality: instant
+  rpc_dialect: evm_json_rpc
+- id: 2
+  name: bar
+  aliases: [foo]
+  family: evm
+  finality: instant
+  rpc_dialect: evm_json_rpc
+";
+        assert!(ChainRegistry::from_yaml(yaml).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Chain-agnostic freshness policy for the state the verifiers read.
+//!
+//! Every verifier maps [`Finality`] to its native parameters:
+//!
+//! | family  | `optimistic`         | `confirmed`                           | `final`                          |
+//! |---------|----------------------|---------------------------------------|----------------------------------|
+//! | NEAR    | `optimistic`         | `near-final`                          | `final`                          |
+//! | EVM     | `latest`             | `latest - 1` on chains that can reorg | `finalized` on chains that reorg |
+//! | Solana  | `processed`          | `confirmed`                           | `finalized`                      |
+//! | Cosmos  | latest height        | latest height                         | latest height                    |
+//! | TON     | last masterchain     | last masterchain                      | last masterchain                 |
+//! | Stellar | latest ledger        | latest ledger                         | latest ledger                    |
+//!
+//! Cosmos, TON and Stellar have deterministic finality, so the level itself doesn't change
+//! anything there. `min_confirmations` additionally moves the queried block back by that many
+//! blocks (seqnos/heights) from the head selected by the level. Solana and Stellar RPCs only
+//! read the state at the head, so `min_confirmations` is rejected for them.
+//!
+//! A minimum age (block timestamp) is not supported: not every family exposes the timestamp
+//! of the queried state, so freshness is expressed in blocks only. Unknown fields, like `min_age`,
+//! are rejected instead of being silently ignored.
+use crate::ChainFamily;
+use anyhow::{Result, ensure};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum FinalityLevel {
+    /// The head the node currently sees, may be rolled back.
+    Optimistic,
+    /// Voted on by the network / buried under a small delay, rollbacks are unlikely.
+    Confirmed,
+    /// Can't be rolled back.
+    Final,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Finality {
+    pub level: FinalityLevel,
+    /// Number of blocks the queried state must be behind the head selected by `level`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_confirmations: Option<u64>,
+}
+
+impl Finality {
+    #[must_use]
+    pub const fn new(level: FinalityLevel) -> Self {
+        Self {
+            level,
+            min_confirmations: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_min_confirmations(mut self, min_confirmations: u64) -> Self {
+        self.min_confirmations = Some(min_confirmations);
+        self
+    }
+
+    /// The policy each family used before it became configurable.
+    #[must_use]
+    pub const fn default_for(family: ChainFamily) -> Self {
+        match family {
+            ChainFamily::Evm | ChainFamily::Solana => Self::new(FinalityLevel::Confirmed),
+            ChainFamily::Near | ChainFamily::Cosmos | ChainFamily::Ton | ChainFamily::Stellar => {
+                Self::new(FinalityLevel::Final)
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn min_confirmations(&self) -> u64 {
+        self.min_confirmations.unwrap_or_default()
+    }
+
+    /// Checks that the verifiers of `family` can honour the policy.
+    pub fn ensure_supported_by(&self, family: ChainFamily) -> Result<()> {
+        ensure!(
+            self.min_confirmations.is_none()
+                || !matches!(family, ChainFamily::Solana | ChainFamily::Stellar),
+            "min_confirmations is not supported by {family:?}, its RPC only reads the head state"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finality_serde() {
+        let finality: Finality = serde_json::from_str(r#"{"level":"final"}"#).unwrap();
+        assert_eq!(finality, Finality::new(FinalityLevel::Final));
+
+        let finality: Finality =
+            serde_json::from_str(r#"{"level":"confirmed","min_confirmations":3}"#).unwrap();
+        assert_eq!(
+            finality,
+            Finality::new(FinalityLevel::Confirmed).with_min_confirmations(3)
+        );
+        assert_eq!(
+            serde_json::to_string(&Finality::new(FinalityLevel::Optimistic)).unwrap(),
+            r#"{"level":"optimistic"}"#
+        );
+    }
+
+    #[test]
+    fn min_confirmations_need_past_state() {
+        let finality = Finality::new(FinalityLevel::Final).with_min_confirmations(3);
+        assert!(finality.ensure_supported_by(ChainFamily::Solana).is_err());
+        assert!(finality.ensure_supported_by(ChainFamily::Stellar).is_err());
+        assert!(finality.ensure_supported_by(ChainFamily::Evm).is_ok());
+        assert!(finality.ensure_supported_by(ChainFamily::Ton).is_ok());
+        assert!(
+            Finality::new(FinalityLevel::Final)
+                .ensure_supported_by(ChainFamily::Solana)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn min_age_is_not_supported() {
+        assert!(serde_json::from_str::<Finality>(r#"{"level":"final","min_age":60}"#).is_err());
+    }
+
+    #[test]
+    fn defaults_keep_previous_behaviour() {
+        assert_eq!(
+            Finality::default_for(ChainFamily::Near).level,
+            FinalityLevel::Final
+        );
+        assert_eq!(
+            Finality::default_for(ChainFamily::Evm).level,
+            FinalityLevel::Confirmed
+        );
+        assert_eq!(
+            Finality::default_for(ChainFamily::Solana).level,
+            FinalityLevel::Confirmed
+        );
+        assert_eq!(
+            Finality::default_for(ChainFamily::Cosmos).min_confirmations(),
+            0
+        );
+    }
+}
@@ -1,7 +1,11 @@
 pub mod base58_wrapper;
 pub mod bridge;
 pub mod chain_id;
+pub mod chain_registry;
+pub mod finality;
 pub mod integer;
+pub mod network;
+pub mod payload;
 pub mod validation;
 
 mod hex_wrapper;
@@ -11,4 +15,8 @@ pub mod uid;
 
 pub use base58_wrapper::*;
 pub use chain_id::*;
+pub use chain_registry::*;
+pub use finality::*;
+pub use network::*;
+pub use payload::*;
 pub use validation::*;
@@ -0,0 +1,241 @@
+//! Network profiles: everything that differs between running the stack against mainnet,
+//! testnet or a local sandbox (registry contracts, bridge contract, network passphrases).
+use crate::ChainId;
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use strum_macros::{Display, EnumString};
+
+pub const STELLAR_PUBLIC_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+pub const STELLAR_TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+pub const STELLAR_FUTURENET_PASSPHRASE: &str = "Test SDF Future Network ; October 2022";
+
+/// The network the whole stack is pointed at.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Display, EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+/// Contracts and network identities that depend on the selected network.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkProfile {
+    pub network: Network,
+    /// NEAR RPC used to send transactions to the registry (e.g. `create_wallet`).
+    pub near_rpc: String,
+    /// NEAR contract that stores wallets and their auth methods (`get_wallet`).
+    pub near_registry_contract: String,
+    /// NEAR contract of the omni bridge, source of truth for withdrawals (`get_transfer`).
+    pub omni_bridge_contract: String,
+    /// Network passphrase used when building Soroban transactions, e.g.
+    /// [`STELLAR_FUTURENET_PASSPHRASE`] for a futurenet deployment.
+    pub stellar_network_passphrase: String,
+    /// Bridge contracts on the destination chains, queried for already executed withdrawal nonces.
+    /// Nonces of withdrawals to chains without an entry are not checked,
+    /// unless the chain is listed in `strict_nonce_chains`.
+    #[serde(default)]
+    pub bridge_contracts: HashMap<ChainId, String>,
+    /// Destination chains whose withdrawals are refused when no bridge contract is configured
+    /// to check their nonces against.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub strict_nonce_chains: HashSet<ChainId>,
+    /// Anchor IDL files of Solana programs by program id, used to decode their accounts
+    /// in account field auth calls.
+    #[serde(default)]
+    pub solana_idls: HashMap<String, PathBuf>,
+}
+
+impl NetworkProfile {
+    #[must_use]
+    pub fn mainnet() -> Self {
+        let bridge_contracts = HashMap::from([
+            (
+                ChainId::Evm(56),
+                "0x233c5370CCfb3cD7409d9A3fb98ab94dE94Cb4Cd".to_string(),
+            ),
+            (
+                ChainId::Evm(4444_118),
+                "juno1va9q7gma6l62aqq988gghv4r7u4hnlgm85ssmsdf9ypw77qfwa0qaz7ea4".to_string(),
+            ),
+            (
+                ChainId::Solana,
+                "8sXzdKW2jFj7V5heRwPMcygzNH3JZnmie5ZRuNoTuKQC".to_string(),
+            ),
+            (
+                ChainId::Stellar,
+                "CCLWL5NYSV2WJQ3VBU44AMDHEVKEPA45N2QP2LL62O3JVKPGWWAQUVAG".to_string(),
+            ),
+            (
+                ChainId::TON_V2,
+                "EQANEViM3AKQzi6Aj3sEeyqFu8pXqhy9Q9xGoId_0qp3CNVJ".to_string(),
+            ),
+        ]);
+        Self {
+            network: Network::Mainnet,
+            near_rpc: "https://rpc.mainnet.near.org".to_string(),
+            near_registry_contract: "mpc.hot.tg".to_string(),
+            omni_bridge_contract: "v2_1.omni.hot.tg".to_string(),
+            stellar_network_passphrase: STELLAR_PUBLIC_PASSPHRASE.to_string(),
+            // The chains whose bridge contracts are known can't silently lose the check.
+            strict_nonce_chains: bridge_contracts.keys().copied().collect(),
+            bridge_contracts,
+            solana_idls: HashMap::new(),
+        }
+    }
+
+    /// NEAR testnet RPC and Stellar testnet passphrase. There's no public testnet
+    /// deployment of the registry and omni bridge, so their contracts are left empty
+    /// and must come from a profile file, see [`Self::ensure_contracts`].
+    #[must_use]
+    pub fn testnet() -> Self {
+        Self {
+            network: Network::Testnet,
+            near_rpc: "https://rpc.testnet.near.org".to_string(),
+            near_registry_contract: String::new(),
+            omni_bridge_contract: String::new(),
+            stellar_network_passphrase: STELLAR_TESTNET_PASSPHRASE.to_string(),
+            bridge_contracts: HashMap::new(),
+            strict_nonce_chains: HashSet::new(),
+            solana_idls: HashMap::new(),
+        }
+    }
+
+    /// Fails if the registry or omni bridge contract isn't set, e.g. for [`Self::testnet`].
+    pub fn ensure_contracts(&self) -> Result<()> {
+        if self.near_registry_contract.is_empty() || self.omni_bridge_contract.is_empty() {
+            bail!(
+                "No registry or omni bridge contract in the {} network profile",
+                self.network
+            );
+        }
+        Ok(())
+    }
+
+    /// Bridge contract to query for executed withdrawal nonces on `chain_id`,
+    /// `None` if there's none and the chain isn't in `strict_nonce_chains`.
+    pub fn nonce_check_contract(&self, chain_id: ChainId) -> Result<Option<&str>> {
+        let contract = self.bridge_contracts.get(&chain_id).map(String::as_str);
+        if contract.is_none() && self.strict_nonce_chains.contains(&chain_id) {
+            bail!(
+                "No bridge contract configured for {chain_id} to check withdrawal nonces against"
+            );
+        }
+        Ok(contract)
+    }
+}
+
+impl Default for NetworkProfile {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+impl From<Network> for NetworkProfile {
+    fn from(network: Network) -> Self {
+        match network {
+            Network::Mainnet => Self::mainnet(),
+            Network::Testnet => Self::testnet(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn network_from_str() {
+        assert_eq!(Network::from_str("mainnet").unwrap(), Network::Mainnet);
+        assert_eq!(Network::from_str("testnet").unwrap(), Network::Testnet);
+        assert!(Network::from_str("devnet").is_err());
+    }
+
+    #[test]
+    fn builtin_profiles_match_network() {
+        assert_eq!(
+            NetworkProfile::from(Network::Mainnet).network,
+            Network::Mainnet
+        );
+        assert_eq!(
+            NetworkProfile::from(Network::Testnet).network,
+            Network::Testnet
+        );
+        assert_eq!(NetworkProfile::default(), NetworkProfile::mainnet());
+    }
+
+    #[test]
+    fn testnet_profile_has_no_contracts() {
+        let profile = NetworkProfile::testnet();
+        assert!(profile.near_registry_contract.is_empty());
+        assert!(profile.omni_bridge_contract.is_empty());
+        assert!(profile.bridge_contracts.is_empty());
+        assert!(profile.ensure_contracts().is_err());
+        assert!(NetworkProfile::mainnet().ensure_contracts().is_ok());
+    }
+
+    #[test]
+    fn profile_json_roundtrip() {
+        let json = r#"{
+            "network": "testnet",
+            "near_rpc": "http://localhost:3030",
+            "near_registry_contract": "mpc.test.near",
+            "omni_bridge_contract": "omni.test.near",
+            "stellar_network_passphrase": "Standalone Network ; February 2017"
+        }"#;
+        let profile: NetworkProfile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile.network, Network::Testnet);
+        assert_eq!(profile.near_registry_contract, "mpc.test.near");
+        assert!(profile.bridge_contracts.is_empty());
+
+        let json = r#"{
+            "network": "mainnet",
+            "near_rpc": "http://localhost:3030",
+            "near_registry_contract": "mpc.test.near",
+            "omni_bridge_contract": "omni.test.near",
+            "stellar_network_passphrase": "Standalone Network ; February 2017",
+            "bridge_contracts": {"56": "0x233c5370CCfb3cD7409d9A3fb98ab94dE94Cb4Cd"}
+        }"#;
+        let profile: NetworkProfile = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            profile.bridge_contracts[&ChainId::Evm(56)],
+            "0x233c5370CCfb3cD7409d9A3fb98ab94dE94Cb4Cd"
+        );
+    }
+
+    #[test]
+    fn nonce_check_is_strict_per_chain() {
+        let mut profile = NetworkProfile::mainnet();
+        assert_eq!(
+            profile.nonce_check_contract(ChainId::Evm(56)).unwrap(),
+            Some("0x233c5370CCfb3cD7409d9A3fb98ab94dE94Cb4Cd")
+        );
+        assert!(
+            profile
+                .nonce_check_contract(ChainId::Evm(4444_118))
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            profile.nonce_check_contract(ChainId::Evm(8453)).unwrap(),
+            None
+        );
+        assert_eq!(
+            NetworkProfile::testnet()
+                .nonce_check_contract(ChainId::Evm(56))
+                .unwrap(),
+            None
+        );
+
+        profile.strict_nonce_chains.insert(ChainId::Evm(8453));
+        assert!(profile.nonce_check_contract(ChainId::Evm(8453)).is_err());
+        profile.bridge_contracts.remove(&ChainId::Evm(56));
+        assert!(profile.nonce_check_contract(ChainId::Evm(56)).is_err());
+    }
+}
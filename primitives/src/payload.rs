@@ -0,0 +1,285 @@
+//! Typed user payloads of a [`ProofModel`](crate::ProofModel).
+//!
+//! Auth contracts on NEAR receive a payload as a string argument of `hot_verify`, so on the wire
+//! [`UserPayload`] is still a string. Decoded payloads keep the string they were decoded from and
+//! are forwarded verbatim, payloads built by the proxy are encoded as compact JSON, the same text
+//! it used to produce from `serde_json::Value`s.
+use crate::bridge::HotVerifyBridge;
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaGenerator;
+use schemars::schema::{
+    InstanceType, Metadata, Schema, SchemaObject, StringValidation, SubschemaValidation,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// Payload of the signature-based auth contracts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AuthSignatures {
+    /// Index of the auth method in the wallet access list.
+    pub auth_method: u64,
+    pub signatures: Vec<String>,
+}
+
+impl AuthSignatures {
+    const KEYS: [&str; 2] = ["auth_method", "signatures"];
+}
+
+/// A decoded payload along with the exact string it was decoded from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Verbatim<T> {
+    value: T,
+    raw: String,
+}
+
+impl<T> Verbatim<T> {
+    fn new(value: T, raw: &str) -> Self {
+        Self {
+            value,
+            raw: raw.to_string(),
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+/// Encodes `value` as compact JSON.
+impl<T: Serialize> From<T> for Verbatim<T> {
+    fn from(value: T) -> Self {
+        let raw = serde_json::to_value(&value)
+            .expect("Payload types are always representable as JSON")
+            .to_string();
+        Self { value, raw }
+    }
+}
+
+impl<T> Deref for Verbatim<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// One element of [`ProofModel::user_payloads`](crate::ProofModel::user_payloads).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum UserPayload {
+    AuthSignatures(Verbatim<AuthSignatures>),
+    Bridge(Verbatim<HotVerifyBridge>),
+    /// Omni bridge nonce of a withdrawal, encoded as a decimal string.
+    WithdrawNonce(u128),
+    /// Payload of an auth contract we don't know the format of, passed through verbatim.
+    Opaque(String),
+}
+
+impl UserPayload {
+    /// The bridge action carried by the payload, if any.
+    #[must_use]
+    pub fn as_bridge(&self) -> Option<HotVerifyBridge> {
+        match self {
+            Self::Bridge(bridge) => Some(HotVerifyBridge::clone(bridge)),
+            _ => None,
+        }
+    }
+
+    /// The payload as it's passed to the auth contract: the string it was decoded from.
+    #[must_use]
+    pub fn encode(&self) -> String {
+        match self {
+            Self::AuthSignatures(signatures) => signatures.as_str().to_string(),
+            Self::Bridge(bridge) => bridge.as_str().to_string(),
+            Self::WithdrawNonce(nonce) => nonce.to_string(),
+            Self::Opaque(payload) => payload.clone(),
+        }
+    }
+
+    fn as_withdraw_nonce(payload: &str) -> Option<u128> {
+        let nonce = payload.parse::<u128>().ok()?;
+        // Only the canonical form, so that decoding and encoding back doesn't change the payload.
+        (nonce.to_string() == payload).then_some(nonce)
+    }
+}
+
+impl From<HotVerifyBridge> for UserPayload {
+    fn from(bridge: HotVerifyBridge) -> Self {
+        Self::Bridge(bridge.into())
+    }
+}
+
+impl FromStr for UserPayload {
+    type Err = anyhow::Error;
+
+    /// Fails on payloads that look like a known format, but don't match it.
+    fn from_str(payload: &str) -> Result<Self> {
+        if let Some(bridge) = HotVerifyBridge::from_user_payload(payload)? {
+            return Ok(Self::Bridge(Verbatim::new(bridge, payload)));
+        }
+        if let Ok(serde_json::Value::Object(object)) = serde_json::from_str(payload) {
+            if object.contains_key("auth_method")
+                && object
+                    .keys()
+                    .all(|key| AuthSignatures::KEYS.contains(&key.as_str()))
+            {
+                let signatures = serde_json::from_value(serde_json::Value::Object(object))
+                    .context("Malformed auth signatures payload")?;
+                return Ok(Self::AuthSignatures(Verbatim::new(signatures, payload)));
+            }
+        }
+        if let Some(nonce) = Self::as_withdraw_nonce(payload) {
+            return Ok(Self::WithdrawNonce(nonce));
+        }
+        Ok(Self::Opaque(payload.to_string()))
+    }
+}
+
+impl TryFrom<String> for UserPayload {
+    type Error = anyhow::Error;
+
+    fn try_from(payload: String) -> Result<Self> {
+        payload.parse()
+    }
+}
+
+impl From<UserPayload> for String {
+    fn from(payload: UserPayload) -> Self {
+        payload.encode()
+    }
+}
+
+impl Display for UserPayload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+/// Schema of the JSON document a payload string carries.
+impl JsonSchema for UserPayload {
+    fn schema_name() -> String {
+        "UserPayload".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let string = |description: &str, pattern: Option<&str>| -> Schema {
+            SchemaObject {
+                metadata: Some(Box::new(Metadata {
+                    description: Some(description.to_string()),
+                    ..Metadata::default()
+                })),
+                instance_type: Some(InstanceType::String.into()),
+                string: pattern.map(|pattern| {
+                    Box::new(StringValidation {
+                        pattern: Some(pattern.to_string()),
+                        ..StringValidation::default()
+                    })
+                }),
+                ..SchemaObject::default()
+            }
+            .into()
+        };
+
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    generator.subschema_for::<AuthSignatures>(),
+                    generator.subschema_for::<HotVerifyBridge>(),
+                    string("Omni bridge withdrawal nonce", Some("^(0|[1-9][0-9]*)$")),
+                    string("Payload of an unknown auth contract", None),
+                ]),
+                ..SubschemaValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChainId;
+    use crate::bridge::{CompletedWithdrawal, CompletedWithdrawalAction};
+
+    const AUTH_PAYLOAD: &str = r#"{"auth_method":0,"signatures":["HZUhhJamfp8GJLL8gEa2F2qZ6TXPu4PYzzWkDqsTQsMcW9rQsG2Hof4eD2Vex6he2fVVy3UNhgi631CY8E9StAH"]}"#;
+
+    #[test]
+    fn payloads_roundtrip_to_the_same_wire_string() {
+        let payloads = [
+            AUTH_PAYLOAD,
+            r#"{"ClearCompletedWithdrawal":{"chain_id":1100,"nonce":"42"}}"#,
+            "1749390032000000032243",
+            "007",
+            r#"{"auth_method":0,"signatures":[],"extra":true}"#,
+            r#"{"signatures": [], "auth_method": 0}"#,
+            r#"{ "ClearCompletedWithdrawal": {"nonce": "42", "chain_id": 1100} }"#,
+            "arbitrary",
+        ];
+        for payload in payloads {
+            let decoded: UserPayload = payload.parse().unwrap();
+            assert_eq!(decoded.encode(), payload);
+        }
+    }
+
+    #[test]
+    fn payloads_are_recognized() {
+        let decoded: UserPayload = AUTH_PAYLOAD.parse().unwrap();
+        assert!(matches!(decoded, UserPayload::AuthSignatures(ref s) if s.auth_method == 0));
+
+        let decoded: UserPayload = "42".parse().unwrap();
+        assert_eq!(decoded, UserPayload::WithdrawNonce(42));
+        assert_eq!(
+            "007".parse::<UserPayload>().unwrap(),
+            UserPayload::Opaque("007".to_string())
+        );
+
+        let clear = HotVerifyBridge::ClearCompletedWithdrawal(CompletedWithdrawalAction {
+            chain_id: ChainId::Stellar,
+            data: CompletedWithdrawal {
+                nonce: 42,
+                receiver_address: None,
+            },
+        });
+        let decoded: UserPayload = serde_json::to_string(&clear).unwrap().parse().unwrap();
+        assert_eq!(decoded.as_bridge(), Some(clear));
+    }
+
+    #[test]
+    fn non_canonical_payloads_are_classified_but_kept_verbatim() {
+        let payload = r#"{"signatures": [], "auth_method": 0}"#;
+        let decoded: UserPayload = payload.parse().unwrap();
+        assert!(matches!(decoded, UserPayload::AuthSignatures(ref s) if s.signatures.is_empty()));
+        assert_eq!(decoded.to_string(), payload);
+
+        let payload = r#"{"ClearCompletedWithdrawal": {"chain_id": 1100, "nonce": "42"}}"#;
+        let decoded: UserPayload = payload.parse().unwrap();
+        assert!(matches!(
+            decoded.as_bridge(),
+            Some(HotVerifyBridge::ClearCompletedWithdrawal(ref action)) if action.data.nonce == 42
+        ));
+        assert_eq!(decoded.encode(), payload);
+    }
+
+    #[test]
+    fn malformed_payloads_are_rejected() {
+        assert!(r#"{"auth_method":"zero","signatures":[]}"#.parse::<UserPayload>().is_err());
+        assert!(r#"{"Deposit":{"chain_id":56}}"#.parse::<UserPayload>().is_err());
+    }
+
+    #[test]
+    fn serde_uses_the_wire_string() {
+        let payloads: Vec<UserPayload> =
+            serde_json::from_str(&serde_json::to_string(&[AUTH_PAYLOAD, "42"]).unwrap()).unwrap();
+        assert_eq!(payloads[1], UserPayload::WithdrawNonce(42));
+        assert_eq!(
+            serde_json::to_value(&payloads).unwrap(),
+            serde_json::json!([AUTH_PAYLOAD, "42"])
+        );
+    }
+}
@@ -1,14 +1,15 @@
-use crate::ChainId;
+use crate::{ChainFamily, ChainId, Finality, UserPayload};
 use derive_more::{Deref, DerefMut, Into};
 use serde::{Deserialize, Serialize};
 use serde_valid::Validate;
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Collection of arguments for each auth method.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
 pub struct ProofModel {
     pub message_body: String,
-    pub user_payloads: Vec<String>,
+    pub user_payloads: Vec<UserPayload>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Into, Deref, DerefMut)]
@@ -17,12 +18,51 @@ pub struct ValidationConfig(pub HashMap<ChainId, ChainValidationConfig>);
 /// For a specific chain:
 /// * `threshold` is the number of servers that need to give the same response to be able to accept it
 /// * `servers` is the available RPCs
+/// * `finality` is how fresh the state read from `servers` may be,
+///   [`Finality::default_for`] the chain family if omitted, only a level and a block count
+///   are supported (no minimum age)
+/// * `max_log_block_range` is the widest `to_block - from_block` of an EVM log query,
+///   [`DEFAULT_MAX_LOG_BLOCK_RANGE`] if omitted
+/// * `head_poll_interval_ms` is how often the heads of `servers` are polled in the background,
+///   [`DEFAULT_HEAD_POLL_INTERVAL`] if omitted
 #[derive(Clone, Debug, Serialize, Deserialize, Validate)]
 #[validate(custom = validate_chain_config)]
 pub struct ChainValidationConfig {
     pub threshold: usize,
     #[validate(unique_items)]
     pub servers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finality: Option<Finality>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_log_block_range: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_poll_interval_ms: Option<u64>,
+}
+
+/// Block range most RPC providers serve in a single `eth_getLogs` request.
+pub const DEFAULT_MAX_LOG_BLOCK_RANGE: u64 = 10_000;
+
+pub const DEFAULT_HEAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+impl ChainValidationConfig {
+    /// Configured finality, or the default one for `family`.
+    #[must_use]
+    pub fn finality_or_default(&self, family: ChainFamily) -> Finality {
+        self.finality
+            .unwrap_or_else(|| Finality::default_for(family))
+    }
+
+    /// Configured EVM log query range, or [`DEFAULT_MAX_LOG_BLOCK_RANGE`].
+    #[must_use]
+    pub fn max_log_block_range_or_default(&self) -> u64 {
+        self.max_log_block_range.unwrap_or(DEFAULT_MAX_LOG_BLOCK_RANGE)
+    }
+
+    /// Configured head polling interval, or [`DEFAULT_HEAD_POLL_INTERVAL`].
+    #[must_use]
+    pub fn head_poll_interval(&self) -> Duration {
+        self.head_poll_interval_ms.map_or(DEFAULT_HEAD_POLL_INTERVAL, Duration::from_millis)
+    }
 }
 
 fn validate_chain_config(
@@ -50,6 +90,11 @@ fn validate_chain_config(
             cfg.servers.len()
         )));
     }
+    if cfg.head_poll_interval_ms == Some(0) {
+        return Err(serde_valid::validation::Error::Custom(
+            "head_poll_interval_ms must be >= 1".to_string(),
+        ));
+    }
     for server in &cfg.servers {
         if !server.starts_with("https://") {
             return Err(serde_valid::validation::Error::Custom(format!(
@@ -68,6 +113,9 @@ mod tests {
         ChainValidationConfig {
             threshold,
             servers: servers.iter().map(|s| (*s).to_string()).collect(),
+            finality: None,
+            head_poll_interval_ms: None,
+            max_log_block_range: None,
         }
     }
 
@@ -99,6 +147,16 @@ mod tests {
         assert!(validate_chain_config(&cfg(3, &["https://a", "https://b", "https://c", "https://d"])).is_ok());
     }
 
+    #[test]
+    fn rejects_zero_head_poll_interval() {
+        let mut config = cfg(1, &["https://a"]);
+        config.head_poll_interval_ms = Some(0);
+        assert!(validate_chain_config(&config).is_err());
+        config.head_poll_interval_ms = Some(500);
+        assert!(validate_chain_config(&config).is_ok());
+        assert_eq!(config.head_poll_interval(), Duration::from_millis(500));
+    }
+
     #[test]
     fn rejects_plaintext_endpoint() {
         assert!(validate_chain_config(&cfg(1, &["http://a"])).is_err());
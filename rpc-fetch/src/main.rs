@@ -115,6 +115,9 @@ async fn main() -> Result<()> {
             let validation_config = ChainValidationConfig {
                 threshold,
                 servers: endpoints.clone(),
+                finality: None,
+                head_poll_interval_ms: None,
+                max_log_block_range: None,
             };
             data.insert(chain_id, validation_config);
         }
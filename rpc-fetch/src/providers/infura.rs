@@ -15,31 +15,7 @@ impl InfuraProvider {
 }
 
 impl SlugFromChainId for InfuraProvider {
-    fn slug(chain_id: ExtendedChainId) -> Option<String> {
-        use ExtendedChainId::{
-            ADI, Abstract, Arbitrum, Aurora, Avax, Base, BeraChain, Bsc, Eth, Flare, Gonka,
-            HyperEVM, Ink, Juno, Kaia, Kava, Linea, Mantle, MegaEthMainnet, MonadMainnet,
-            MonadTestnet, Near, Optimism, Polygon, Scroll, Solana, Stellar, Ton, XLayer, ZkSync,
-        };
-        match chain_id {
-            Eth => Some("mainnet".to_string()),
-            Optimism => Some("optimism-mainnet".to_string()),
-            Bsc => Some("bsc-mainnet".to_string()),
-            Polygon => Some("polygon-mainnet".to_string()),
-            ZkSync => Some("zksync-mainnet".to_string()),
-            Base => Some("base-mainnet".to_string()),
-            Arbitrum => Some("arbitrum-mainnet".to_string()),
-            Avax => Some("avalanche-mainnet".to_string()),
-            Scroll => Some("scroll-mainnet".to_string()),
-            Linea => Some("linea-mainnet".to_string()),
-            Mantle => Some("mantle-mainnet".to_string()),
-
-            ADI | Juno | Gonka | Near | Abstract | Ink | Flare | HyperEVM | MonadMainnet
-            | MonadTestnet | XLayer | Stellar | Kava | BeraChain | Aurora | Solana | Kaia | Ton
-            | MegaEthMainnet => None,
-            _ => None, // TODO: We don't support infura anymore.
-        }
-    }
+    const PROVIDER: &'static str = "infura";
 }
 
 #[async_trait]
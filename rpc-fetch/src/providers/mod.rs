@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use hot_validation_primitives::ExtendedChainId;
+use hot_validation_primitives::{ChainRegistry, ExtendedChainId};
 use std::collections::HashMap;
 
 pub mod alchemy;
@@ -13,5 +13,13 @@ pub trait Provider {
 }
 
 pub trait SlugFromChainId {
-    fn slug(chain_id: ExtendedChainId) -> Option<String>;
+    /// Provider key in `provider_slugs` of the chain registry.
+    const PROVIDER: &'static str;
+
+    fn slug(chain_id: ExtendedChainId) -> Option<String> {
+        ChainRegistry::global()
+            .get(chain_id)?
+            .provider_slug(Self::PROVIDER)
+            .map(ToString::to_string)
+    }
 }
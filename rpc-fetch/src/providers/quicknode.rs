@@ -17,50 +17,7 @@ impl QuicknodeProvider {
 }
 
 impl SlugFromChainId for QuicknodeProvider {
-    fn slug(chain_id: ExtendedChainId) -> Option<String> {
-        use ExtendedChainId::{
-            ADI, Abstract, Arbitrum, Aurora, Avax, Base, BeraChain, Bsc, Eth, Flare, Gonka,
-            HyperEVM, Ink, Juno, Kaia, Kava, Linea, Mantle, MegaEthMainnet, MegaEthTestnet,
-            MonadMainnet, MonadTestnet, Near, Optimism, Plasma, Polygon, Scroll, Solana, Stellar,
-            Ton, XLayer, ZkSync,
-        };
-        match chain_id {
-            Optimism => Some("optimism".to_string()),
-            Bsc => Some("bsc".to_string()),
-            Polygon => Some("matic".to_string()),
-            MonadMainnet => Some("monad-mainnet".to_string()),
-            MonadTestnet => Some("monad-testnet".to_string()),
-            ZkSync => Some("zksync-mainnet".to_string()),
-            Stellar => Some("stellar-mainnet".to_string()),
-            Base => Some("base-mainnet".to_string()),
-            Arbitrum => Some("arbitrum-mainnet".to_string()),
-            Avax => Some("avalanche-mainnet".to_string()),
-            BeraChain => Some("bera-mainnet".to_string()),
-            Scroll => Some("scroll-mainnet".to_string()),
-            Ton => Some("ton-mainnet".to_string()),
-            Solana => Some("solana-mainnet".to_string()),
-            XLayer => Some("xlayer-mainnet".to_string()),
-            HyperEVM => Some("hype-mainnet".to_string()),
-            Linea => Some("linea-mainnet".to_string()),
-            Kaia => Some("kaia-mainnet".to_string()),
-            Mantle => Some("mantle-mainnet".to_string()),
-            Abstract => Some("abstract-mainnet".to_string()),
-            Ink => Some("ink-mainnet".to_string()),
-            Flare => Some("flare-mainnet".to_string()),
-            Plasma => Some("plasma-mainnet".to_string()),
-            MegaEthMainnet => Some("megaeth-mainnet".to_string()),
-
-            Near | // it's "near-mainnet", but the load is too high, so we don't add it automatically, 
-                   // rather we supply custom endpoints for it
-            Eth | // has to return base endpoint
-            ADI |
-            Kava |
-            Juno |
-            MegaEthTestnet |
-            Gonka |
-            Aurora => None,
-        }
-    }
+    const PROVIDER: &'static str = "quicknode";
 }
 
 #[derive(Debug, Deserialize)]
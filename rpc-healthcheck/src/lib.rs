@@ -2,17 +2,17 @@ pub mod observer;
 
 use anyhow::Result;
 use futures_util::{StreamExt, stream};
-use hot_validation_primitives::ChainId;
-use reqwest::{Client, Url};
+use hot_validation_primitives::{ChainId, ChainRegistry, RpcDialect};
+use reqwest::{Client, RequestBuilder, Url};
 use serde_json::json;
 use std::time::Duration;
 
 const MAX_CONCURRENT_REQUESTS: usize = 5;
 const TIMEOUT_DURATION: Duration = Duration::from_secs(5);
 
-fn build_payload(chain_id: ChainId) -> serde_json::Value {
-    match chain_id {
-        ChainId::Near => {
+fn build_payload(dialect: Option<RpcDialect>) -> serde_json::Value {
+    match dialect {
+        Some(RpcDialect::NearJsonRpc) => {
             json!({
                 "jsonrpc": "2.0",
                 "method": "block",
@@ -20,27 +20,37 @@ fn build_payload(chain_id: ChainId) -> serde_json::Value {
                 "id": 1,
             })
         }
-        ChainId::Stellar => {
+        Some(RpcDialect::StellarRpc) => {
             json!({
                 "jsonrpc": "2.0",
                 "id": 1,
                 "method": "getLatestLedger"
             })
         }
-        ChainId::Ton | ChainId::TON_V2 => json!({
+        Some(RpcDialect::ToncenterV2) => json!({
             "method": "getMasterchainInfo",
             "params": {},
             "id": "dontcare",
             "jsonrpc": "2.0"
         }),
-        ChainId::Solana => {
+        Some(RpcDialect::SolanaJsonRpc) => {
             json!({
               "jsonrpc":"2.0",
               "id":1,
               "method":"getBlockHeight"
             })
         }
-        ChainId::Evm(_) => {
+        // LCD servers are checked with a GET, see `build_request`.
+        Some(RpcDialect::CometBftRpc | RpcDialect::CosmosLcd) => {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "status",
+                "params": {}
+            })
+        }
+        // Unknown chains are assumed to be EVM.
+        Some(RpcDialect::EvmJsonRpc) | None => {
             json!({
                 "jsonrpc": "2.0",
                 "method": "eth_blockNumber",
@@ -51,6 +61,20 @@ fn build_payload(chain_id: ChainId) -> serde_json::Value {
     }
 }
 
+/// Request of the latest block from `server`, in the dialect the server speaks.
+fn build_request(client: &Client, dialect: Option<RpcDialect>, server: &str) -> RequestBuilder {
+    let Some(dialect) = dialect else {
+        return client.post(server).json(&build_payload(None));
+    };
+    match dialect.of_server(server) {
+        (RpcDialect::CosmosLcd, url) => client.get(format!(
+            "{}/cosmos/base/tendermint/v1beta1/blocks/latest",
+            url.trim_end_matches('/')
+        )),
+        (dialect, url) => client.post(url).json(&build_payload(Some(dialect))),
+    }
+}
+
 /// An RPC url with removed secret
 #[derive(Debug, Clone)]
 pub struct SafeUrl(String);
@@ -91,16 +115,15 @@ pub async fn healthcheck_many(
     chain_id: ChainId,
     servers: &[String],
 ) -> Vec<Result<SafeUrl, FailedServer>> {
-    let payload = build_payload(chain_id);
+    let dialect = ChainRegistry::global()
+        .get(chain_id)
+        .map(|chain| chain.rpc_dialect);
 
     let results = stream::iter(servers.iter().cloned())
         .map(|server| {
-            let client = client.clone();
-            let payload = payload.clone();
+            let request = build_request(client, dialect, &server);
             async move {
-                client
-                    .post(&server)
-                    .json(&payload)
+                request
                     .timeout(TIMEOUT_DURATION)
                     .send()
                     .await
@@ -177,6 +200,18 @@ mod tests {
         assert_any_ok(results)
     }
 
+    #[tokio::test]
+    async fn cosmos_healthcheck_many() -> Result<()> {
+        let client = Client::new();
+        let servers = vec![
+            "https://juno-api.stakeandrelax.net".to_string(),
+            "cometbft+https://juno-rpc.polkachu.com".to_string(),
+        ];
+        let results = healthcheck_many(&client, ChainId::Evm(4444_118), &servers).await;
+        assert!(results.iter().all(Result::is_ok), "{results:?}");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn solana_healthcheck_many() -> Result<()> {
         let client = Client::new();